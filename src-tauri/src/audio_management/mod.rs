@@ -1,17 +1,33 @@
-use std::sync::Mutex;
-use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+#[cfg(windows)]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use std::thread;
+use std::collections::{HashMap, HashSet, VecDeque};
 use serde::{Serialize, Deserialize};
+use tauri::Emitter;
 
 #[cfg(windows)]
 use windows::{
     core::*,
     Win32::System::Com::*,
+    Win32::System::Com::StructuredStorage::PropVariantToStringAlloc,
     Win32::Media::Audio::*,
     Win32::Media::Audio::Endpoints::*,
     Win32::Foundation::*,
     Win32::System::Threading::*,
+    Win32::System::Diagnostics::ToolHelp::*,
+    Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName,
+    Win32::UI::Shell::PropertiesSystem::IPropertyStore,
 };
 
+lazy_static::lazy_static! {
+    /// An endpoint's friendly name doesn't change without a driver reinstall, so cache it by
+    /// device ID rather than re-reading the property store on every poll - this matters once
+    /// multiple devices are being named at once (`list_audio_devices`), not just the default.
+    static ref DEVICE_NAME_CACHE: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Constants
 // ─────────────────────────────────────────────────────────────────────────────
@@ -28,6 +44,28 @@ const INITIAL_SESSION_CAPACITY: usize = 64;
 /// Interval for logging enumerate calls (every N calls)
 const LOG_INTERVAL: usize = 200;
 
+/// Channel count assumed for sessions that don't expose `IChannelAudioVolume` - see
+/// `AudioSession::channel_count`.
+const DEFAULT_SESSION_CHANNEL_COUNT: u32 = 2;
+
+/// Attempts for `retry_transient`, including the first try.
+const TRANSIENT_RETRY_ATTEMPTS: u32 = 3;
+
+/// Base backoff between retries in `retry_transient`; multiplied by the attempt
+/// number so the wait grows slightly on each pass.
+const TRANSIENT_RETRY_BACKOFF_MS: u64 = 40;
+
+/// How long the duplication bridge sleeps between polls of an empty loopback capture
+/// packet, or of a target buffer with no room yet - see `run_duplicate_bridge`.
+const DUPLICATE_BRIDGE_POLL_MS: u64 = 5;
+
+/// Bumped every time `duplicate_session_to_device`/`stop_session_duplication` runs, so a
+/// bridge thread that's been superseded (stopped, or restarted against a different target)
+/// notices and exits on its own instead of needing an explicit shutdown signal - same idiom
+/// as `control_server::CONTROL_SERVER_GENERATION`.
+#[cfg(windows)]
+static DUPLICATE_BRIDGE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 /// Information about an audio session (application)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioSession {
@@ -36,15 +74,597 @@ pub struct AudioSession {
     pub process_id: u32,
     pub process_name: String, // e.g., "Discord.exe"
     pub volume: f32, // 0.0 to 1.0
+    /// `volume` rounded to the nearest whole percent, so the UI can display "75%"
+    /// without re-deriving it from the float (and drifting by a point in the process).
+    pub volume_percent: u8,
+    pub is_muted: bool,
+    /// `false` for sessions that don't expose `ISimpleAudioVolume` (certain system or
+    /// exclusive-mode apps) - `volume`/`is_muted` are meaningless placeholders in that case,
+    /// and `set_session_volume`/`set_session_mute` return an `Unsupported` error for them.
+    pub controllable: bool,
+    /// Milliseconds since this session was first seen by `enumerate_sessions` (i.e. since
+    /// ClearComms started, or since the app launched if that was more recent). Resets if the
+    /// session disappears and later reappears under the same ID, since that's effectively a
+    /// new session as far as anything auto-collapsing idle strips cares.
+    pub age_ms: u64,
+    /// Milliseconds since this session last transitioned out of `AudioSessionStateActive`,
+    /// or `None` while it's currently active. Lets the UI auto-collapse strips that have sat
+    /// inactive for longer than some threshold without polling session state itself.
+    pub inactive_since_ms: Option<u64>,
+    /// Channel count from `IChannelAudioVolume::GetChannelCount`, for rendering a
+    /// mono/stereo/surround meter instead of assuming stereo. Defaults to 2 for sessions that
+    /// don't expose `IChannelAudioVolume` either (the same sessions that come back
+    /// non-`controllable`, in practice).
+    pub channel_count: u32,
+    /// The endpoint this session was found on when last enumerated, from `device.GetId()`.
+    /// Lets `set_session_volume`/`set_session_mute` honour a `settings::device_pins` entry
+    /// by scoping to the session instance actually on the pinned device, rather than every
+    /// instance with a matching `process_id` regardless of which endpoint it's playing to.
+    pub device_id: String,
+}
+
+/// A render+capture session pair grouped by process name into one UI model, so apps that
+/// show up as both (e.g. Discord, with call audio on the output side and mic capture on the
+/// input side) render as a single strip with separate in/out sliders instead of two unrelated
+/// entries. Either side is `None` when the process has no session of that kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedSession {
+    pub process_name: String,
+    pub display_name: String,
+    pub process_id: u32,
+    pub output_session_id: Option<String>,
+    pub output_volume: Option<f32>,
+    pub output_muted: Option<bool>,
+    pub input_session_id: Option<String>,
+    pub input_volume: Option<f32>,
+    pub input_muted: Option<bool>,
+}
+
+/// One session's volume/mute state diverging between two snapshots, as found by `diff_sessions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionChange {
+    pub session_id: String,
+    pub process_name: String,
+    pub volume_delta: f32,
+    pub mute_changed: bool,
+    pub is_muted: bool,
+}
+
+/// Result of `diff_sessions`: sessions that appeared, disappeared, or had their volume/mute
+/// state change between two snapshots - e.g. a stream-automation script checking "did OBS just
+/// launch", without it having to reimplement session matching itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDiff {
+    pub added: Vec<AudioSession>,
+    pub removed: Vec<AudioSession>,
+    pub changed: Vec<SessionChange>,
+}
+
+/// Compare two session snapshots by `session_id`, backing the `diff_sessions` command.
+fn compute_session_diff(previous: &[AudioSession], current: &[AudioSession]) -> SessionDiff {
+    let previous_by_id: HashMap<&str, &AudioSession> = previous.iter()
+        .map(|s| (s.session_id.as_str(), s))
+        .collect();
+    let current_by_id: HashMap<&str, &AudioSession> = current.iter()
+        .map(|s| (s.session_id.as_str(), s))
+        .collect();
+
+    let added = current.iter()
+        .filter(|s| !previous_by_id.contains_key(s.session_id.as_str()))
+        .cloned()
+        .collect();
+    let removed = previous.iter()
+        .filter(|s| !current_by_id.contains_key(s.session_id.as_str()))
+        .cloned()
+        .collect();
+
+    let mut changed = Vec::new();
+    for session in current {
+        let Some(prev) = previous_by_id.get(session.session_id.as_str()) else { continue };
+        let volume_delta = session.volume - prev.volume;
+        let mute_changed = session.is_muted != prev.is_muted;
+        if volume_delta.abs() > f32::EPSILON || mute_changed {
+            changed.push(SessionChange {
+                session_id: session.session_id.clone(),
+                process_name: session.process_name.clone(),
+                volume_delta,
+                mute_changed,
+                is_muted: session.is_muted,
+            });
+        }
+    }
+
+    SessionDiff { added, removed, changed }
+}
+
+/// Outcome of `set_session_gain`: the session volume API has no headroom above 1.0, so
+/// `applied_gain` is clamped and `at_ceiling` tells the UI whether the request actually hit
+/// that ceiling (as opposed to a request at or below unity, which applies exactly).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionGainResult {
+    pub requested_gain: f32,
+    pub applied_gain: f32,
+    pub at_ceiling: bool,
+}
+
+/// Payload for `"session-volume-changed"`, emitted from `enumerate_sessions` when a poll
+/// notices a session's volume differs from the last cached value - i.e. it was changed
+/// externally (Windows Volume Mixer, another tool) rather than through our own
+/// `set_session_volume`/`set_session_gain`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionVolumeChange {
+    pub session_id: String,
+    pub process_name: String,
+    pub volume: f32,
+    pub volume_percent: u8,
+}
+
+/// Payload for `"session-mute-changed"`, the mute-state counterpart to `SessionVolumeChange`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMuteChange {
+    pub session_id: String,
+    pub process_name: String,
     pub is_muted: bool,
 }
 
+/// Clamp a 0-100 percent value and map it to the 0.0-1.0 scalar the session APIs use.
+fn percent_to_scalar(pct: u8) -> f32 {
+    pct.min(100) as f32 / 100.0
+}
+
+/// Round a 0.0-1.0 scalar to the nearest whole percent for display.
+fn scalar_to_percent(volume: f32) -> u8 {
+    (volume.clamp(0.0, 1.0) * 100.0).round() as u8
+}
+
+/// How the channel strip orders sessions returned by enumeration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortMode {
+    /// Alphabetical by process name (the implicit default today).
+    Name,
+    /// Loudest current peak level first.
+    Peak,
+    /// Actively-producing-sound sessions first, then by name.
+    ActiveFirst,
+    /// User-defined order, persisted in settings.
+    Manual,
+}
+
+/// Which of Windows' default-device roles to target. Windows lets a user set the
+/// "communications" default (used by comms apps, e.g. a headset) independently of the
+/// "console"/"multimedia" default (everything else, e.g. speakers) - on a split setup the
+/// two point at different endpoints entirely. Commands that operate on "the default device"
+/// (system volume/mute, `get_default_device`) need to know which one the caller means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeviceRole {
+    /// The general/"console" default - what most non-comms playback uses.
+    Console,
+    /// The default device set for communications apps specifically.
+    Communications,
+}
+
+impl Default for DeviceRole {
+    fn default() -> Self {
+        DeviceRole::Console
+    }
+}
+
+#[cfg(windows)]
+impl DeviceRole {
+    fn to_erole(self) -> ERole {
+        match self {
+            DeviceRole::Console => eConsole,
+            DeviceRole::Communications => eCommunications,
+        }
+    }
+}
+
+/// Stream health snapshot for an audio endpoint, for troubleshooting crackly audio.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDiagnostics {
+    pub device_id: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub buffer_size_frames: u32,
+    pub stream_latency_ms: f64,
+    /// `None` when glitch tracking isn't available (requires a persistently running stream).
+    pub underrun_count: Option<u32>,
+}
+
+/// An optional capture-chain feature exposed (or not) via a capture device's topology parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureFeature {
+    /// A discrete gain-boost stage, usually an extra `IAudioVolumeLevel` part labelled
+    /// "Boost" sitting ahead of the normal capture volume node.
+    Boost,
+    /// Automatic gain control, via the topology's `IAudioAutoGainControl` part if present.
+    Agc,
+    /// Driver-implemented noise suppression. Almost never exposed as a generic topology
+    /// part in practice - most drivers implement it in a proprietary APO instead - so this
+    /// is the feature most likely to come back unsupported even on hardware that has it.
+    NoiseSuppression,
+}
+
+/// Which capture-chain features `get_capture_features` found support for on a device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureFeatureSupport {
+    pub boost: bool,
+    pub agc: bool,
+    pub noise_suppression: bool,
+}
+
+/// Runtime-probed feature support, returned by `get_capabilities`, so the frontend can hide
+/// controls that won't work on the user's system instead of showing them and erroring.
+/// Windows 10/11 vary in both OS version (per-app routing) and driver/hardware (mic boost), so
+/// this is a mix of OS build checks and live interface probes rather than a single flag.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// Per-session volume/mute control (what the channel strip already does via WASAPI
+    /// session enumeration). Present on every Windows version ClearComms targets - kept as
+    /// a capability anyway so a UI that checks `get_capabilities` up front doesn't need a
+    /// separate "this one's always true" carve-out.
+    pub per_app_routing: bool,
+    /// Whether capture (mic input) sessions can be enumerated at all - see
+    /// `AudioManager::enumerate_capture_sessions`. False would mean the audio subsystem
+    /// itself is unavailable, not just "no microphone connected".
+    pub capture_control: bool,
+    /// Whether the current default capture device's driver exposes a mic boost control -
+    /// see `get_capture_features`'s doc comment on why this is a best-effort, name-based
+    /// heuristic rather than a documented COM interface.
+    pub mic_boost: bool,
+    /// Whether the current default render device has a readable `FxProperties` "exclusive
+    /// mode" registry key - see `AudioManager::get_exclusive_mode_allowed`. Reading it needs
+    /// no elevation, unlike changing it.
+    pub exclusive_mode_toggle: bool,
+    /// Always `false` - there's no SimConnect/SimVar integration wired up in this codebase
+    /// yet (see `settings::Settings::show_on_pause`). Kept here so the frontend has one
+    /// place to check for it once that integration exists, rather than needing a new command.
+    pub simconnect_available: bool,
+}
+
+/// One audio session exactly as `debug_dump_sessions` found it, with nothing filtered out -
+/// the diagnostic counterpart to `resolve_audio_session`, which drops system sessions
+/// (`process_id` 0) and collapses a failed interface cast down to `controllable: false`.
+/// Here both are reported directly, so a "session doesn't appear" report can be checked
+/// against literally everything Windows' enumerator returned for that endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugSessionDump {
+    /// "Render" or "Capture" - which endpoint flow this session was found on.
+    pub data_flow: String,
+    pub session_index: u32,
+    /// `None` when `IAudioSessionControl2` itself failed to cast, not just a field on it.
+    pub session_id: Option<String>,
+    pub instance_id: Option<String>,
+    pub process_id: Option<u32>,
+    pub process_name: Option<String>,
+    /// `"Active"`, `"Inactive"`, or `"Expired"` - `AudioSessionState` stringified, since the
+    /// `windows` crate's generated enum isn't itself serializable.
+    pub state: Option<String>,
+    /// Grouping param GUID as Windows formats it (e.g. `{00000000-0000-0000-0000-000000000000}`
+    /// when the session didn't set one), for spotting sessions Windows itself has grouped together.
+    pub grouping_param: Option<String>,
+    pub control2_cast_ok: bool,
+    pub simple_volume_cast_ok: bool,
+    pub channel_volume_cast_ok: bool,
+}
+
+impl Default for SortMode {
+    fn default() -> Self {
+        SortMode::Name
+    }
+}
+
 /// Manages Windows Core Audio API for application volume control
 pub struct AudioManager {
     sessions: HashMap<String, AudioSession>,
     current_device_id: String,
+    /// A new default device ID seen on the previous poll but not yet confirmed on a second
+    /// consecutive one. Cleared as soon as a poll reports the old ID again or a different
+    /// new one, so only a stable switch gets reported - see `check_device_changed`.
+    pending_device_id: Option<String>,
     enumerate_calls: usize,
     last_logged_counts: Option<(usize, usize)>,
+    /// When each currently-cached session was first seen, for `AudioSession::age_ms`.
+    session_first_seen: HashMap<String, Instant>,
+    /// When each currently-cached session last transitioned out of active state, for
+    /// `AudioSession::inactive_since_ms`. Absent while the session is currently active.
+    session_last_active: HashMap<String, Instant>,
+    /// Coalescing state for `"session-volume-changed"`, keyed by session id - see
+    /// `emit_volume_change_coalesced`. Shared (rather than plain `HashMap`) because the
+    /// trailing-edge flush runs on its own short-lived thread once the throttle window closes.
+    volume_emit_state: Arc<Mutex<HashMap<String, VolumeEmitState>>>,
+    /// Rolling peak-value history per session, for `get_session_peak_history`'s sparkline -
+    /// see `PEAK_HISTORY_LENGTH`. Sampled from the same `IAudioMeterInformation::GetPeakValue`
+    /// read `enumerate_sessions` already does for `SortMode::Peak`, rather than a separate
+    /// metering loop: there's no true push here any more than there is for volume/mute
+    /// changes, so this piggybacks on the same poll the frontend already drives
+    /// `get_audio_sessions` with instead of adding a second, independently-threaded sampler.
+    peak_history: HashMap<String, VecDeque<f32>>,
+    /// Each session's volume as it stood right before the most recent `scale_all_volumes`,
+    /// keyed by session id - `None` when no scale is currently active. Kept in memory rather
+    /// than in `settings` since it's a transient undo point, not a persisted preference.
+    scale_restore: Option<HashMap<String, f32>>,
+    /// What `start_monitor_session` changed, so `stop_monitor_session` can put it back exactly -
+    /// same "save before touching, restore on demand" shape as `scale_restore`. `None` when no
+    /// session is currently being monitored.
+    monitor_restore: Option<MonitorSessionState>,
+}
+
+/// Snapshot taken by `start_monitor_session` before it touches anything, so
+/// `stop_monitor_session` can undo precisely what was done rather than guessing at defaults.
+struct MonitorSessionState {
+    session_id: String,
+    original_volume: f32,
+    original_muted: bool,
+    /// Other sessions' mute state before soloing, keyed by process name the same way
+    /// `activate_priority_mode`/`priority_mode_prior_mutes` keys its own snapshot. `None`
+    /// when `start_monitor_session` wasn't asked to solo.
+    soloed_mutes: Option<HashMap<String, bool>>,
+    /// Whether `start_monitor_session` started a duplication bridge to a monitoring device,
+    /// so `stop_monitor_session` knows to call `stop_session_duplication`.
+    duplicated: bool,
+}
+
+/// How many samples of peak-value history `get_session_peak_history` keeps per session -
+/// enough for a small sparkline without the backing buffer growing unbounded.
+const PEAK_HISTORY_LENGTH: usize = 60;
+
+/// How often `"session-volume-changed"` is allowed to fire per session - see
+/// `emit_volume_change_coalesced`.
+const VOLUME_EMIT_THROTTLE: Duration = Duration::from_millis(50);
+
+/// Per-session bookkeeping for `emit_volume_change_coalesced`: when the session last actually
+/// emitted, and (if a change arrived too soon after that) the latest value waiting to be
+/// flushed once the throttle window closes.
+struct VolumeEmitState {
+    last_emit: Instant,
+    pending: Option<SessionVolumeChange>,
+}
+
+impl AudioManager {
+    /// The session snapshot from the last `enumerate_sessions` call, without forcing a fresh
+    /// one - for callers like `binding_poller` that want to resolve a binding's process name
+    /// against a recent-enough snapshot on every poll tick without re-enumerating COM sessions
+    /// that often. Not behind `#[cfg(windows)]` since `sessions` itself isn't - it's just
+    /// always empty on a platform where `AudioManager::new()` can never actually succeed.
+    pub fn cached_sessions(&self) -> &HashMap<String, AudioSession> {
+        &self.sessions
+    }
+
+    /// Recent peak-value samples for one session, oldest first, for a UI sparkline - see
+    /// `peak_history`. Empty (not an error) for a session with no tracked history yet, same
+    /// as an unknown session, since the distinction isn't actionable for a sparkline either way.
+    pub fn session_peak_history(&self, session_id: &str) -> Vec<f32> {
+        self.peak_history.get(session_id).map(|h| h.iter().copied().collect()).unwrap_or_default()
+    }
+
+    /// Mute every cached session without a tag in `tags`, returning each one's prior mute
+    /// state (keyed by process name) so the caller can persist it and hand it back to
+    /// `deactivate_priority_mode` later. Plain session-state logic with no COM calls beyond
+    /// the per-session `set_session_mute` this already does elsewhere, so it's not behind
+    /// `#[cfg(windows)]` - same reasoning as `cached_sessions`. Takes `tags` rather than
+    /// reading `settings` itself so it stays a pure function of its inputs, testable against
+    /// the `dev-mock` backend's fixture sessions without a live settings file.
+    pub fn activate_priority_mode(&mut self, tags: &HashMap<String, String>) -> std::result::Result<HashMap<String, bool>, String> {
+        let untagged: Vec<(String, String, bool)> = self.sessions.values()
+            .filter(|s| !tags.contains_key(&s.process_name))
+            .map(|s| (s.session_id.clone(), s.process_name.clone(), s.is_muted))
+            .collect();
+
+        let mut prior_mutes = HashMap::new();
+        for (session_id, process_name, was_muted) in untagged {
+            prior_mutes.insert(process_name, was_muted);
+            if !was_muted {
+                let _ = self.set_session_mute(&session_id, true);
+            }
+        }
+
+        Ok(prior_mutes)
+    }
+
+    /// Restore each session named in `prior_mutes` to the mute state it held before
+    /// `activate_priority_mode` ran - the undo half of that method, same rationale for why
+    /// it's plain, cross-platform logic.
+    pub fn deactivate_priority_mode(&mut self, prior_mutes: HashMap<String, bool>) -> std::result::Result<(), String> {
+        for (process_name, was_muted) in prior_mutes {
+            let session_id = self.sessions.values()
+                .find(|s| s.process_name == process_name)
+                .map(|s| s.session_id.clone());
+
+            if let Some(session_id) = session_id {
+                let _ = self.set_session_mute(&session_id, was_muted);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Briefly route `session_id` to an audible level (and optionally to `target_device` via
+    /// `duplicate_session_to_device`, and/or solo it by muting every other session the same
+    /// way `activate_priority_mode` does) so the user can confirm which app a strip actually
+    /// belongs to - the "Listen to this device" idea, but for a session rather than a whole
+    /// endpoint. Errors if a session is already being monitored; call `stop_monitor_session`
+    /// first rather than stacking two at once.
+    pub fn start_monitor_session(
+        &mut self,
+        session_id: &str,
+        target_device: Option<&str>,
+        solo: bool,
+    ) -> std::result::Result<(), String> {
+        if self.monitor_restore.is_some() {
+            return Err("A session is already being monitored - call stop_monitor_session first".to_string());
+        }
+
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        if !session.controllable {
+            return Err(format!("Unsupported: session '{}' does not expose volume control", session_id));
+        }
+
+        let original_volume = session.volume;
+        let original_muted = session.is_muted;
+        let process_name = session.process_name.clone();
+
+        let soloed_mutes = if solo {
+            let untagged: HashMap<String, String> = HashMap::new();
+            let mut prior_mutes = self.activate_priority_mode(&untagged)?;
+            // The monitored session itself isn't "untagged priority" audio to mute - it's the
+            // one thing this is trying to make audible - so exclude it from the solo mute set
+            // and make sure it isn't muted from a moment ago either.
+            prior_mutes.remove(&process_name);
+            let _ = self.set_session_mute(session_id, false);
+            Some(prior_mutes)
+        } else {
+            None
+        };
+
+        self.set_session_volume(session_id, MONITOR_AUDIBLE_VOLUME)?;
+
+        let duplicated = if let Some(target_device) = target_device {
+            Self::duplicate_session_to_device(session_id, target_device)?;
+            true
+        } else {
+            false
+        };
+
+        self.monitor_restore = Some(MonitorSessionState {
+            session_id: session_id.to_string(),
+            original_volume,
+            original_muted,
+            soloed_mutes,
+            duplicated,
+        });
+
+        Ok(())
+    }
+
+    /// Undo the most recent `start_monitor_session`: restore the monitored session's own
+    /// volume/mute, unmute anything `solo` muted, and stop any duplication bridge. Errors if
+    /// no session is currently being monitored.
+    pub fn stop_monitor_session(&mut self) -> std::result::Result<(), String> {
+        let restore = self.monitor_restore.take()
+            .ok_or("No session is currently being monitored")?;
+
+        if restore.duplicated {
+            Self::stop_session_duplication();
+        }
+
+        if let Some(soloed_mutes) = restore.soloed_mutes {
+            self.deactivate_priority_mode(soloed_mutes)?;
+        }
+
+        let _ = self.set_session_volume(&restore.session_id, restore.original_volume);
+        let _ = self.set_session_mute(&restore.session_id, restore.original_muted);
+
+        Ok(())
+    }
+
+    /// Nudge `session_id`'s volume by `delta` (negative to lower) relative to its current
+    /// cached value, clamped to 0.0-1.0 - the relative counterpart to `set_session_volume`'s
+    /// absolute set, for a stepped button control (e.g. `binding_poller`'s accelerating
+    /// volume-up/down) that only knows "a bit more/less" rather than a target value. Returns
+    /// the resulting volume as actually applied - `set_session_volume` may re-clamp the
+    /// target further against the process's `session_volume_cap`, so the pre-clamp target
+    /// itself isn't a safe value to hand back to a caller.
+    pub fn adjust_session_volume(&mut self, session_id: &str, delta: f32) -> std::result::Result<f32, String> {
+        let current = self.sessions.get(session_id)
+            .map(|s| s.volume)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        let new_volume = (current + delta).clamp(0.0, 1.0);
+        self.set_session_volume(session_id, new_volume)?;
+
+        Ok(self.sessions.get(session_id).map(|s| s.volume).unwrap_or(new_volume))
+    }
+
+    /// Undo everything ClearComms has changed since it started touching sessions: unmute every
+    /// session tracked by `clear_clearcomms_mutes`, put back each session's pre-ClearComms
+    /// volume from `settings::take_original_volumes`, and cleanly back out of priority mode or
+    /// an in-progress session monitor if either is active. Distinct from a "set everything to
+    /// 100%" panic reset - this restores what was actually there before, the same
+    /// "saved snapshot, not a guess" approach `deactivate_priority_mode`/`stop_monitor_session`
+    /// already take - so handing the PC to someone else doesn't just trade one arbitrary state
+    /// for another. Leaves bindings, tags and every other saved config alone; only the live
+    /// overrides tracked for crash recovery are cleared.
+    pub fn restore_windows_state(&mut self) -> std::result::Result<(), String> {
+        if self.monitor_restore.is_some() {
+            self.stop_monitor_session()?;
+        }
+
+        if let Some(prior_mutes) = crate::settings::get().priority_mode_prior_mutes {
+            self.deactivate_priority_mode(prior_mutes)?;
+            crate::settings::update(|s| s.priority_mode_prior_mutes = None);
+        }
+
+        self.clear_clearcomms_mutes()?;
+
+        for (process_name, original_volume) in crate::settings::take_original_volumes() {
+            if let Some(session_id) = self.sessions.values().find(|s| s.process_name == process_name).map(|s| s.session_id.clone()) {
+                let _ = self.set_session_volume(&session_id, original_volume);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Volume `start_monitor_session` bumps a session to while it's being monitored - loud enough
+/// to identify over room noise without necessarily matching whatever `scale_all_volumes`-style
+/// absolute level the user actually wants once they're done confirming which app this is.
+const MONITOR_AUDIBLE_VOLUME: f32 = 0.85;
+
+/// Result of a confirmed default-device switch, returned by `check_device_changed` once
+/// the new endpoint has been stable across two consecutive polls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultDeviceChange {
+    pub device_id: String,
+    /// Best-effort display name (e.g. "Speakers (Realtek Audio)"); empty if the property
+    /// store lookup fails, which callers should treat as "name unavailable" rather than fatal.
+    pub device_name: String,
+}
+
+/// The current default render device's ID and friendly name, for callers (e.g. the frontend's
+/// own change tracking) that want to compare against a known-good value deterministically
+/// rather than relying solely on `check_device_changed`'s polling cadence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultDeviceInfo {
+    pub device_id: String,
+    pub device_name: String,
+}
+
+/// One active render endpoint's ID and friendly name, as returned by `list_audio_devices` -
+/// unlike `DefaultDeviceInfo`, not necessarily the system default, for UI that lets the user
+/// pick among several output devices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDeviceInfo {
+    pub device_id: String,
+    pub device_name: String,
+}
+
+/// Mirrors WASAPI's `DEVICE_STATE_*` flags for a render endpoint, as returned by
+/// `list_all_devices` - `Active` is the only state `list_audio_devices` ever reports, since it
+/// only enumerates `DEVICE_STATE_ACTIVE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioDeviceState {
+    Active,
+    Disabled,
+    NotPresent,
+    Unplugged,
+}
+
+/// One render endpoint's ID, friendly name and current state, as returned by
+/// `list_all_devices` - unlike `AudioDeviceInfo`, covers devices that aren't currently active
+/// (disabled, unplugged, or no longer present) so a picker can show a pinned device that will
+/// reattach once it comes back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioEndpointInfo {
+    pub device_id: String,
+    pub device_name: String,
+    pub state: AudioDeviceState,
 }
 
 #[cfg(windows)]
@@ -76,58 +696,193 @@ impl Drop for ProcessHandle {
     }
 }
 
+/// Cap on how far `query_full_process_image_path` will grow its buffer in response to
+/// repeated `ERROR_INSUFFICIENT_BUFFER` - generous enough for any long-path-aware Windows
+/// 10+ path, but still a hard stop rather than growing forever on a pathological result.
 #[cfg(windows)]
-/// Get the executable name from a process ID with proper resource cleanup
-fn get_process_name(process_id: u32) -> String {
-    if process_id == 0 {
-        return "System".to_string();
-    }
+const MAX_LONG_PATH_LENGTH: usize = 32768;
 
-    if let Ok(process_handle) = ProcessHandle::open(process_id) {
-        unsafe {
-            // Buffer for the executable path
-            let mut buffer = vec![0u16; MAX_PATH_LENGTH];
-            let mut size = buffer.len() as u32;
+/// Query the full image path for `process_handle`, growing the buffer past `MAX_PATH_LENGTH`
+/// when Windows reports `ERROR_INSUFFICIENT_BUFFER` instead of giving up - long-path-aware
+/// apps installed deeply nested (e.g. a portable app synced under OneDrive) can exceed the
+/// classic MAX_PATH limit.
+#[cfg(windows)]
+fn query_full_process_image_path(process_handle: &ProcessHandle, name_format: PROCESS_NAME_FORMAT) -> Option<String> {
+    let mut buffer_len = MAX_PATH_LENGTH;
+
+    loop {
+        let mut buffer = vec![0u16; buffer_len];
+        let mut size = buffer.len() as u32;
 
-            // Get the full executable path
-            let result = QueryFullProcessImageNameW(
+        let result = unsafe {
+            QueryFullProcessImageNameW(
                 process_handle.as_handle(),
-                PROCESS_NAME_WIN32,
+                name_format,
                 PWSTR(buffer.as_mut_ptr()),
                 &mut size,
-            );
+            )
+        };
 
-            if result.is_ok() && size > 0 {
-                // Convert to String
-                let path = String::from_utf16_lossy(&buffer[0..size as usize]);
+        if result.is_ok() && size > 0 {
+            return Some(String::from_utf16_lossy(&buffer[0..size as usize]));
+        }
 
-                // Extract just the filename from the full path
-                if let Some(filename) = path.split('\\').next_back() {
-                    return filename.to_string();
-                }
+        let insufficient_buffer = unsafe { GetLastError() } == ERROR_INSUFFICIENT_BUFFER;
+        if !insufficient_buffer || buffer_len >= MAX_LONG_PATH_LENGTH {
+            return None;
+        }
+
+        buffer_len = (buffer_len * 2).min(MAX_LONG_PATH_LENGTH);
+    }
+}
+
+/// Registry value name, under an endpoint's `FxProperties` key, for the "Allow applications
+/// to take exclusive control of this device" checkbox - see
+/// `AudioManager::set_exclusive_mode_allowed`. Undocumented by Microsoft, but the same value
+/// registry-tweak guides have used for this exact checkbox since Vista; `0` disallows
+/// exclusive mode, `1` allows it.
+#[cfg(windows)]
+const EXCLUSIVE_MODE_VALUE_NAME: &str = "{1da5d803-d492-4edd-8c23-e0c0ffee7f0e},5";
+
+/// Encode a `&str` as a null-terminated UTF-16 buffer for the Win32 `*W` registry calls -
+/// same conversion `get_diagnostics`/`set_session_volume` already do for endpoint IDs, just
+/// named here since `set_exclusive_mode_allowed`/`get_exclusive_mode_allowed` need it twice each.
+#[cfg(windows)]
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Current Windows build number (e.g. `22621` for a Windows 11 22H2 box), read from the same
+/// registry value `winver`/Settings > About show - `None` if the key's missing or unreadable,
+/// which `AudioManager::capabilities` treats as "assume supported" rather than failing outright.
+#[cfg(windows)]
+fn windows_build_number() -> Option<u32> {
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegOpenKeyExW, RegQueryValueExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ, REG_SZ,
+    };
+
+    unsafe {
+        let subkey_wide = to_wide(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion");
+        let mut hkey = HKEY::default();
+        RegOpenKeyExW(HKEY_LOCAL_MACHINE, PCWSTR(subkey_wide.as_ptr()), 0, KEY_READ, &mut hkey).ok()?;
+
+        let value_name_wide = to_wide("CurrentBuildNumber");
+        let mut buffer = [0u16; 32];
+        let mut data_size = (buffer.len() * std::mem::size_of::<u16>()) as u32;
+        let mut data_type = REG_SZ;
+
+        let result = RegQueryValueExW(
+            hkey,
+            PCWSTR(value_name_wide.as_ptr()),
+            None,
+            Some(&mut data_type),
+            Some(buffer.as_mut_ptr() as *mut u8),
+            Some(&mut data_size),
+        );
+        let _ = RegCloseKey(hkey);
+        result.ok()?;
+
+        let chars = (data_size as usize / std::mem::size_of::<u16>()).saturating_sub(1);
+        String::from_utf16(&buffer[..chars]).ok()?.trim().parse().ok()
+    }
+}
+
+/// Both possible `FxProperties` registry subkeys for an endpoint ID - one per audio flow,
+/// since an endpoint ID alone doesn't say whether it names a render or capture device, and
+/// opening whichever one doesn't apply is a cheap, harmless `Err` the caller just skips past.
+/// `None` if `device_id` doesn't contain a `{...}` GUID to extract at all.
+#[cfg(windows)]
+fn exclusive_mode_fx_properties_subkeys(device_id: &str) -> Option<Vec<String>> {
+    let guid_start = device_id.rfind('{')?;
+    let guid = &device_id[guid_start..];
+    Some(["Render", "Capture"].iter()
+        .map(|flow| format!(
+            "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\MMDevices\\Audio\\{}\\{}\\FxProperties",
+            flow, guid,
+        ))
+        .collect())
+}
+
+#[cfg(windows)]
+/// Get the executable name from a process ID with proper resource cleanup
+fn get_process_name(process_id: u32) -> String {
+    if process_id == 0 {
+        return "System".to_string();
+    }
 
-                return path;
+    if let Ok(process_handle) = ProcessHandle::open(process_id) {
+        // Prefer the Win32 path form; fall back to the native NT form if that fails, since a
+        // process can occasionally reject one format but answer the other.
+        let path = query_full_process_image_path(&process_handle, PROCESS_NAME_WIN32)
+            .or_else(|| query_full_process_image_path(&process_handle, PROCESS_NAME_NATIVE));
+
+        if let Some(path) = path {
+            // Extract just the filename from the full path
+            if let Some(filename) = path.split('\\').next_back() {
+                return filename.to_string();
             }
-            // ProcessHandle automatically closes on drop
+
+            return path;
         }
+        // ProcessHandle automatically closes on drop
     }
 
     // Fallback if we can't get the process name
     format!("Process {}", process_id)
 }
 
+thread_local! {
+    /// Whether this thread has already called `CoInitializeEx`, paired with the `ComGuard` that
+    /// will call `CoUninitialize` for it. Tauri dispatches commands across its blocking worker
+    /// pool rather than onto one fixed thread, and COM initialization is per-thread - so
+    /// `AudioManager::new` initializing COM once on whichever thread called `init_audio_manager`
+    /// doesn't help a later command that lands on a different pool thread. Every `AudioManager`
+    /// function that touches a COM API (anything creating an `IMMDeviceEnumerator` or similar)
+    /// calls `ensure_com_initialized` first instead, memoized per-thread so repeat commands on
+    /// an already-initialized thread are a no-op.
+    static COM_GUARD: std::cell::RefCell<Option<ComGuard>> = std::cell::RefCell::new(None);
+}
+
+/// RAII pairing for one thread's `CoInitializeEx` call - dropped (running `CoUninitialize`)
+/// when `COM_GUARD` itself is dropped, i.e. when the owning thread is torn down.
 #[cfg(windows)]
-impl AudioManager {
-    /// Create a new audio manager instance
-    pub fn new() -> std::result::Result<Self, String> {
-        tracing::info!("[Audio] Initialising COM library...");
-        // Initialize COM for this thread
+struct ComGuard;
+
+#[cfg(windows)]
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        unsafe { CoUninitialize(); }
+    }
+}
+
+/// Initialize COM (apartment-threaded, matching the rest of this module's COM usage) on the
+/// calling thread if it hasn't been already. Call this before any direct COM API use - see the
+/// `COM_GUARD` doc comment for why a single call in `AudioManager::new` isn't sufficient.
+#[cfg(windows)]
+fn ensure_com_initialized() -> std::result::Result<(), String> {
+    COM_GUARD.with(|guard| {
+        if guard.borrow().is_some() {
+            return Ok(());
+        }
+
         unsafe {
             CoInitializeEx(None, COINIT_APARTMENTTHREADED)
                 .ok()
                 .map_err(|e: Error| format!("Failed to initialize COM: {}", e))?;
         }
-        
+
+        *guard.borrow_mut() = Some(ComGuard);
+        Ok(())
+    })
+}
+
+#[cfg(windows)]
+impl AudioManager {
+    /// Create a new audio manager instance
+    pub fn new() -> std::result::Result<Self, String> {
+        tracing::info!("[Audio] Initialising COM library...");
+        ensure_com_initialized()?;
+
         tracing::info!("[Audio] Detecting default audio device...");
         // Get initial default device ID
         let device_id = Self::get_default_device_id()?;
@@ -136,13 +891,30 @@ impl AudioManager {
         Ok(Self {
             sessions: HashMap::new(),
             current_device_id: device_id,
+            pending_device_id: None,
             enumerate_calls: 0,
             last_logged_counts: None,
+            session_first_seen: HashMap::new(),
+            session_last_active: HashMap::new(),
+            volume_emit_state: Arc::new(Mutex::new(HashMap::new())),
+            peak_history: HashMap::new(),
+            scale_restore: None,
+            monitor_restore: None,
         })
     }
-    
-    /// Get the current default audio device ID
+
+    /// Get the current default audio device ID (console role - see `DeviceRole`).
     fn get_default_device_id() -> std::result::Result<String, String> {
+        Self::get_default_device_info(DeviceRole::Console).map(|info| info.device_id)
+    }
+
+    /// Get the current default capture (recording) device ID - the `eCapture` counterpart to
+    /// `get_default_device_id`, which only ever resolves `eRender`. Used by `capabilities` to
+    /// probe mic boost support without requiring the caller to already know which mic is
+    /// default.
+    fn default_capture_device_id() -> std::result::Result<String, String> {
+        ensure_com_initialized()?;
+
         unsafe {
             let enumerator: IMMDeviceEnumerator = CoCreateInstance(
                 &MMDeviceEnumerator,
@@ -151,38 +923,25 @@ impl AudioManager {
             ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
             let device = enumerator
-                .GetDefaultAudioEndpoint(eRender, eConsole)
-                .map_err(|e: Error| format!("Failed to get default audio endpoint: {}", e))?;
+                .GetDefaultAudioEndpoint(eCapture, eConsole)
+                .map_err(|e: Error| format!("Failed to get default capture endpoint: {}", e))?;
 
             let id = device.GetId()
                 .map_err(|e: Error| format!("Failed to get device ID: {}", e))?;
-
             let id_string = id.to_string()
-                .map_err(|e| format!("Failed to convert device ID: {}", e));
-
-            // Free COM-allocated PWSTR to prevent memory leak
-            // Win32 docs: "the caller is responsible for freeing the memory"
+                .map_err(|e| format!("Failed to convert device ID: {}", e))?;
             CoTaskMemFree(Some(id.0 as *const core::ffi::c_void));
 
-            id_string
-        }
-    }
-    
-    /// Check if default device has changed, return true if changed
-    pub fn check_device_changed(&mut self) -> std::result::Result<bool, String> {
-        let new_device_id = Self::get_default_device_id()?;
-        
-        if new_device_id != self.current_device_id {
-            tracing::info!("[Audio] Default device changed: {} -> {}", self.current_device_id, new_device_id);
-            self.current_device_id = new_device_id;
-            Ok(true)
-        } else {
-            Ok(false)
+            Ok(id_string)
         }
     }
-    
-    /// Get the system audio endpoint volume interface
-    fn get_endpoint_volume() -> std::result::Result<IAudioEndpointVolume, String> {
+
+    /// Get the given role's default audio device's endpoint ID and friendly name together, so
+    /// callers that want both (`get_default_device`, the debounce in `check_device_changed`)
+    /// don't need to create the device enumerator twice.
+    fn get_default_device_info(role: DeviceRole) -> std::result::Result<DefaultDeviceInfo, String> {
+        ensure_com_initialized()?;
+
         unsafe {
             let enumerator: IMMDeviceEnumerator = CoCreateInstance(
                 &MMDeviceEnumerator,
@@ -191,64 +950,76 @@ impl AudioManager {
             ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
             let device = enumerator
-                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .GetDefaultAudioEndpoint(eRender, role.to_erole())
                 .map_err(|e: Error| format!("Failed to get default audio endpoint: {}", e))?;
 
-            device
-                .Activate(CLSCTX_ALL, None)
-                .map_err(|e: Error| format!("Failed to activate endpoint volume: {}", e))
+            let id = device.GetId()
+                .map_err(|e: Error| format!("Failed to get device ID: {}", e))?;
+
+            let id_string = id.to_string()
+                .map_err(|e| format!("Failed to convert device ID: {}", e))?;
+
+            // Free COM-allocated PWSTR to prevent memory leak
+            // Win32 docs: "the caller is responsible for freeing the memory"
+            CoTaskMemFree(Some(id.0 as *const core::ffi::c_void));
+
+            let device_name = Self::cached_device_friendly_name(&device, &id_string);
+
+            Ok(DefaultDeviceInfo { device_id: id_string, device_name })
         }
     }
 
-    /// Get the system (device endpoint) master volume level (0.0 to 1.0)
-    pub fn get_system_volume(&self) -> std::result::Result<f32, String> {
-        unsafe {
-            Self::get_endpoint_volume()?
-                .GetMasterVolumeLevelScalar()
-                .map_err(|e: Error| format!("Failed to get master volume: {}", e))
+    /// `get_device_friendly_name`, but checking `DEVICE_NAME_CACHE` by `device_id` first.
+    fn cached_device_friendly_name(device: &IMMDevice, device_id: &str) -> String {
+        if let Some(cached) = DEVICE_NAME_CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(device_id) {
+            return cached.clone();
         }
-    }
 
-    /// Get the system (device endpoint) mute state
-    pub fn get_system_mute(&self) -> std::result::Result<bool, String> {
-        unsafe {
-            Ok(Self::get_endpoint_volume()?
-                .GetMute()
-                .map_err(|e: Error| format!("Failed to get mute state: {}", e))?
-                .as_bool())
+        let name = Self::get_device_friendly_name(device);
+        if !name.is_empty() {
+            DEVICE_NAME_CACHE.lock().unwrap_or_else(|e| e.into_inner())
+                .insert(device_id.to_string(), name.clone());
         }
+        name
     }
 
-    /// Set the system (device endpoint) master volume level (0.0 to 1.0)
-    pub fn set_system_volume(&self, volume: f32) -> std::result::Result<(), String> {
-        let volume = volume.clamp(0.0, 1.0);
+    /// Best-effort friendly name (e.g. "Speakers (Realtek Audio)") for an endpoint, via its
+    /// property store. Returns an empty string on any failure rather than an error - the
+    /// endpoint ID is the authoritative identity, so a missing name shouldn't fail the caller.
+    fn get_device_friendly_name(device: &IMMDevice) -> String {
         unsafe {
-            Self::get_endpoint_volume()?
-                .SetMasterVolumeLevelScalar(volume, std::ptr::null())
-                .map_err(|e: Error| format!("Failed to set master volume: {}", e))
+            let Ok(store): std::result::Result<IPropertyStore, Error> = device.OpenPropertyStore(STGM_READ) else {
+                return String::new();
+            };
+
+            let Ok(prop) = store.GetValue(&PKEY_Device_FriendlyName) else {
+                return String::new();
+            };
+
+            match PropVariantToStringAlloc(&prop) {
+                Ok(name) => {
+                    let name_string = name.to_string().unwrap_or_default();
+                    CoTaskMemFree(Some(name.0 as *const core::ffi::c_void));
+                    name_string
+                }
+                Err(_) => String::new(),
+            }
         }
     }
 
-    /// Set the system (device endpoint) mute state
-    pub fn set_system_mute(&self, muted: bool) -> std::result::Result<(), String> {
+    /// List every active render endpoint's ID and friendly name, for device-picker UI that
+    /// needs more than just the system default - the one place friendly-name caching actually
+    /// pays for itself, since every device here gets named on every call.
+    pub fn list_audio_devices() -> std::result::Result<Vec<AudioDeviceInfo>, String> {
+        ensure_com_initialized()?;
+
         unsafe {
-            Self::get_endpoint_volume()?
-                .SetMute(BOOL(muted as i32), std::ptr::null())
-                .map_err(|e: Error| format!("Failed to set mute state: {}", e))
-        }
-    }
-
-    /// Enumerate all active audio sessions from all audio devices with proper resource management
-    pub fn enumerate_sessions(&mut self) -> std::result::Result<Vec<AudioSession>, String> {
-        unsafe {
-            // Create device enumerator
             let enumerator: IMMDeviceEnumerator = CoCreateInstance(
                 &MMDeviceEnumerator,
                 None,
                 CLSCTX_ALL,
             ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
-            // Get all audio render devices
             let device_collection = enumerator
                 .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
                 .map_err(|e: Error| format!("Failed to enumerate audio endpoints: {}", e))?;
@@ -257,141 +1028,47 @@ impl AudioManager {
                 .GetCount()
                 .map_err(|e: Error| format!("Failed to get device count: {}", e))?;
 
-            let mut sessions = Vec::with_capacity(INITIAL_SESSION_CAPACITY); // Pre-allocate reasonable capacity
-            let mut live_session_ids: HashSet<String> = HashSet::with_capacity(INITIAL_SESSION_CAPACITY);
+            let mut devices = Vec::with_capacity(device_count as usize);
 
-            // Iterate through all audio devices
             for device_index in 0..device_count {
                 let device = match device_collection.Item(device_index) {
                     Ok(dev) => dev,
-                    Err(_) => continue, // Skip devices we can't access
-                };
-
-                // Get audio session manager for this device
-                let session_manager: IAudioSessionManager2 = match device.Activate(CLSCTX_ALL, None) {
-                    Ok(mgr) => mgr,
-                    Err(_) => continue, // Skip if we can't get session manager
-                };
-
-                // Get session enumerator for this device
-                let session_enum = match session_manager.GetSessionEnumerator() {
-                    Ok(enumerator) => enumerator,
-                    Err(_) => continue,
-                };
-
-                let count = match session_enum.GetCount() {
-                    Ok(c) => c,
                     Err(_) => continue,
                 };
 
-                // Enumerate sessions for this device
-                for i in 0..count {
-                    if let Ok(session_control) = session_enum.GetSession(i) {
-                        if let Ok(session_control2) = session_control.cast::<IAudioSessionControl2>() {
-                            // Get session details
-                            let process_id = session_control2
-                                .GetProcessId()
-                                .unwrap_or(0);
-
-                            // Skip system sessions (process_id 0)
-                            if process_id == 0 {
-                                continue;
-                            }
+                let Ok(id) = device.GetId() else { continue };
+                let id_string = id.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(id.0 as *const core::ffi::c_void));
 
-                            let session_id = match session_control2.GetSessionInstanceIdentifier() {
-                                Ok(pwstr) => {
-                                    let s = pwstr.to_string()
-                                        .unwrap_or_else(|_| format!("session_{}", i));
-                                    // Free COM-allocated PWSTR to prevent memory leak
-                                    CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
-                                    s
-                                }
-                                Err(_) => format!("session_{}", i),
-                            };
-
-                            let display_name = match session_control2.GetDisplayName() {
-                                Ok(pwstr) => {
-                                    let s = pwstr.to_string()
-                                        .unwrap_or_else(|_| format!("Process {}", process_id));
-                                    // Free COM-allocated PWSTR to prevent memory leak
-                                    CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
-                                    s
-                                }
-                                Err(_) => format!("Process {}", process_id),
-                            };
-
-                            // Get the actual process executable name
-                            let process_name = get_process_name(process_id);
-
-                            // Get volume control
-                            if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
-                                let volume = simple_volume.GetMasterVolume().unwrap_or(1.0);
-                                let is_muted = simple_volume.GetMute().unwrap_or(BOOL(0)).as_bool();
-
-                                let session = AudioSession {
-                                    session_id: session_id.clone(),
-                                    display_name,
-                                    process_id,
-                                    process_name: process_name.clone(),
-                                    volume,
-                                    is_muted,
-                                };
-
-                                live_session_ids.insert(session_id.clone());
-                                sessions.push(session.clone());
-                                self.sessions.insert(session_id, session);
-                            }
-                        }
-                    }
+                if id_string.is_empty() {
+                    continue;
                 }
-            } // End device loop
 
-            // Remove sessions that are no longer active to prevent cache growth
-            self.sessions.retain(|id, _| live_session_ids.contains(id));
-            
-            // Prevent unbounded memory growth by limiting cache size
-            if self.sessions.len() > MAX_SESSION_CACHE_SIZE {
-                // Keep only the most recent entries
-                let mut session_keys: Vec<String> = self.sessions.keys().cloned().collect();
-                session_keys.truncate(MAX_SESSION_CACHE_SIZE / 2); // Remove oldest half
-                self.sessions.retain(|k, _| session_keys.contains(k));
-                tracing::warn!("[Audio] Cache size limit reached, pruned to {} entries", self.sessions.len());
-            }
-
-            self.enumerate_calls = self.enumerate_calls.wrapping_add(1);
-            let active_count = live_session_ids.len();
-            let cache_count = self.sessions.len();
-
-            let counts_changed = match self.last_logged_counts {
-                Some((last_active, last_cache)) => last_active != active_count || last_cache != cache_count,
-                None => true,
-            };
+                let mut device_name = Self::cached_device_friendly_name(&device, &id_string);
+                if device_name.is_empty() {
+                    // Fall back to the raw endpoint ID so the picker UI always has something
+                    // to show, rather than a blank entry when the property store lookup fails.
+                    device_name = id_string.clone();
+                }
 
-            if counts_changed || self.enumerate_calls % LOG_INTERVAL == 0 {
-                tracing::debug!(
-                    "[Audio] enumerate_sessions: {} active (cache size {}, calls: {})",
-                    active_count,
-                    cache_count,
-                    self.enumerate_calls
-                );
-                self.last_logged_counts = Some((active_count, cache_count));
+                devices.push(AudioDeviceInfo {
+                    device_id: id_string,
+                    device_name,
+                });
             }
 
-            Ok(sessions)
+            Ok(devices)
         }
     }
 
-    /// Set volume for a specific session and all sessions of the same process (searches all devices)
-    pub fn set_session_volume(&mut self, session_id: &str, volume: f32) -> std::result::Result<(), String> {
-        let volume = volume.clamp(0.0, 1.0);
-        
-        // First, find the process_id for this session
-        let target_process_id = self.sessions.get(session_id)
-            .map(|s| s.process_id)
-            .ok_or_else(|| format!("Session not found: {}", session_id))?;
-        
-        let mut updated_count = 0;
-        
+    /// List every render endpoint regardless of state - active, disabled, unplugged, or no
+    /// longer present - for a device picker that needs to show a pinned device while it's
+    /// disconnected, so `reattach_pinned_sessions` has something to match against once it
+    /// comes back. `list_audio_devices` stays active-only for everywhere else that just wants
+    /// devices a session could actually be routed to right now.
+    pub fn list_all_devices() -> std::result::Result<Vec<AudioEndpointInfo>, String> {
+        ensure_com_initialized()?;
+
         unsafe {
             let enumerator: IMMDeviceEnumerator = CoCreateInstance(
                 &MMDeviceEnumerator,
@@ -399,73 +1076,103 @@ impl AudioManager {
                 CLSCTX_ALL,
             ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
-            // Get all audio render devices
             let device_collection = enumerator
-                .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+                .EnumAudioEndpoints(eRender, DEVICE_STATE(DEVICE_STATEMASK_ALL))
                 .map_err(|e: Error| format!("Failed to enumerate audio endpoints: {}", e))?;
 
-            let device_count = device_collection.GetCount().unwrap_or(0);
+            let device_count = device_collection
+                .GetCount()
+                .map_err(|e: Error| format!("Failed to get device count: {}", e))?;
+
+            let mut devices = Vec::with_capacity(device_count as usize);
 
-            // Search through all devices for sessions with matching process_id
             for device_index in 0..device_count {
                 let device = match device_collection.Item(device_index) {
                     Ok(dev) => dev,
                     Err(_) => continue,
                 };
 
-                let session_manager: IAudioSessionManager2 = match device.Activate(CLSCTX_ALL, None) {
-                    Ok(mgr) => mgr,
-                    Err(_) => continue,
-                };
+                let Ok(id) = device.GetId() else { continue };
+                let id_string = id.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(id.0 as *const core::ffi::c_void));
 
-                let session_enum = match session_manager.GetSessionEnumerator() {
-                    Ok(enumerator) => enumerator,
-                    Err(_) => continue,
-                };
+                if id_string.is_empty() {
+                    continue;
+                }
 
-                let count = session_enum.GetCount().unwrap_or(0);
+                let state = match device.GetState() {
+                    Ok(DEVICE_STATE_ACTIVE) => AudioDeviceState::Active,
+                    Ok(DEVICE_STATE_DISABLED) => AudioDeviceState::Disabled,
+                    Ok(DEVICE_STATE_NOTPRESENT) => AudioDeviceState::NotPresent,
+                    Ok(DEVICE_STATE_UNPLUGGED) => AudioDeviceState::Unplugged,
+                    // A device can be reported with more than one flag set (e.g. unplugged
+                    // *and* not present); fall back to the least useful-sounding state rather
+                    // than guessing, since "not present" is the one that reattachment cares
+                    // about least.
+                    _ => AudioDeviceState::NotPresent,
+                };
 
-                for i in 0..count {
-                    if let Ok(session_control) = session_enum.GetSession(i) {
-                        if let Ok(session_control2) = session_control.cast::<IAudioSessionControl2>() {
-                            let process_id = session_control2
-                                .GetProcessId()
-                                .unwrap_or(0);
-
-                            // Apply volume to ALL sessions with matching process_id
-                            if process_id == target_process_id {
-                                if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
-                                    let _ = simple_volume.SetMasterVolume(volume, std::ptr::null());
-                                    updated_count += 1;
-                                }
-                            }
-                        }
-                    }
+                // Only active endpoints reliably support opening a property store - naming a
+                // disabled/unplugged device best-effort, falling back to its raw ID like
+                // `list_audio_devices` already does for an active device with no friendly name.
+                let mut device_name = Self::cached_device_friendly_name(&device, &id_string);
+                if device_name.is_empty() {
+                    device_name = id_string.clone();
                 }
-            } // End device loop
 
-            // Update cache for the requested session
-            if let Some(session) = self.sessions.get_mut(session_id) {
-                session.volume = volume;
+                devices.push(AudioEndpointInfo {
+                    device_id: id_string,
+                    device_name,
+                    state,
+                });
             }
 
-            if updated_count > 0 {
-                Ok(())
-            } else {
-                Err(format!("No sessions found for process_id: {}", target_process_id))
-            }
+            Ok(devices)
         }
     }
 
-    /// Mute or unmute all sessions of the same process (searches all devices)
-    pub fn set_session_mute(&mut self, session_id: &str, muted: bool) -> std::result::Result<(), String> {
-        // First, find the process_id for this session
-        let target_process_id = self.sessions.get(session_id)
-            .map(|s| s.process_id)
-            .ok_or_else(|| format!("Session not found: {}", session_id))?;
-        
-        let mut updated_count = 0;
-        
+    /// Check if the default device has changed, requiring the new endpoint to be stable
+    /// across two consecutive polls before reporting it - device-switch transitions can
+    /// briefly surface an intermediate endpoint, which would otherwise cause a spurious
+    /// re-enumeration. Returns the new device's ID and friendly name once confirmed.
+    pub fn check_device_changed(&mut self) -> std::result::Result<Option<DefaultDeviceChange>, String> {
+        let new_device_info = Self::get_default_device_info(DeviceRole::Console)?;
+
+        if new_device_info.device_id == self.current_device_id {
+            self.pending_device_id = None;
+            return Ok(None);
+        }
+
+        if self.pending_device_id.as_deref() != Some(new_device_info.device_id.as_str()) {
+            // First sighting of this candidate - wait for the next poll to confirm it.
+            self.pending_device_id = Some(new_device_info.device_id);
+            return Ok(None);
+        }
+
+        tracing::info!(
+            "[Audio] Default device changed: {} -> {} ({})",
+            self.current_device_id, new_device_info.device_id, new_device_info.device_name
+        );
+        self.current_device_id = new_device_info.device_id.clone();
+        self.pending_device_id = None;
+
+        Ok(Some(DefaultDeviceChange {
+            device_id: new_device_info.device_id,
+            device_name: new_device_info.device_name,
+        }))
+    }
+
+    /// Get `role`'s default device's ID and friendly name, for callers that want to
+    /// compare against a known value deterministically rather than relying on the polled
+    /// `check_device_changed` (which only ever tracks the console role).
+    pub fn get_default_device(role: DeviceRole) -> std::result::Result<DefaultDeviceInfo, String> {
+        Self::get_default_device_info(role)
+    }
+
+    /// Get `role`'s audio endpoint volume interface.
+    fn get_endpoint_volume(role: DeviceRole) -> std::result::Result<IAudioEndpointVolume, String> {
+        ensure_com_initialized()?;
+
         unsafe {
             let enumerator: IMMDeviceEnumerator = CoCreateInstance(
                 &MMDeviceEnumerator,
@@ -473,203 +1180,2755 @@ impl AudioManager {
                 CLSCTX_ALL,
             ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
-            // Get all audio render devices
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, role.to_erole())
+                .map_err(|e: Error| format!("Failed to get default audio endpoint: {}", e))?;
+
+            device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e: Error| format!("Failed to activate endpoint volume: {}", e))
+        }
+    }
+
+    /// Get `role`'s default device's master volume level (0.0 to 1.0).
+    pub fn get_system_volume(&self, role: DeviceRole) -> std::result::Result<f32, String> {
+        unsafe {
+            Self::get_endpoint_volume(role)?
+                .GetMasterVolumeLevelScalar()
+                .map_err(|e: Error| format!("Failed to get master volume: {}", e))
+        }
+    }
+
+    /// Get `role`'s default device's mute state.
+    pub fn get_system_mute(&self, role: DeviceRole) -> std::result::Result<bool, String> {
+        unsafe {
+            Ok(Self::get_endpoint_volume(role)?
+                .GetMute()
+                .map_err(|e: Error| format!("Failed to get mute state: {}", e))?
+                .as_bool())
+        }
+    }
+
+    /// Set `role`'s default device's master volume level (0.0 to 1.0).
+    pub fn set_system_volume(&self, role: DeviceRole, volume: f32) -> std::result::Result<(), String> {
+        let volume = volume.clamp(0.0, 1.0);
+        unsafe {
+            Self::get_endpoint_volume(role)?
+                .SetMasterVolumeLevelScalar(volume, std::ptr::null())
+                .map_err(|e: Error| format!("Failed to set master volume: {}", e))
+        }
+    }
+
+    /// Set `role`'s default device's mute state.
+    pub fn set_system_mute(&self, role: DeviceRole, muted: bool) -> std::result::Result<(), String> {
+        unsafe {
+            Self::get_endpoint_volume(role)?
+                .SetMute(BOOL(muted as i32), std::ptr::null())
+                .map_err(|e: Error| format!("Failed to set mute state: {}", e))
+        }
+    }
+
+    /// Find the render endpoint ID currently hosting `session_id`. Sessions aren't pinned to
+    /// the default render device - `enumerate_sessions` walks every active endpoint via
+    /// `EnumAudioEndpoints`, not just the default one - so `duplicate_session_to_device` needs
+    /// to resolve the actual endpoint itself rather than assuming the default.
+    fn find_session_device_id(session_id: &str) -> std::result::Result<String, String> {
+        ensure_com_initialized()?;
+
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
+
             let device_collection = enumerator
                 .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
                 .map_err(|e: Error| format!("Failed to enumerate audio endpoints: {}", e))?;
 
             let device_count = device_collection.GetCount().unwrap_or(0);
 
-            // Search through all devices for sessions with matching process_id
             for device_index in 0..device_count {
-                let device = match device_collection.Item(device_index) {
-                    Ok(dev) => dev,
-                    Err(_) => continue,
-                };
+                let Ok(device) = device_collection.Item(device_index) else { continue };
+                let Ok(session_manager): std::result::Result<IAudioSessionManager2, _> = device.Activate(CLSCTX_ALL, None) else { continue };
+                let Ok(session_enum) = session_manager.GetSessionEnumerator() else { continue };
+                let count = session_enum.GetCount().unwrap_or(0);
 
-                let session_manager: IAudioSessionManager2 = match device.Activate(CLSCTX_ALL, None) {
-                    Ok(mgr) => mgr,
-                    Err(_) => continue,
-                };
+                for session_index in 0..count {
+                    let Ok(session_control) = session_enum.GetSession(session_index) else { continue };
+                    let Ok(session_control2) = session_control.cast::<IAudioSessionControl2>() else { continue };
+                    let Ok(instance_id) = session_control2.GetSessionInstanceIdentifier() else { continue };
+                    let found_id = instance_id.to_string().unwrap_or_default();
+                    CoTaskMemFree(Some(instance_id.0 as *const core::ffi::c_void));
+
+                    if found_id == session_id {
+                        let device_id = device.GetId().ok().and_then(|p| {
+                            let s = p.to_string().ok();
+                            CoTaskMemFree(Some(p.0 as *const core::ffi::c_void));
+                            s
+                        });
+                        return device_id.ok_or_else(|| "Failed to read device ID".to_string());
+                    }
+                }
+            }
+        }
 
-                let session_enum = match session_manager.GetSessionEnumerator() {
-                    Ok(enumerator) => enumerator,
-                    Err(_) => continue,
+        Err(format!("No active render session found with ID {}", session_id))
+    }
+
+    /// Start the experimental session-duplication bridge: loopback-capture the render endpoint
+    /// currently hosting `session_id` and render a copy of its mix to `target_device_id`, for
+    /// streamers who want the same audio in their headset and captured into OBS at once.
+    ///
+    /// WASAPI loopback only exposes a render endpoint's whole mix, not one session in
+    /// isolation - there's no lower-level API that isolates a single app's stream for
+    /// rerouting - so this duplicates *everything* currently playing through that endpoint,
+    /// not just `session_id`. No sample-rate conversion is attempted either; source and target
+    /// running at different rates will drift or sound off-pitch. Both are acceptable for an
+    /// experimental, opt-in feature but not something ClearComms would ever turn on by default.
+    /// Starting a new bridge replaces any bridge already running, via the same generation
+    /// idiom `control_server` uses to retire a superseded accept loop.
+    pub fn duplicate_session_to_device(session_id: &str, target_device_id: &str) -> std::result::Result<(), String> {
+        let source_device_id = Self::find_session_device_id(session_id)?;
+        let target_device_id = target_device_id.to_string();
+        let generation = DUPLICATE_BRIDGE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+        thread::spawn(move || {
+            if let Err(e) = Self::run_duplicate_bridge(&source_device_id, &target_device_id, generation) {
+                tracing::warn!("[Audio] Session duplication bridge stopped: {}", e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stop the experimental session-duplication bridge, if one is running. The bridge thread
+    /// notices its generation is stale on its next loop iteration and exits on its own; this
+    /// just guarantees no further work is attributed to a bridge the caller thinks is stopped.
+    pub fn stop_session_duplication() {
+        DUPLICATE_BRIDGE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Background loop backing `duplicate_session_to_device` - see its doc comment for the
+    /// loopback-capture + render approach and its limitations. Runs until either stream
+    /// errors or `generation` no longer matches `DUPLICATE_BRIDGE_GENERATION`, i.e. a newer
+    /// call has superseded this one.
+    fn run_duplicate_bridge(source_device_id: &str, target_device_id: &str, generation: u64) -> std::result::Result<(), String> {
+        ensure_com_initialized()?;
+
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
+
+            let source_id_wide = to_wide(source_device_id);
+            let source_device = enumerator
+                .GetDevice(PCWSTR(source_id_wide.as_ptr()))
+                .map_err(|e: Error| format!("Failed to open source device {}: {}", source_device_id, e))?;
+            let source_client: IAudioClient = source_device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e: Error| format!("Failed to activate source loopback client: {}", e))?;
+            let source_format = source_client
+                .GetMixFormat()
+                .map_err(|e: Error| format!("Failed to get source mix format: {}", e))?;
+            source_client
+                .Initialize(AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_LOOPBACK, 5_000_000, 0, source_format, None)
+                .map_err(|e: Error| format!("Failed to initialise source loopback client: {}", e))?;
+            let capture_client: IAudioCaptureClient = source_client
+                .GetService()
+                .map_err(|e: Error| format!("Failed to get capture client service: {}", e))?;
+
+            let target_id_wide = to_wide(target_device_id);
+            let target_device = enumerator
+                .GetDevice(PCWSTR(target_id_wide.as_ptr()))
+                .map_err(|e: Error| format!("Failed to open target device {}: {}", target_device_id, e))?;
+            let target_client: IAudioClient = target_device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e: Error| format!("Failed to activate target render client: {}", e))?;
+            let target_format = target_client
+                .GetMixFormat()
+                .map_err(|e: Error| format!("Failed to get target mix format: {}", e))?;
+            target_client
+                .Initialize(AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_NOPERSIST, 5_000_000, 0, target_format, None)
+                .map_err(|e: Error| format!("Failed to initialise target render client: {}", e))?;
+            let target_buffer_frames = target_client
+                .GetBufferSize()
+                .map_err(|e: Error| format!("Failed to get target buffer size: {}", e))?;
+            let render_client: IAudioRenderClient = target_client
+                .GetService()
+                .map_err(|e: Error| format!("Failed to get render client service: {}", e))?;
+
+            let source_channels = (*source_format).nChannels as usize;
+            let target_channels = (*target_format).nChannels as usize;
+            let source_is_float = (*source_format).wBitsPerSample == 32;
+            let target_is_float = (*target_format).wBitsPerSample == 32;
+            let shared_channels = source_channels.min(target_channels);
+            let target_bytes_per_frame = target_channels * if target_is_float { 4 } else { 2 };
+
+            source_client.Start().map_err(|e: Error| format!("Failed to start loopback capture: {}", e))?;
+            target_client.Start().map_err(|e: Error| format!("Failed to start bridge render: {}", e))?;
+
+            let result = loop {
+                if DUPLICATE_BRIDGE_GENERATION.load(Ordering::SeqCst) != generation {
+                    break Ok(());
+                }
+
+                let packet_size = match capture_client.GetNextPacketSize() {
+                    Ok(size) => size,
+                    Err(e) => break Err(format!("Loopback capture failed: {}", e)),
                 };
 
-                let count = session_enum.GetCount().unwrap_or(0);
+                if packet_size == 0 {
+                    thread::sleep(Duration::from_millis(DUPLICATE_BRIDGE_POLL_MS));
+                    continue;
+                }
 
-                for i in 0..count {
-                    if let Ok(session_control) = session_enum.GetSession(i) {
-                        if let Ok(session_control2) = session_control.cast::<IAudioSessionControl2>() {
-                            let process_id = session_control2
-                                .GetProcessId()
-                                .unwrap_or(0);
-
-                            // Apply mute to ALL sessions with matching process_id
-                            if process_id == target_process_id {
-                                if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
-                                    let _ = simple_volume.SetMute(BOOL(muted as i32), std::ptr::null());
-                                    updated_count += 1;
-                                }
+                let mut data = std::ptr::null_mut();
+                let mut frames = 0u32;
+                let mut flags = 0u32;
+                if let Err(e) = capture_client.GetBuffer(&mut data, &mut frames, &mut flags, None, None) {
+                    break Err(format!("Failed to get capture buffer: {}", e));
+                }
+
+                let silent = flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0;
+
+                // Wait for room in the target's buffer before writing the whole packet at once -
+                // same "poll padding, then GetBuffer" approach as `play_test_tone`. Also bail out
+                // on a stale generation here, not just in the outer loop - otherwise a target
+                // buffer that stays backed up keeps this thread (and its COM/device handles)
+                // alive forever past the point `stop_session_duplication` thinks it stopped.
+                while target_buffer_frames.saturating_sub(target_client.GetCurrentPadding().unwrap_or(0)) < frames {
+                    if DUPLICATE_BRIDGE_GENERATION.load(Ordering::SeqCst) != generation {
+                        break;
+                    }
+                    thread::sleep(Duration::from_millis(DUPLICATE_BRIDGE_POLL_MS));
+                }
+
+                if DUPLICATE_BRIDGE_GENERATION.load(Ordering::SeqCst) != generation {
+                    break Ok(());
+                }
+
+                let render_result = render_client.GetBuffer(frames).and_then(|render_buffer| {
+                    if silent || source_is_float != target_is_float {
+                        // A silent packet, or mismatched float/int mix formats between source
+                        // and target (rare - both are almost always 32-bit float in shared
+                        // mode) - not worth a full sample-format converter for an experimental
+                        // bridge, so just render silence rather than garbage samples.
+                        std::ptr::write_bytes(render_buffer, 0, frames as usize * target_bytes_per_frame);
+                    } else if target_is_float {
+                        let src = std::slice::from_raw_parts(data as *const f32, frames as usize * source_channels);
+                        let dst = std::slice::from_raw_parts_mut(render_buffer as *mut f32, frames as usize * target_channels);
+                        for frame in 0..frames as usize {
+                            for ch in 0..target_channels {
+                                dst[frame * target_channels + ch] = if ch < shared_channels { src[frame * source_channels + ch] } else { 0.0 };
+                            }
+                        }
+                    } else {
+                        let src = std::slice::from_raw_parts(data as *const i16, frames as usize * source_channels);
+                        let dst = std::slice::from_raw_parts_mut(render_buffer as *mut i16, frames as usize * target_channels);
+                        for frame in 0..frames as usize {
+                            for ch in 0..target_channels {
+                                dst[frame * target_channels + ch] = if ch < shared_channels { src[frame * source_channels + ch] } else { 0 };
                             }
                         }
                     }
+                    render_client.ReleaseBuffer(frames, 0)
+                });
+
+                let _ = capture_client.ReleaseBuffer(frames);
+
+                if let Err(e) = render_result {
+                    break Err(format!("Failed to write bridge render buffer: {}", e));
                 }
-            } // End device loop
+            };
 
-            // Update cache for the requested session
-            if let Some(session) = self.sessions.get_mut(session_id) {
-                session.is_muted = muted;
-            }
+            let _ = source_client.Stop();
+            let _ = target_client.Stop();
+            CoTaskMemFree(Some(source_format as *const core::ffi::c_void));
+            CoTaskMemFree(Some(target_format as *const core::ffi::c_void));
 
-            if updated_count > 0 {
-                Ok(())
-            } else {
-                Err(format!("No sessions found for process_id: {}", target_process_id))
-            }
+            result
         }
     }
-}
 
-#[cfg(not(windows))]
-impl AudioManager {
-    pub fn new() -> std::result::Result<Self, String> {
-        Err("Audio manager only supported on Windows".to_string())
-    }
+    /// Render a short sine-wave test tone directly to the given output endpoint, using its
+    /// shared-mode mix format. Useful for calibrating relative levels between devices (e.g.
+    /// headset vs speakers) without needing an application session to drive volume through.
+    pub fn play_test_tone(device_id: &str, frequency_hz: f32, seconds: f32, level: f32) -> std::result::Result<(), String> {
+        let level = level.clamp(0.0, 1.0);
+        let seconds = seconds.max(0.0);
 
-    pub fn enumerate_sessions(&mut self) -> std::result::Result<Vec<AudioSession>, String> {
-        Err("Audio manager only supported on Windows".to_string())
-    }
+        ensure_com_initialized()?;
 
-    pub fn set_session_volume(&mut self, _session_id: &str, _volume: f32) -> std::result::Result<(), String> {
-        Err("Audio manager only supported on Windows".to_string())
-    }
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(
+                &MMDeviceEnumerator,
+                None,
+                CLSCTX_ALL,
+            ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
-    pub fn set_session_mute(&mut self, _session_id: &str, _muted: bool) -> std::result::Result<(), String> {
-        Err("Audio manager only supported on Windows".to_string())
-    }
-}
+            let device_id_wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+            let device = enumerator
+                .GetDevice(PCWSTR(device_id_wide.as_ptr()))
+                .map_err(|e: Error| format!("Failed to open device {}: {}", device_id, e))?;
 
-#[cfg(windows)]
-impl AudioManager {
-    /// Explicit cleanup method for proper resource management
-    pub fn cleanup(&mut self) {
-        tracing::info!("[Audio] Cleaning up audio manager resources...");
-        
-        // Clear internal caches
-        self.sessions.clear();
-        // Release memory back to the system
-        self.sessions.shrink_to_fit();
-        
-        // Reset counters
-        self.enumerate_calls = 0;
-        self.last_logged_counts = None;
-        
-        // Reset device ID to release string memory
-        self.current_device_id = String::new();
-        
-        tracing::info!("[Audio] Audio manager cleanup complete");
-    }
-}
+            let client: IAudioClient = device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e: Error| format!("Failed to activate render client: {}", e))?;
+
+            let mix_format = client
+                .GetMixFormat()
+                .map_err(|e: Error| format!("Failed to get mix format: {}", e))?;
+
+            client
+                .Initialize(
+                    AUDCLNT_SHAREMODE_SHARED,
+                    AUDCLNT_STREAMFLAGS_NOPERSIST,
+                    5_000_000, // 500ms buffer, in 100ns units
+                    0,
+                    mix_format,
+                    None,
+                )
+                .map_err(|e: Error| format!("Failed to initialise render client: {}", e))?;
+
+            let buffer_frame_count = client
+                .GetBufferSize()
+                .map_err(|e: Error| format!("Failed to get buffer size: {}", e))?;
+
+            let render_client: IAudioRenderClient = client
+                .GetService()
+                .map_err(|e: Error| format!("Failed to get render client service: {}", e))?;
+
+            let format = &*mix_format;
+            let channels = format.nChannels as usize;
+            let sample_rate = format.nSamplesPerSec as f32;
+            // Shared-mode mix formats are almost always 32-bit IEEE float; fall back to 16-bit PCM otherwise.
+            let is_float = format.wBitsPerSample == 32;
+
+            let total_frames = (sample_rate * seconds) as u32;
+            let phase_step = 2.0 * std::f32::consts::PI * frequency_hz / sample_rate;
+            let mut phase: f32 = 0.0;
+            let mut frames_written: u32 = 0;
+
+            client.Start().map_err(|e: Error| format!("Failed to start render client: {}", e))?;
+
+            while frames_written < total_frames {
+                let padding = client.GetCurrentPadding().unwrap_or(0);
+                let available = buffer_frame_count.saturating_sub(padding);
+                let remaining = total_frames - frames_written;
+                let frames_to_write = available.min(remaining);
+
+                if frames_to_write == 0 {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    continue;
+                }
 
-impl Drop for AudioManager {
-    fn drop(&mut self) {
-        #[cfg(windows)]
-        {
-            tracing::debug!("[Audio] Dropping audio manager...");
-            self.cleanup();
-            unsafe {
-                CoUninitialize();
+                let buffer = render_client
+                    .GetBuffer(frames_to_write)
+                    .map_err(|e: Error| format!("Failed to get render buffer: {}", e))?;
+
+                if is_float {
+                    let samples = std::slice::from_raw_parts_mut(buffer as *mut f32, frames_to_write as usize * channels);
+                    for frame in samples.chunks_mut(channels) {
+                        let sample = phase.sin() * level;
+                        frame.iter_mut().for_each(|s| *s = sample);
+                        phase += phase_step;
+                    }
+                } else {
+                    let samples = std::slice::from_raw_parts_mut(buffer as *mut i16, frames_to_write as usize * channels);
+                    for frame in samples.chunks_mut(channels) {
+                        let sample = (phase.sin() * level * i16::MAX as f32) as i16;
+                        frame.iter_mut().for_each(|s| *s = sample);
+                        phase += phase_step;
+                    }
+                }
+
+                render_client
+                    .ReleaseBuffer(frames_to_write, 0)
+                    .map_err(|e: Error| format!("Failed to release render buffer: {}", e))?;
+
+                frames_written += frames_to_write;
             }
-            tracing::debug!("[Audio] Audio manager dropped");
+
+            // Let the final buffer drain before tearing the stream down.
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            let _ = client.Stop();
+
+            CoTaskMemFree(Some(mix_format as *const core::ffi::c_void));
+
+            Ok(())
         }
     }
-}
 
-// Global audio manager instance
-static AUDIO_MANAGER: Mutex<Option<AudioManager>> = Mutex::new(None);
+    /// Query stream health diagnostics for an endpoint, reusing the same `IAudioClient`
+    /// activation path as the test tone / endpoint-format features. Reports latency and
+    /// format as negotiated by `Initialize`; `underrun_count` is `None` because tracking
+    /// glitches requires a persistently running stream, which this short-lived probe isn't.
+    pub fn get_diagnostics(device_id: Option<&str>) -> std::result::Result<AudioDiagnostics, String> {
+        ensure_com_initialized()?;
 
-/// Initialize the audio manager
-#[tauri::command]
-pub fn init_audio_manager() -> std::result::Result<String, String> {
-    tracing::info!("[Audio] Initialising audio manager...");
-    let manager = AudioManager::new()?;
-    
-    let mut lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
-    *lock = Some(manager);
-    
-    tracing::info!("[Audio] Audio manager ready");
-    Ok("Audio manager initialised successfully".to_string())
-}
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(
+                &MMDeviceEnumerator,
+                None,
+                CLSCTX_ALL,
+            ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
 
-/// Get all active audio sessions
-#[tauri::command]
-pub fn get_audio_sessions() -> std::result::Result<Vec<AudioSession>, String> {
-    let mut lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
-    let manager = lock
-        .as_mut()
-        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
-    manager.enumerate_sessions()
-}
+            let device = match device_id {
+                Some(id) => {
+                    let id_wide: Vec<u16> = id.encode_utf16().chain(std::iter::once(0)).collect();
+                    enumerator
+                        .GetDevice(PCWSTR(id_wide.as_ptr()))
+                        .map_err(|e: Error| format!("Failed to open device {}: {}", id, e))?
+                }
+                None => enumerator
+                    .GetDefaultAudioEndpoint(eRender, eConsole)
+                    .map_err(|e: Error| format!("Failed to get default audio endpoint: {}", e))?,
+            };
 
-/// Set volume for a specific audio session
+            let resolved_device_id = device
+                .GetId()
+                .ok()
+                .and_then(|p| {
+                    let s = p.to_string().ok();
+                    CoTaskMemFree(Some(p.0 as *const core::ffi::c_void));
+                    s
+                })
+                .unwrap_or_default();
+
+            let client: IAudioClient = device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e: Error| format!("Failed to activate audio client: {}", e))?;
+
+            let mix_format = client
+                .GetMixFormat()
+                .map_err(|e: Error| format!("Failed to get mix format: {}", e))?;
+
+            client
+                .Initialize(
+                    AUDCLNT_SHAREMODE_SHARED,
+                    AUDCLNT_STREAMFLAGS_NOPERSIST,
+                    5_000_000,
+                    0,
+                    mix_format,
+                    None,
+                )
+                .map_err(|e: Error| format!("Failed to initialise audio client: {}", e))?;
+
+            let format = &*mix_format;
+            let sample_rate = format.nSamplesPerSec;
+            let channels = format.nChannels;
+            let bits_per_sample = format.wBitsPerSample;
+
+            let buffer_size_frames = client
+                .GetBufferSize()
+                .map_err(|e: Error| format!("Failed to get buffer size: {}", e))?;
+
+            let latency_ref_time = client
+                .GetStreamLatency()
+                .map_err(|e: Error| format!("Failed to get stream latency: {}", e))?;
+
+            CoTaskMemFree(Some(mix_format as *const core::ffi::c_void));
+
+            Ok(AudioDiagnostics {
+                device_id: resolved_device_id,
+                sample_rate,
+                channels,
+                bits_per_sample,
+                buffer_size_frames,
+                // GetStreamLatency is in 100ns units.
+                stream_latency_ms: latency_ref_time as f64 / 10_000.0,
+                underrun_count: None,
+            })
+        }
+    }
+
+    /// Activate a capture device's topology object and collect every part reachable from
+    /// its connectors by walking `EnumPartsIncoming`. Topologies are small (a handful of
+    /// parts per device), so this doesn't bother with a cycle guard.
+    fn capture_topology_parts(device_id: &str) -> std::result::Result<Vec<IPart>, String> {
+        ensure_com_initialized()?;
+
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(
+                &MMDeviceEnumerator,
+                None,
+                CLSCTX_ALL,
+            ).map_err(|e: Error| format!("Failed to create device enumerator: {}", e))?;
+
+            let id_wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+            let device = enumerator
+                .GetDevice(PCWSTR(id_wide.as_ptr()))
+                .map_err(|e: Error| format!("Failed to open device {}: {}", device_id, e))?;
+
+            let topology: IDeviceTopology = device
+                .Activate(CLSCTX_ALL, None)
+                .map_err(|e: Error| format!("Failed to activate device topology: {}", e))?;
+
+            let connector_count = topology
+                .GetConnectorCount()
+                .map_err(|e: Error| format!("Failed to get connector count: {}", e))?;
+
+            let mut parts = Vec::new();
+            for i in 0..connector_count {
+                let Ok(connector) = topology.GetConnector(i) else { continue };
+                let Ok(part) = connector.cast::<IPart>() else { continue };
+                Self::collect_parts_incoming(&part, &mut parts);
+            }
+
+            Ok(parts)
+        }
+    }
+
+    /// Depth-first collection of `part` and everything upstream of it.
+    unsafe fn collect_parts_incoming(part: &IPart, out: &mut Vec<IPart>) {
+        out.push(part.clone());
+        let Ok(incoming) = part.EnumPartsIncoming() else { return };
+        let Ok(count) = incoming.GetCount() else { return };
+        for i in 0..count {
+            if let Ok(next) = incoming.GetPart(i) {
+                Self::collect_parts_incoming(&next, out);
+            }
+        }
+    }
+
+    /// Find the first topology part exposing the given control interface IID.
+    unsafe fn find_part_with_control_interface(parts: &[IPart], iid: &GUID) -> Option<IPart> {
+        for part in parts {
+            let Ok(count) = part.GetControlInterfaceCount() else { continue };
+            for i in 0..count {
+                if let Ok(control) = part.GetControlInterface(i) {
+                    if control.GetIID().map(|g| g == *iid).unwrap_or(false) {
+                        return Some(part.clone());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Report which of {boost, agc, noise_suppression} a capture device's driver exposes
+    /// through its topology parts. Driver-dependent: plenty of capture devices support none
+    /// of these beyond the plain capture volume/mute already covered by `IAudioEndpointVolume`.
+    pub fn get_capture_features(device_id: &str) -> std::result::Result<CaptureFeatureSupport, String> {
+        let parts = Self::capture_topology_parts(device_id)?;
+
+        unsafe {
+            let agc = Self::find_part_with_control_interface(&parts, &IAudioAutoGainControl::IID).is_some();
+
+            // There's no dedicated "mic boost" COM interface in the public Core Audio API;
+            // boost is typically implemented as an extra IAudioVolumeLevel gain stage. Treat
+            // any part whose name mentions "boost" and exposes that interface as support.
+            let boost = parts.iter().any(|part| {
+                let name_matches = part.GetName()
+                    .ok()
+                    .and_then(|p| {
+                        let s = p.to_string().ok();
+                        CoTaskMemFree(Some(p.0 as *const core::ffi::c_void));
+                        s
+                    })
+                    .map(|name| name.to_lowercase().contains("boost"))
+                    .unwrap_or(false);
+
+                name_matches && Self::find_part_with_control_interface(std::slice::from_ref(part), &IAudioVolumeLevel::IID).is_some()
+            });
+
+            // Noise suppression has no generic topology representation either, and unlike
+            // boost there isn't even a reliable naming convention to heuristically match on -
+            // drivers that support it almost always do so via a proprietary APO instead.
+            let noise_suppression = false;
+
+            Ok(CaptureFeatureSupport { boost, agc, noise_suppression })
+        }
+    }
+
+    /// Toggle a capture-chain feature via its topology part, returning a clear "unsupported"
+    /// error (rather than a raw COM failure) when the driver doesn't expose a matching part.
+    pub fn set_capture_feature(device_id: &str, feature: CaptureFeature, value: bool) -> std::result::Result<(), String> {
+        let parts = Self::capture_topology_parts(device_id)?;
+
+        unsafe {
+            match feature {
+                CaptureFeature::Agc => {
+                    let part = Self::find_part_with_control_interface(&parts, &IAudioAutoGainControl::IID)
+                        .ok_or("This device's driver doesn't expose an AGC control")?;
+
+                    let mut raw: *mut core::ffi::c_void = std::ptr::null_mut();
+                    part.Activate(CLSCTX_ALL.0 as u32, &IAudioAutoGainControl::IID, Some(&mut raw))
+                        .map_err(|e: Error| format!("Failed to activate AGC control: {}", e))?;
+                    let agc: IAudioAutoGainControl = Interface::from_raw(raw);
+
+                    agc.SetEnabled(BOOL(value as i32), None)
+                        .map_err(|e: Error| format!("Failed to set AGC state: {}", e))
+                }
+                CaptureFeature::Boost => {
+                    let part = parts.iter().find(|part| {
+                        part.GetName()
+                            .ok()
+                            .and_then(|p| {
+                                let s = p.to_string().ok();
+                                CoTaskMemFree(Some(p.0 as *const core::ffi::c_void));
+                                s
+                            })
+                            .map(|name| name.to_lowercase().contains("boost"))
+                            .unwrap_or(false)
+                    }).ok_or("This device's driver doesn't expose a mic boost control")?;
+
+                    let mut raw: *mut core::ffi::c_void = std::ptr::null_mut();
+                    part.Activate(CLSCTX_ALL.0 as u32, &IAudioVolumeLevel::IID, Some(&mut raw))
+                        .map_err(|e: Error| format!("This device's driver doesn't expose a mic boost control: {}", e))?;
+                    let volume_level: IAudioVolumeLevel = Interface::from_raw(raw);
+
+                    let mut min_db = 0.0f32;
+                    let mut max_db = 0.0f32;
+                    let mut step_db = 0.0f32;
+                    volume_level.GetLevelRange(0, &mut min_db, &mut max_db, &mut step_db)
+                        .map_err(|e: Error| format!("Failed to read boost level range: {}", e))?;
+
+                    volume_level.SetLevelUniform(if value { max_db } else { min_db }, None)
+                        .map_err(|e: Error| format!("Failed to set boost level: {}", e))
+                }
+                CaptureFeature::NoiseSuppression => {
+                    Err("This device's driver doesn't expose a generic noise suppression control".to_string())
+                }
+            }
+        }
+    }
+
+    /// Toggle "Allow applications to take exclusive control of this device" (Sound Control
+    /// Panel, device Properties > Advanced) for an endpoint. There's no `IPropertyStore` key
+    /// or public API for this checkbox - it's a registry-only setting, at `FxProperties\\
+    /// {EXCLUSIVE_MODE_VALUE_NAME}` under the endpoint's `MMDevices` key, same as the Control
+    /// Panel itself writes. That key lives under `HKEY_LOCAL_MACHINE`, so this needs
+    /// ClearComms running elevated - an unelevated process gets the same access-denied error
+    /// editing it by hand would.
+    pub fn set_exclusive_mode_allowed(device_id: &str, allowed: bool) -> std::result::Result<(), String> {
+        use windows::Win32::System::Registry::{
+            RegCloseKey, RegOpenKeyExW, RegSetValueExW, HKEY, HKEY_LOCAL_MACHINE, KEY_SET_VALUE, REG_DWORD,
+        };
+
+        let subkeys = exclusive_mode_fx_properties_subkeys(device_id)
+            .ok_or_else(|| format!("Couldn't find a device GUID in '{}'", device_id))?;
+
+        let value: u32 = allowed as u32;
+        let mut last_error = "This device has no FxProperties registry key for exclusive mode".to_string();
+
+        for subkey in &subkeys {
+            let subkey_wide = to_wide(subkey);
+            let mut hkey = HKEY::default();
+
+            let open_result = unsafe {
+                RegOpenKeyExW(HKEY_LOCAL_MACHINE, PCWSTR(subkey_wide.as_ptr()), 0, KEY_SET_VALUE, &mut hkey)
+            };
+            if open_result.is_err() {
+                continue;
+            }
+
+            let value_name_wide = to_wide(EXCLUSIVE_MODE_VALUE_NAME);
+            let set_result = unsafe {
+                RegSetValueExW(hkey, PCWSTR(value_name_wide.as_ptr()), 0, REG_DWORD, Some(&value.to_le_bytes()))
+            };
+            unsafe { let _ = RegCloseKey(hkey); }
+
+            match set_result.ok() {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = format!("Failed to write exclusive mode setting under '{}': {}", subkey, e),
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Current state of the "Allow applications to take exclusive control of this device"
+    /// checkbox - see `set_exclusive_mode_allowed`.
+    pub fn get_exclusive_mode_allowed(device_id: &str) -> std::result::Result<bool, String> {
+        use windows::Win32::System::Registry::{
+            RegCloseKey, RegOpenKeyExW, RegQueryValueExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ, REG_DWORD,
+        };
+
+        let subkeys = exclusive_mode_fx_properties_subkeys(device_id)
+            .ok_or_else(|| format!("Couldn't find a device GUID in '{}'", device_id))?;
+
+        for subkey in &subkeys {
+            let subkey_wide = to_wide(subkey);
+            let mut hkey = HKEY::default();
+
+            if unsafe { RegOpenKeyExW(HKEY_LOCAL_MACHINE, PCWSTR(subkey_wide.as_ptr()), 0, KEY_READ, &mut hkey) }.is_err() {
+                continue;
+            }
+
+            let value_name_wide = to_wide(EXCLUSIVE_MODE_VALUE_NAME);
+            let mut data: u32 = 0;
+            let mut data_size = std::mem::size_of::<u32>() as u32;
+            let mut data_type = REG_DWORD;
+
+            let result = unsafe {
+                RegQueryValueExW(
+                    hkey,
+                    PCWSTR(value_name_wide.as_ptr()),
+                    None,
+                    Some(&mut data_type),
+                    Some(&mut data as *mut u32 as *mut u8),
+                    Some(&mut data_size),
+                )
+            };
+            unsafe { let _ = RegCloseKey(hkey); }
+
+            if result.is_ok() {
+                return Ok(data != 0);
+            }
+        }
+
+        Err("This device has no FxProperties registry key for exclusive mode".to_string())
+    }
+
+    /// Probe runtime feature support - see `Capabilities`'s field docs for what each one
+    /// means and how it's determined. Never fails: a probe that errors (no mic connected, no
+    /// `FxProperties` key on this device) just reports that capability as unsupported rather
+    /// than failing the whole call, since the frontend needs an answer for every field either way.
+    pub fn capabilities() -> Capabilities {
+        let per_app_routing = windows_build_number().map(|build| build >= 10240).unwrap_or(true);
+
+        let mic_boost = Self::default_capture_device_id()
+            .and_then(|id| Self::get_capture_features(&id))
+            .map(|support| support.boost)
+            .unwrap_or(false);
+
+        let exclusive_mode_toggle = Self::get_default_device_id()
+            .and_then(|id| Self::get_exclusive_mode_allowed(&id))
+            .is_ok();
+
+        Capabilities {
+            per_app_routing,
+            capture_control: Self::enumerate_capture_sessions().is_ok(),
+            mic_boost,
+            exclusive_mode_toggle,
+            simconnect_available: false,
+        }
+    }
+
+    /// Run `visit` against every audio session control across all active endpoints for
+    /// `data_flow` (`eRender` for playback sessions, `eCapture` for recording sessions, e.g.
+    /// an app's mic input). Centralises the enumerator/device-collection/session-manager
+    /// acquisition that `enumerate_sessions`, `set_session_volume`, and `set_session_mute`
+    /// each used to repeat, so there's a single place that can leak an unreleased COM
+    /// reference on an early return instead of three. The `windows` crate's generated
+    /// interfaces release themselves via `Drop` as each device/session iteration's locals go
+    /// out of scope, so nothing here needs an explicit `Release` call; callers still own any
+    /// unsafe work on the controls they're handed, since per-session details (volume, mute,
+    /// peak) vary by call site.
+    ///
+    /// Despite the similarity to "default session manager", this walks every active endpoint
+    /// of the given flow, not just the system default — sessions can live on any of them. That
+    /// also means `enumerate_sessions`/`enumerate_capture_sessions` already see a comms app's
+    /// session even when its device is only the `eCommunications` default and not `eConsole` -
+    /// `DeviceRole` only matters for the commands that target "the" default device directly
+    /// (`get_default_device`, `get_system_volume`/`get_system_mute` and their setters).
+    fn for_each_session_control<F>(data_flow: EDataFlow, mut visit: F) -> std::result::Result<(), String>
+    where
+        F: FnMut(&IAudioSessionControl, &IAudioSessionControl2, u32, &str),
+    {
+        ensure_com_initialized()?;
+
+        unsafe {
+            let enumerator: IMMDeviceEnumerator = CoCreateInstance(
+                &MMDeviceEnumerator,
+                None,
+                CLSCTX_ALL,
+            ).map_err(|e: Error| format!("Failed to create device enumerator: {} (0x{:08X})", e, e.code().0 as u32))?;
+
+            let device_collection = enumerator
+                .EnumAudioEndpoints(data_flow, DEVICE_STATE_ACTIVE)
+                .map_err(|e: Error| format!("Failed to enumerate audio endpoints: {} (0x{:08X})", e, e.code().0 as u32))?;
+
+            let device_count = device_collection
+                .GetCount()
+                .map_err(|e: Error| format!("Failed to get device count: {} (0x{:08X})", e, e.code().0 as u32))?;
+
+            for device_index in 0..device_count {
+                let device = match device_collection.Item(device_index) {
+                    Ok(dev) => dev,
+                    Err(_) => continue, // Skip devices we can't access
+                };
+
+                let session_manager: IAudioSessionManager2 = match device.Activate(CLSCTX_ALL, None) {
+                    Ok(mgr) => mgr,
+                    Err(_) => continue, // Skip if we can't get session manager
+                };
+
+                let session_enum = match session_manager.GetSessionEnumerator() {
+                    Ok(enumerator) => enumerator,
+                    Err(_) => continue,
+                };
+
+                let count = match session_enum.GetCount() {
+                    Ok(c) => c,
+                    Err(_) => continue,
+                };
+
+                // Resolved once per device rather than per session - every session on this
+                // device shares it, and it's what lets `set_session_volume`/`set_session_mute`
+                // scope a pinned session to the device it's actually meant to stay on (see
+                // `settings::device_pins`) instead of whichever endpoint it happens to be
+                // enumerated from first.
+                let device_id = device.GetId().ok().and_then(|p| {
+                    let s = p.to_string().ok();
+                    CoTaskMemFree(Some(p.0 as *const core::ffi::c_void));
+                    s
+                }).unwrap_or_default();
+
+                for session_index in 0..count {
+                    if let Ok(session_control) = session_enum.GetSession(session_index) {
+                        if let Ok(session_control2) = session_control.cast::<IAudioSessionControl2>() {
+                            visit(&session_control, &session_control2, session_index as u32, &device_id);
+                        }
+                    }
+                }
+            } // End device loop
+
+            Ok(())
+        }
+    }
+
+    /// True for HRESULTs worth a short retry instead of surfacing immediately —
+    /// currently just `AUDCLNT_E_DEVICE_INVALIDATED` (0x88890004), which shows up
+    /// for a brief window while Windows is switching the default render device.
+    fn is_transient_com_error(err: &str) -> bool {
+        err.contains("0x88890004")
+    }
+
+    /// Retries `op` up to `TRANSIENT_RETRY_ATTEMPTS` times with a short linear
+    /// backoff when it fails with a transient COM error. Each retry re-runs `op`
+    /// from scratch, so callers built on `for_each_session_control` naturally
+    /// re-acquire the enumerator rather than reusing anything that may have gone
+    /// stale. Non-transient errors, and the final attempt's error, are returned
+    /// as-is.
+    fn retry_transient<T>(mut op: impl FnMut() -> std::result::Result<T, String>) -> std::result::Result<T, String> {
+        for attempt in 1..=TRANSIENT_RETRY_ATTEMPTS {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < TRANSIENT_RETRY_ATTEMPTS && Self::is_transient_com_error(&e) => {
+                    tracing::warn!(
+                        "[Audio] Transient COM error, retrying ({}/{}): {}",
+                        attempt, TRANSIENT_RETRY_ATTEMPTS, e
+                    );
+                    std::thread::sleep(std::time::Duration::from_millis(TRANSIENT_RETRY_BACKOFF_MS * attempt as u64));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("loop always returns on its last iteration")
+    }
+
+    /// Build an `AudioSession` from one session control, or `None` for a system session
+    /// (`process_id` 0) that shouldn't be reported at all. Shared by `enumerate_sessions` and
+    /// `enumerate_capture_sessions`, which differ only in which endpoints they walk and what
+    /// they do with the result (sorting/caching/eventing vs. a plain one-shot list).
+    unsafe fn resolve_audio_session(
+        session_control: &IAudioSessionControl,
+        session_control2: &IAudioSessionControl2,
+        session_index: u32,
+        device_id: &str,
+    ) -> Option<AudioSession> {
+        let process_id = session_control2.GetProcessId().unwrap_or(0);
+
+        // Skip system sessions (process_id 0)
+        if process_id == 0 {
+            return None;
+        }
+
+        let session_id = match session_control2.GetSessionInstanceIdentifier() {
+            Ok(pwstr) => {
+                let s = pwstr.to_string()
+                    .unwrap_or_else(|_| format!("session_{}", session_index));
+                // Free COM-allocated PWSTR to prevent memory leak
+                CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
+                s
+            }
+            Err(_) => format!("session_{}", session_index),
+        };
+
+        let display_name = match session_control2.GetDisplayName() {
+            Ok(pwstr) => {
+                let s = pwstr.to_string()
+                    .unwrap_or_else(|_| format!("Process {}", process_id));
+                // Free COM-allocated PWSTR to prevent memory leak
+                CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
+                s
+            }
+            Err(_) => format!("Process {}", process_id),
+        };
+
+        // Get the actual process executable name
+        let process_name = get_process_name(process_id);
+
+        // Get volume control. Some sessions (certain system or exclusive-mode apps) don't
+        // expose `ISimpleAudioVolume` at all - they're still reported, just flagged as
+        // `controllable: false`, so the UI can show them greyed out instead of them
+        // silently vanishing from the list.
+        let controllable_volume = session_control.cast::<ISimpleAudioVolume>().ok();
+
+        let (volume, is_muted) = match &controllable_volume {
+            Some(simple_volume) => (
+                simple_volume.GetMasterVolume().unwrap_or(1.0),
+                simple_volume.GetMute().unwrap_or(BOOL(0)).as_bool(),
+            ),
+            None => (0.0, false),
+        };
+
+        let channel_count = session_control.cast::<IChannelAudioVolume>()
+            .ok()
+            .and_then(|channel_volume| channel_volume.GetChannelCount().ok())
+            .unwrap_or(DEFAULT_SESSION_CHANNEL_COUNT);
+
+        Some(AudioSession {
+            session_id,
+            display_name,
+            process_id,
+            process_name,
+            volume,
+            volume_percent: scalar_to_percent(volume),
+            is_muted,
+            controllable: controllable_volume.is_some(),
+            // Filled in by `enumerate_sessions` from its first-seen/last-active tracking;
+            // left at defaults here since `enumerate_capture_sessions` has no such cache.
+            age_ms: 0,
+            inactive_since_ms: None,
+            channel_count,
+            device_id: device_id.to_string(),
+        })
+    }
+
+    /// Dump every session the enumerator reports on both render and capture endpoints, with
+    /// nothing filtered - not even system sessions (`process_id` 0) or ones that fail the
+    /// `IAudioSessionControl2` cast `for_each_session_control` requires. Built as its own
+    /// walk rather than reusing `for_each_session_control`/`resolve_audio_session`, since
+    /// those two intentionally skip exactly what this needs to see. Not meant to be polled -
+    /// it's a one-shot "show me everything" for a support request, not a piece of the normal
+    /// enumeration path.
+    unsafe fn dump_sessions_for_flow(data_flow: EDataFlow, flow_label: &str, out: &mut Vec<DebugSessionDump>) {
+        let enumerator: IMMDeviceEnumerator = match CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) {
+            Ok(e) => e,
+            Err(_) => return,
+        };
+
+        let device_collection = match enumerator.EnumAudioEndpoints(data_flow, DEVICE_STATE_ACTIVE) {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+
+        let device_count = device_collection.GetCount().unwrap_or(0);
+
+        for device_index in 0..device_count {
+            let device = match device_collection.Item(device_index) {
+                Ok(dev) => dev,
+                Err(_) => continue,
+            };
+
+            let session_manager: IAudioSessionManager2 = match device.Activate(CLSCTX_ALL, None) {
+                Ok(mgr) => mgr,
+                Err(_) => continue,
+            };
+
+            let session_enum = match session_manager.GetSessionEnumerator() {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+
+            let count = session_enum.GetCount().unwrap_or(0);
+
+            for session_index in 0..count {
+                let session_control = match session_enum.GetSession(session_index) {
+                    Ok(s) => s,
+                    Err(_) => continue, // The one case this still can't report on - no control to dump at all.
+                };
+
+                let state = session_control.GetState().ok().map(|s| match s {
+                    AudioSessionStateActive => "Active".to_string(),
+                    AudioSessionStateInactive => "Inactive".to_string(),
+                    AudioSessionStateExpired => "Expired".to_string(),
+                    other => format!("Unknown({})", other.0),
+                });
+
+                let grouping_param = session_control.GetGroupingParam().ok().map(|g| format!("{:?}", g));
+
+                let control2 = session_control.cast::<IAudioSessionControl2>().ok();
+
+                let (session_id, instance_id, process_id) = match &control2 {
+                    Some(control2) => {
+                        let session_id = control2.GetSessionIdentifier().ok().and_then(|pwstr| {
+                            let s = pwstr.to_string().ok();
+                            CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
+                            s
+                        });
+                        let instance_id = control2.GetSessionInstanceIdentifier().ok().and_then(|pwstr| {
+                            let s = pwstr.to_string().ok();
+                            CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
+                            s
+                        });
+                        let process_id = control2.GetProcessId().ok();
+                        (session_id, instance_id, process_id)
+                    }
+                    None => (None, None, None),
+                };
+
+                let process_name = process_id.map(get_process_name);
+
+                out.push(DebugSessionDump {
+                    data_flow: flow_label.to_string(),
+                    session_index: session_index as u32,
+                    session_id,
+                    instance_id,
+                    process_id,
+                    process_name,
+                    state,
+                    grouping_param,
+                    control2_cast_ok: control2.is_some(),
+                    simple_volume_cast_ok: session_control.cast::<ISimpleAudioVolume>().is_ok(),
+                    channel_volume_cast_ok: session_control.cast::<IChannelAudioVolume>().is_ok(),
+                });
+            }
+        }
+    }
+
+    /// Diagnostic dump behind `debug_dump_sessions` - see `dump_sessions_for_flow`.
+    pub fn debug_dump_sessions() -> std::result::Result<Vec<DebugSessionDump>, String> {
+        ensure_com_initialized()?;
+
+        let mut sessions = Vec::new();
+        unsafe {
+            Self::dump_sessions_for_flow(eRender, "Render", &mut sessions);
+            Self::dump_sessions_for_flow(eCapture, "Capture", &mut sessions);
+        }
+        Ok(sessions)
+    }
+
+    /// Enumerate all active audio sessions from all audio devices with proper resource management.
+    ///
+    /// When `app` is provided, emits a `"session-discovered"` event as each session resolves and a
+    /// final `"enumeration-complete"` event with the total count, so the UI can populate progressively
+    /// on systems with many sessions or slow `OpenProcess` calls instead of appearing to hang. It also
+    /// diffs each session against the last cached value and emits `"session-volume-changed"`/
+    /// `"session-mute-changed"` for anything that moved since the last call - `set_session_volume`/
+    /// `set_session_mute` update the cache eagerly, so a mismatch here means
+    /// the Windows Volume Mixer or another tool changed it, not us. There's no true `IAudioSessionEvents`
+    /// push here; this piggybacks on the same 1s poll the frontend already drives `get_audio_sessions`
+    /// with, which keeps the COM lifetime story (and the apartment-threading it already relies on)
+    /// exactly as it is rather than adding a second, independently-threaded callback sink.
+    pub fn enumerate_sessions(&mut self, app: Option<&tauri::AppHandle>) -> std::result::Result<Vec<AudioSession>, String> {
+        let mut sessions = Vec::with_capacity(INITIAL_SESSION_CAPACITY); // Pre-allocate reasonable capacity
+        let mut live_session_ids: HashSet<String> = HashSet::with_capacity(INITIAL_SESSION_CAPACITY);
+        // peak level + active-state, keyed by session id, used only for sort ordering
+        let mut sort_aux: HashMap<String, (f32, bool)> = HashMap::with_capacity(INITIAL_SESSION_CAPACITY);
+
+        let now = Instant::now();
+        let sessions_cache = &mut self.sessions;
+        let session_first_seen = &mut self.session_first_seen;
+        let session_last_active = &mut self.session_last_active;
+        let volume_emit_state = &self.volume_emit_state;
+        let peak_history = &mut self.peak_history;
+
+        Self::for_each_session_control(eRender, |session_control, session_control2, session_index, device_id| unsafe {
+            let Some(mut session) = Self::resolve_audio_session(session_control, session_control2, session_index, device_id) else {
+                return;
+            };
+            let session_id = session.session_id.clone();
+
+            // Peak level (via the session's meter interface) and active state,
+            // used only to order the channel strip when sort mode calls for it.
+            let peak = session_control
+                .cast::<IAudioMeterInformation>()
+                .and_then(|meter| meter.GetPeakValue())
+                .unwrap_or(0.0);
+            let is_active = session_control
+                .GetState()
+                .map(|state| state == AudioSessionStateActive)
+                .unwrap_or(false);
+            sort_aux.insert(session_id.clone(), (peak, is_active));
+
+            // Append to this session's peak history, capped to PEAK_HISTORY_LENGTH samples -
+            // see `peak_history`'s doc comment. Only tracks new sessions up to
+            // MAX_SESSION_CACHE_SIZE, same bound `self.sessions` itself is pruned to below,
+            // so a flood of short-lived sessions can't grow this unbounded either.
+            if let Some(history) = peak_history.get_mut(&session_id) {
+                if history.len() >= PEAK_HISTORY_LENGTH {
+                    history.pop_front();
+                }
+                history.push_back(peak);
+            } else if peak_history.len() < MAX_SESSION_CACHE_SIZE {
+                let mut history = VecDeque::with_capacity(PEAK_HISTORY_LENGTH);
+                history.push_back(peak);
+                peak_history.insert(session_id.clone(), history);
+            }
+
+            let first_seen = *session_first_seen.entry(session_id.clone()).or_insert(now);
+            session.age_ms = now.duration_since(first_seen).as_millis() as u64;
+
+            if is_active {
+                session_last_active.remove(&session_id);
+            } else {
+                session_last_active.entry(session_id.clone()).or_insert(now);
+            }
+            session.inactive_since_ms = session_last_active.get(&session_id)
+                .map(|since| now.duration_since(*since).as_millis() as u64);
+
+            live_session_ids.insert(session_id.clone());
+            sessions.push(session.clone());
+
+            if let Some(app) = app {
+                let _ = app.emit("session-discovered", &session);
+
+                if let Some(previous) = sessions_cache.get(&session_id) {
+                    if (previous.volume - session.volume).abs() > f32::EPSILON {
+                        emit_volume_change_coalesced(volume_emit_state, app, SessionVolumeChange {
+                            session_id: session_id.clone(),
+                            process_name: session.process_name.clone(),
+                            volume: session.volume,
+                            volume_percent: session.volume_percent,
+                        });
+                    }
+                    if previous.is_muted != session.is_muted {
+                        let _ = app.emit("session-mute-changed", SessionMuteChange {
+                            session_id: session_id.clone(),
+                            process_name: session.process_name.clone(),
+                            is_muted: session.is_muted,
+                        });
+                    }
+                }
+            }
+
+            sessions_cache.insert(session_id, session);
+        })?;
+
+        // Remove sessions that are no longer active to prevent cache growth
+        self.sessions.retain(|id, _| live_session_ids.contains(id));
+        self.session_first_seen.retain(|id, _| live_session_ids.contains(id));
+        self.session_last_active.retain(|id, _| live_session_ids.contains(id));
+        self.peak_history.retain(|id, _| live_session_ids.contains(id));
+
+        // Prevent unbounded memory growth by limiting cache size
+        if self.sessions.len() > MAX_SESSION_CACHE_SIZE {
+            // Keep only the most recent entries
+            let mut session_keys: Vec<String> = self.sessions.keys().cloned().collect();
+            session_keys.truncate(MAX_SESSION_CACHE_SIZE / 2); // Remove oldest half
+            self.sessions.retain(|k, _| session_keys.contains(k));
+            tracing::warn!("[Audio] Cache size limit reached, pruned to {} entries", self.sessions.len());
+        }
+
+        self.enumerate_calls = self.enumerate_calls.wrapping_add(1);
+        let active_count = live_session_ids.len();
+        let cache_count = self.sessions.len();
+
+        let counts_changed = match self.last_logged_counts {
+            Some((last_active, last_cache)) => last_active != active_count || last_cache != cache_count,
+            None => true,
+        };
+
+        if counts_changed || self.enumerate_calls % LOG_INTERVAL == 0 {
+            tracing::debug!(
+                "[Audio] enumerate_sessions: {} active (cache size {}, calls: {})",
+                active_count,
+                cache_count,
+                self.enumerate_calls
+            );
+            self.last_logged_counts = Some((active_count, cache_count));
+        }
+
+        Self::sort_sessions(&mut sessions, &sort_aux);
+
+        if let Some(app) = app {
+            let _ = app.emit("enumeration-complete", sessions.len());
+        }
+
+        Ok(sessions)
+    }
+
+    /// Enumerate active capture sessions (e.g. an app's mic input) across all active
+    /// recording endpoints. Unlike `enumerate_sessions`, this isn't cached or sorted - it
+    /// exists to back `get_combined_sessions`, which is the one place capture sessions are
+    /// surfaced today.
+    pub fn enumerate_capture_sessions() -> std::result::Result<Vec<AudioSession>, String> {
+        let mut sessions = Vec::with_capacity(INITIAL_SESSION_CAPACITY);
+
+        Self::for_each_session_control(eCapture, |session_control, session_control2, session_index, device_id| unsafe {
+            if let Some(session) = Self::resolve_audio_session(session_control, session_control2, session_index, device_id) {
+                sessions.push(session);
+            }
+        })?;
+
+        Ok(sessions)
+    }
+
+    /// Group render and capture sessions by process name into one combined entry per app, for
+    /// apps like Discord that show up as both an output session (call audio) and an input
+    /// session (mic capture) - users think of that as one "Discord" strip with two sliders,
+    /// not two unrelated entries.
+    pub fn get_combined_sessions(&mut self, app: Option<&tauri::AppHandle>) -> std::result::Result<Vec<CombinedSession>, String> {
+        let output_sessions = self.enumerate_sessions(app)?;
+        let capture_sessions = Self::enumerate_capture_sessions()?;
+
+        let mut combined: HashMap<String, CombinedSession> = HashMap::new();
+
+        for session in output_sessions {
+            let entry = combined.entry(session.process_name.clone()).or_insert_with(|| CombinedSession {
+                process_name: session.process_name.clone(),
+                display_name: session.display_name.clone(),
+                process_id: session.process_id,
+                output_session_id: None,
+                output_volume: None,
+                output_muted: None,
+                input_session_id: None,
+                input_volume: None,
+                input_muted: None,
+            });
+            entry.output_session_id = Some(session.session_id);
+            entry.output_volume = Some(session.volume);
+            entry.output_muted = Some(session.is_muted);
+        }
+
+        for session in capture_sessions {
+            let entry = combined.entry(session.process_name.clone()).or_insert_with(|| CombinedSession {
+                process_name: session.process_name.clone(),
+                display_name: session.display_name.clone(),
+                process_id: session.process_id,
+                output_session_id: None,
+                output_volume: None,
+                output_muted: None,
+                input_session_id: None,
+                input_volume: None,
+                input_muted: None,
+            });
+            entry.input_session_id = Some(session.session_id);
+            entry.input_volume = Some(session.volume);
+            entry.input_muted = Some(session.is_muted);
+        }
+
+        let mut combined: Vec<CombinedSession> = combined.into_values().collect();
+        combined.sort_by(|a, b| a.process_name.cmp(&b.process_name));
+        Ok(combined)
+    }
+
+    /// Order sessions per the configured `SortMode`, with process name as a stable
+    /// tiebreaker so the order doesn't thrash when peaks/states are equal.
+    fn sort_sessions(sessions: &mut [AudioSession], sort_aux: &HashMap<String, (f32, bool)>) {
+        let settings = crate::settings::get();
+
+        match settings.sort_mode {
+            SortMode::Name => {
+                sessions.sort_by(|a, b| a.process_name.cmp(&b.process_name));
+            }
+            SortMode::Peak => {
+                sessions.sort_by(|a, b| {
+                    let peak_a = sort_aux.get(&a.session_id).map(|v| v.0).unwrap_or(0.0);
+                    let peak_b = sort_aux.get(&b.session_id).map(|v| v.0).unwrap_or(0.0);
+                    peak_b
+                        .partial_cmp(&peak_a)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then_with(|| a.process_name.cmp(&b.process_name))
+                });
+            }
+            SortMode::ActiveFirst => {
+                sessions.sort_by(|a, b| {
+                    let active_a = sort_aux.get(&a.session_id).map(|v| v.1).unwrap_or(false);
+                    let active_b = sort_aux.get(&b.session_id).map(|v| v.1).unwrap_or(false);
+                    active_b
+                        .cmp(&active_a)
+                        .then_with(|| a.process_name.cmp(&b.process_name))
+                });
+            }
+            SortMode::Manual => {
+                let order = &settings.manual_session_order;
+                sessions.sort_by(|a, b| {
+                    let pos_a = order.iter().position(|p| p == &a.process_name);
+                    let pos_b = order.iter().position(|p| p == &b.process_name);
+                    match (pos_a, pos_b) {
+                        (Some(pa), Some(pb)) => pa.cmp(&pb),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => a.process_name.cmp(&b.process_name),
+                    }
+                });
+            }
+        }
+    }
+
+    /// Set volume for a specific session and all sessions of the same process (searches all devices)
+    pub fn set_session_volume(&mut self, session_id: &str, volume: f32) -> std::result::Result<(), String> {
+        let mut volume = volume.clamp(0.0, 1.0);
+
+        // First, find the process_id for this session
+        let target_session = self.sessions.get(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        if !target_session.controllable {
+            return Err(format!("Unsupported: session '{}' does not expose volume control", session_id));
+        }
+
+        // Enforced here rather than left to callers, so a binding or scene can't push a
+        // capped channel (alarms, ATC) past its ceiling by going around a higher-level check -
+        // see `settings::session_volume_caps`.
+        if let Some(cap) = crate::settings::session_volume_cap(&target_session.process_name) {
+            volume = volume.min(cap);
+        }
+
+        let target_process_id = target_session.process_id;
+        crate::settings::capture_original_volume(&target_session.process_name, target_session.volume);
+        let pinned_device_id = crate::settings::get().device_pins.get(&target_session.process_name).map(|pin| pin.device_id.clone());
+
+        let (matched_count, updated_count) = Self::retry_transient(|| {
+            let mut matched_count = 0;
+            let mut updated_count = 0;
+
+            Self::for_each_session_control(eRender, |session_control, session_control2, _session_index, device_id| unsafe {
+                let process_id = session_control2.GetProcessId().unwrap_or(0);
+
+                // Apply volume to matching sessions - all of them, unless this process is
+                // pinned to a specific device (see `settings::device_pins`), in which case
+                // only the instance actually on that device, so a same-named process still
+                // running on whatever's now the default doesn't absorb a change meant for
+                // the pinned endpoint instead.
+                let device_matches = pinned_device_id.as_deref().map_or(true, |pinned| pinned == device_id);
+                if process_id == target_process_id && device_matches {
+                    matched_count += 1;
+                    if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
+                        let _ = simple_volume.SetMasterVolume(volume, std::ptr::null());
+                        updated_count += 1;
+                    }
+                }
+            })?;
+
+            Ok((matched_count, updated_count))
+        })?;
+
+        // Update cache for the requested session
+        if let Some(session) = self.sessions.get_mut(session_id) {
+            session.volume = volume;
+            session.volume_percent = scalar_to_percent(volume);
+        }
+
+        if updated_count > 0 {
+            Ok(())
+        } else if matched_count > 0 {
+            Err("Session found but volume control unavailable".to_string())
+        } else {
+            Err(format!("No sessions found for process_id: {}", target_process_id))
+        }
+    }
+
+    /// Convenience wrapper over `set_session_volume` that takes a whole percent
+    /// instead of a 0.0-1.0 scalar, so callers that quantize to percentages (UI
+    /// sliders, CSV-imported bindings) don't each re-implement the conversion.
+    pub fn set_session_volume_percent(&mut self, session_id: &str, pct: u8) -> std::result::Result<(), String> {
+        self.set_session_volume(session_id, percent_to_scalar(pct))
+    }
+
+    /// Requests a volume above unity ("gain") for a session. `ISimpleAudioVolume` itself has
+    /// no headroom above 1.0 - there's no per-session boost in the Windows Core Audio session
+    /// APIs, only on the full capture/render endpoint. Quietly turning down every *other*
+    /// session, or raising the shared endpoint volume, would "work" but changes audio the user
+    /// didn't ask to touch and would surprise them the moment a second quiet app showed up, so
+    /// this deliberately doesn't do either: it clamps at 1.0 and reports that it did, so the UI
+    /// can tell the user why the slider stopped responding instead of silently doing nothing.
+    pub fn set_session_gain(&mut self, session_id: &str, gain: f32) -> std::result::Result<SessionGainResult, String> {
+        let target_gain = gain.clamp(0.0, 1.0);
+        self.set_session_volume(session_id, target_gain)?;
+
+        // `set_session_volume` may have re-clamped `target_gain` further against the
+        // process's `session_volume_cap` - read back what it actually left the session at
+        // rather than assuming our own pre-cap target stuck.
+        let applied_gain = self.sessions.get(session_id).map(|s| s.volume).unwrap_or(target_gain);
+
+        Ok(SessionGainResult {
+            requested_gain: gain,
+            applied_gain,
+            at_ceiling: gain > 1.0,
+        })
+    }
+
+    /// Walk `CreateToolhelp32Snapshot`'s process list and return the PID of every process
+    /// named `root_process_name` plus every descendant of one, reached by following the
+    /// parent-PID chain. Chrome/Electron-style apps spawn several child processes that each
+    /// get their own audio session, so "control Slack" means hitting all of them, not just
+    /// whichever PID happens to own the main window.
+    #[cfg(windows)]
+    fn resolve_process_tree_pids(root_process_name: &str) -> std::result::Result<HashSet<u32>, String> {
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)
+                .map_err(|e: Error| format!("Failed to snapshot process list: {}", e))?;
+
+            let mut entries = Vec::new();
+            let mut entry = PROCESSENTRY32W {
+                dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+                ..Default::default()
+            };
+
+            if Process32FirstW(snapshot, &mut entry).is_ok() {
+                loop {
+                    let name_len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+                    let name = String::from_utf16_lossy(&entry.szExeFile[..name_len]);
+                    entries.push((entry.th32ProcessID, entry.th32ParentProcessID, name));
+
+                    if Process32NextW(snapshot, &mut entry).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            let _ = CloseHandle(snapshot);
+
+            let mut matched: HashSet<u32> = entries.iter()
+                .filter(|(_, _, name)| name == root_process_name)
+                .map(|(pid, _, _)| *pid)
+                .collect();
+
+            // Repeatedly pull in any process whose parent is already in the set, until a
+            // pass finds nothing new - handles grandchildren (e.g. a renderer spawned by a
+            // helper spawned by the main process), not just direct children.
+            loop {
+                let before = matched.len();
+                for (pid, parent_pid, _) in &entries {
+                    if matched.contains(parent_pid) {
+                        matched.insert(*pid);
+                    }
+                }
+                if matched.len() == before {
+                    break;
+                }
+            }
+
+            Ok(matched)
+        }
+    }
+
+    /// Apply `volume` to every session belonging to `root_process_name` or one of its
+    /// descendant processes (see `resolve_process_tree_pids`), for apps like Slack/Discord
+    /// where a single logical app shows up as several PIDs, each with its own session.
+    /// Returns how many sessions were updated.
+    pub fn set_volume_by_process_tree(&mut self, root_process_name: &str, volume: f32) -> std::result::Result<u32, String> {
+        let volume = volume.clamp(0.0, 1.0);
+        let target_pids = Self::resolve_process_tree_pids(root_process_name)?;
+
+        if target_pids.is_empty() {
+            return Err(format!("No running process named '{}'", root_process_name));
+        }
+
+        // Each PID's own ceiling, not just the root's - a descendant process with its own name
+        // and its own separately-configured cap (see `settings::session_volume_caps`) must be
+        // checked against *its* cap, not whatever the root process happens to be capped at. A
+        // PID with no cached session yet gets the uncapped `volume`, same as before this cap
+        // existed.
+        let pid_volumes: HashMap<u32, f32> = target_pids.iter()
+            .map(|&pid| {
+                let capped = self.sessions.values()
+                    .find(|s| s.process_id == pid)
+                    .and_then(|s| crate::settings::session_volume_cap(&s.process_name))
+                    .map(|cap| volume.min(cap))
+                    .unwrap_or(volume);
+                (pid, capped)
+            })
+            .collect();
+
+        let updated_count = Self::retry_transient(|| {
+            let mut updated_count = 0;
+
+            Self::for_each_session_control(eRender, |session_control, session_control2, _session_index, _device_id| unsafe {
+                let process_id = session_control2.GetProcessId().unwrap_or(0);
+
+                if let Some(&target_volume) = pid_volumes.get(&process_id) {
+                    if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
+                        let _ = simple_volume.SetMasterVolume(target_volume, std::ptr::null());
+                        updated_count += 1;
+                    }
+                }
+            })?;
+
+            Ok(updated_count)
+        })?;
+
+        for session in self.sessions.values_mut() {
+            if let Some(&target_volume) = pid_volumes.get(&session.process_id) {
+                session.volume = target_volume;
+                session.volume_percent = scalar_to_percent(target_volume);
+            }
+        }
+
+        if updated_count > 0 {
+            Ok(updated_count)
+        } else {
+            Err(format!("No sessions found for process tree rooted at '{}'", root_process_name))
+        }
+    }
+
+    /// Multiply every controllable session's current volume by `factor` in one COM pass,
+    /// remembering each original so `restore_all_volumes` can undo it exactly - a quick
+    /// "everything a bit quieter" for e.g. an incoming phone call. Relative to whatever's
+    /// already set (unlike a scene, which would apply an absolute snapshot) and manually
+    /// triggered (unlike focus-duck, which reacts to window focus on its own). Errors if a
+    /// scale is already active, so two calls in a row can't compound on top of each other and
+    /// lose the true original.
+    pub fn scale_all_volumes(&mut self, factor: f32) -> std::result::Result<u32, String> {
+        if self.scale_restore.is_some() {
+            return Err("A volume scale is already active - call restore_all_volumes first".to_string());
+        }
+
+        let originals: HashMap<String, f32> = self.sessions.iter()
+            .filter(|(_, session)| session.controllable)
+            .map(|(session_id, session)| (session_id.clone(), session.volume))
+            .collect();
+
+        if originals.is_empty() {
+            return Err("No controllable sessions to scale".to_string());
+        }
+
+        // Same ceiling `set_session_volume` enforces, applied per target session before the
+        // batch COM write - see `settings::session_volume_caps`.
+        let caps = crate::settings::get().session_volume_caps;
+        let targets: HashMap<String, f32> = originals.iter()
+            .map(|(session_id, volume)| {
+                let mut target = (volume * factor).clamp(0.0, 1.0);
+                if let Some(process_name) = self.sessions.get(session_id).map(|s| s.process_name.clone()) {
+                    if let Some(&cap) = caps.get(&process_name) {
+                        target = target.min(cap);
+                    }
+                }
+                (session_id.clone(), target)
+            })
+            .collect();
+
+        let updated_count = Self::apply_session_volumes(&targets)?;
+
+        for (session_id, &target_volume) in &targets {
+            if let Some(session) = self.sessions.get_mut(session_id) {
+                session.volume = target_volume;
+                session.volume_percent = scalar_to_percent(target_volume);
+            }
+        }
+
+        self.scale_restore = Some(originals);
+        Ok(updated_count)
+    }
+
+    /// Undo the most recent `scale_all_volumes`, restoring each session's exact prior volume
+    /// in one COM pass. Errors if no scale is currently active.
+    pub fn restore_all_volumes(&mut self) -> std::result::Result<u32, String> {
+        let mut originals = self.scale_restore.take()
+            .ok_or("No volume scale is active to restore")?;
+
+        // Same ceiling `set_session_volume` enforces - a session capped after it was scaled
+        // down should still come back capped, not above it, once restored - see
+        // `settings::session_volume_caps`.
+        let caps = crate::settings::get().session_volume_caps;
+        for (session_id, volume) in originals.iter_mut() {
+            if let Some(process_name) = self.sessions.get(session_id).map(|s| s.process_name.clone()) {
+                if let Some(&cap) = caps.get(&process_name) {
+                    *volume = volume.min(cap);
+                }
+            }
+        }
+
+        let updated_count = Self::apply_session_volumes(&originals)?;
+
+        for (session_id, &original_volume) in &originals {
+            if let Some(session) = self.sessions.get_mut(session_id) {
+                session.volume = original_volume;
+                session.volume_percent = scalar_to_percent(original_volume);
+            }
+        }
+
+        Ok(updated_count)
+    }
+
+    /// Shared COM pass behind `scale_all_volumes`/`restore_all_volumes`: walk every render
+    /// session once, applying `targets[session_id]` to whichever ones match instead of doing a
+    /// separate `for_each_session_control` walk per session the way a loop of individual
+    /// `set_session_volume` calls would.
+    fn apply_session_volumes(targets: &HashMap<String, f32>) -> std::result::Result<u32, String> {
+        Self::retry_transient(|| {
+            let mut updated_count = 0;
+
+            Self::for_each_session_control(eRender, |session_control, session_control2, _session_index, _device_id| unsafe {
+                let session_id = match session_control2.GetSessionInstanceIdentifier() {
+                    Ok(pwstr) => {
+                        let s = pwstr.to_string().unwrap_or_default();
+                        CoTaskMemFree(Some(pwstr.0 as *const core::ffi::c_void));
+                        s
+                    }
+                    Err(_) => return,
+                };
+
+                if let Some(&target_volume) = targets.get(&session_id) {
+                    if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
+                        let _ = simple_volume.SetMasterVolume(target_volume, std::ptr::null());
+                        updated_count += 1;
+                    }
+                }
+            })?;
+
+            Ok(updated_count)
+        })
+    }
+
+    /// Mute or unmute all sessions of the same process (searches all devices)
+    pub fn set_session_mute(&mut self, session_id: &str, muted: bool) -> std::result::Result<(), String> {
+        // First, find the process_id for this session
+        let target_session = self.sessions.get(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+        if !target_session.controllable {
+            return Err(format!("Unsupported: session '{}' does not expose volume control", session_id));
+        }
+
+        let target_process_id = target_session.process_id;
+        let pinned_device_id = crate::settings::get().device_pins.get(&target_session.process_name).map(|pin| pin.device_id.clone());
+
+        let (matched_count, updated_count) = Self::retry_transient(|| {
+            let mut matched_count = 0;
+            let mut updated_count = 0;
+
+            Self::for_each_session_control(eRender, |session_control, session_control2, _session_index, device_id| unsafe {
+                let process_id = session_control2.GetProcessId().unwrap_or(0);
+
+                // Apply mute to matching sessions - all of them, unless this process is
+                // pinned to a specific device, in which case only the instance on that
+                // device (see the matching comment in `set_session_volume`).
+                let device_matches = pinned_device_id.as_deref().map_or(true, |pinned| pinned == device_id);
+                if process_id == target_process_id && device_matches {
+                    matched_count += 1;
+                    if let Ok(simple_volume) = session_control.cast::<ISimpleAudioVolume>() {
+                        let _ = simple_volume.SetMute(BOOL(muted as i32), std::ptr::null());
+                        updated_count += 1;
+                    }
+                }
+            })?;
+
+            Ok((matched_count, updated_count))
+        })?;
+
+        // Update cache for the requested session
+        if let Some(session) = self.sessions.get_mut(session_id) {
+            session.is_muted = muted;
+
+            // Track sessions we muted so `clear_clearcomms_mutes` can recover them
+            // after a crash without touching mutes the user applied themselves.
+            if muted {
+                crate::settings::mark_clearcomms_muted(&session.process_name);
+            } else {
+                crate::settings::unmark_clearcomms_muted(&session.process_name);
+            }
+        }
+
+        if updated_count > 0 {
+            Ok(())
+        } else if matched_count > 0 {
+            Err("Session found but mute control unavailable".to_string())
+        } else {
+            Err(format!("No sessions found for process_id: {}", target_process_id))
+        }
+    }
+
+    /// Unmute every session tracked as muted by ClearComms itself (see
+    /// `set_session_mute`), rather than blindly unmuting everything - recovers
+    /// gracefully from a crash that left mutes applied with no UI state pointing
+    /// back at them. Returns the process names that were actually recovered.
+    pub fn clear_clearcomms_mutes(&mut self) -> std::result::Result<Vec<String>, String> {
+        let tracked = crate::settings::get().clearcomms_muted_processes;
+        let mut recovered = Vec::new();
+
+        for process_name in tracked {
+            let matching_session_id = self.sessions.values()
+                .find(|s| s.process_name == process_name)
+                .map(|s| s.session_id.clone());
+
+            match matching_session_id {
+                Some(session_id) if self.set_session_mute(&session_id, false).is_ok() => {
+                    recovered.push(process_name);
+                }
+                _ => {
+                    // Not currently running, or the unmute itself failed - there's
+                    // nothing left to recover, so stop tracking it either way.
+                    crate::settings::unmark_clearcomms_muted(&process_name);
+                }
+            }
+        }
+
+        Ok(recovered)
+    }
+}
+
+/// Emit `"session-volume-changed"` for `change`, throttled and coalesced per session so a fast
+/// slider drag in the Windows Volume Mixer (or any other rapid external change) can't flood the
+/// UI with events - see `VOLUME_EMIT_THROTTLE`. Emits immediately if the session hasn't fired
+/// within the throttle window; otherwise stashes `change` as the latest pending value and, if
+/// nothing's already scheduled, spawns a one-shot thread to flush it once the window closes, so
+/// the final value is always delivered rather than dropped. Takes the state `Arc` directly
+/// (rather than `&self`) so it can be called from inside `enumerate_sessions`'s
+/// `for_each_session_control` closure, which only borrows the individual fields it needs.
+#[cfg(windows)]
+fn emit_volume_change_coalesced(volume_emit_state: &Arc<Mutex<HashMap<String, VolumeEmitState>>>, app: &tauri::AppHandle, change: SessionVolumeChange) {
+    let mut states = volume_emit_state.lock().unwrap();
+    let now = Instant::now();
+
+    let elapsed_since_last = states.get(&change.session_id).map(|s| now.duration_since(s.last_emit));
+    if elapsed_since_last.map_or(true, |elapsed| elapsed >= VOLUME_EMIT_THROTTLE) {
+        states.insert(change.session_id.clone(), VolumeEmitState { last_emit: now, pending: None });
+        drop(states);
+        let _ = app.emit("session-volume-changed", change);
+        return;
+    }
+
+    let state = states.get_mut(&change.session_id).expect("checked above");
+    let already_scheduled = state.pending.is_some();
+    state.pending = Some(change.clone());
+    if already_scheduled {
+        return;
+    }
+
+    let delay = VOLUME_EMIT_THROTTLE.saturating_sub(now.duration_since(state.last_emit));
+    drop(states);
+
+    let volume_emit_state = volume_emit_state.clone();
+    let app = app.clone();
+    let session_id = change.session_id;
+    thread::spawn(move || {
+        thread::sleep(delay);
+        let mut states = volume_emit_state.lock().unwrap();
+        if let Some(state) = states.get_mut(&session_id) {
+            if let Some(final_change) = state.pending.take() {
+                state.last_emit = Instant::now();
+                drop(states);
+                let _ = app.emit("session-volume-changed", final_change);
+            }
+        }
+    });
+}
+
+#[cfg(all(not(windows), not(feature = "dev-mock")))]
+impl AudioManager {
+    pub fn new() -> std::result::Result<Self, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn enumerate_sessions(&mut self, _app: Option<&tauri::AppHandle>) -> std::result::Result<Vec<AudioSession>, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn enumerate_capture_sessions() -> std::result::Result<Vec<AudioSession>, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn get_combined_sessions(&mut self, _app: Option<&tauri::AppHandle>) -> std::result::Result<Vec<CombinedSession>, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_session_volume(&mut self, _session_id: &str, _volume: f32) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_session_volume_percent(&mut self, _session_id: &str, _pct: u8) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_session_gain(&mut self, _session_id: &str, _gain: f32) -> std::result::Result<SessionGainResult, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_session_mute(&mut self, _session_id: &str, _muted: bool) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_volume_by_process_tree(&mut self, _root_process_name: &str, _volume: f32) -> std::result::Result<u32, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn scale_all_volumes(&mut self, _factor: f32) -> std::result::Result<u32, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn restore_all_volumes(&mut self) -> std::result::Result<u32, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn clear_clearcomms_mutes(&mut self) -> std::result::Result<Vec<String>, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn play_test_tone(_device_id: &str, _frequency_hz: f32, _seconds: f32, _level: f32) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn duplicate_session_to_device(_session_id: &str, _target_device_id: &str) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn stop_session_duplication() {}
+
+    pub fn get_diagnostics(_device_id: Option<&str>) -> std::result::Result<AudioDiagnostics, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn get_capture_features(_device_id: &str) -> std::result::Result<CaptureFeatureSupport, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_capture_feature(_device_id: &str, _feature: CaptureFeature, _value: bool) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn debug_dump_sessions() -> std::result::Result<Vec<DebugSessionDump>, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn set_exclusive_mode_allowed(_device_id: &str, _allowed: bool) -> std::result::Result<(), String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn get_exclusive_mode_allowed(_device_id: &str) -> std::result::Result<bool, String> {
+        Err("Audio manager only supported on Windows".to_string())
+    }
+
+    pub fn capabilities() -> Capabilities {
+        Capabilities {
+            per_app_routing: false,
+            capture_control: false,
+            mic_boost: false,
+            exclusive_mode_toggle: false,
+            simconnect_available: false,
+        }
+    }
+}
+
+/// Process names for the synthetic sessions `dev-mock` populates on startup - see the
+/// `dev-mock` feature doc comment in `Cargo.toml`.
+#[cfg(all(not(windows), feature = "dev-mock"))]
+const MOCK_SESSION_PROCESSES: [(&str, &str); 3] = [
+    ("msfs.exe", "Microsoft Flight Simulator"),
+    ("discord.exe", "Discord"),
+    ("chrome.exe", "Google Chrome"),
+];
+
+/// Synthetic backend for developing the frontend off Windows - see the `dev-mock` feature
+/// doc comment in `Cargo.toml`. Sessions live entirely in `self.sessions`, the same cache
+/// field the real Windows backend populates, so every getter already works unmodified;
+/// only the handful of methods that would otherwise need real COM calls are overridden here.
+#[cfg(all(not(windows), feature = "dev-mock"))]
+impl AudioManager {
+    pub fn new() -> std::result::Result<Self, String> {
+        let mut sessions = HashMap::new();
+        for (index, (process_name, display_name)) in MOCK_SESSION_PROCESSES.iter().enumerate() {
+            let volume = 0.75;
+            sessions.insert(format!("mock-session-{}", index), AudioSession {
+                session_id: format!("mock-session-{}", index),
+                display_name: display_name.to_string(),
+                process_id: 1000 + index as u32,
+                process_name: process_name.to_string(),
+                volume,
+                volume_percent: scalar_to_percent(volume),
+                is_muted: false,
+                controllable: true,
+                age_ms: 0,
+                inactive_since_ms: None,
+                channel_count: DEFAULT_SESSION_CHANNEL_COUNT,
+                device_id: "mock-device-0".to_string(),
+            });
+        }
+
+        Ok(Self {
+            sessions,
+            current_device_id: "mock-default-device".to_string(),
+            pending_device_id: None,
+            enumerate_calls: 0,
+            last_logged_counts: None,
+            session_first_seen: HashMap::new(),
+            session_last_active: HashMap::new(),
+            volume_emit_state: Arc::new(Mutex::new(HashMap::new())),
+            peak_history: HashMap::new(),
+            scale_restore: None,
+            monitor_restore: None,
+        })
+    }
+
+    pub fn enumerate_sessions(&mut self, _app: Option<&tauri::AppHandle>) -> std::result::Result<Vec<AudioSession>, String> {
+        Ok(self.sessions.values().cloned().collect())
+    }
+
+    pub fn enumerate_capture_sessions() -> std::result::Result<Vec<AudioSession>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn get_combined_sessions(&mut self, _app: Option<&tauri::AppHandle>) -> std::result::Result<Vec<CombinedSession>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn set_session_volume(&mut self, session_id: &str, volume: f32) -> std::result::Result<(), String> {
+        let session = self.sessions.get_mut(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        session.volume = volume.clamp(0.0, 1.0);
+        session.volume_percent = scalar_to_percent(session.volume);
+        Ok(())
+    }
+
+    pub fn set_session_volume_percent(&mut self, session_id: &str, pct: u8) -> std::result::Result<(), String> {
+        self.set_session_volume(session_id, pct as f32 / 100.0)
+    }
+
+    pub fn set_session_gain(&mut self, session_id: &str, gain: f32) -> std::result::Result<SessionGainResult, String> {
+        let applied_gain = gain.clamp(0.0, 1.0);
+        self.set_session_volume(session_id, applied_gain)?;
+        Ok(SessionGainResult { requested_gain: gain, applied_gain, at_ceiling: gain > 1.0 })
+    }
+
+    pub fn set_session_mute(&mut self, session_id: &str, muted: bool) -> std::result::Result<(), String> {
+        let session = self.sessions.get_mut(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        session.is_muted = muted;
+        Ok(())
+    }
+
+    pub fn set_volume_by_process_tree(&mut self, root_process_name: &str, volume: f32) -> std::result::Result<u32, String> {
+        let mut updated_count = 0;
+        for session in self.sessions.values_mut() {
+            if session.process_name == root_process_name {
+                session.volume = volume.clamp(0.0, 1.0);
+                session.volume_percent = scalar_to_percent(session.volume);
+                updated_count += 1;
+            }
+        }
+        if updated_count > 0 {
+            Ok(updated_count)
+        } else {
+            Err(format!("No sessions found for process tree rooted at '{}'", root_process_name))
+        }
+    }
+
+    pub fn scale_all_volumes(&mut self, factor: f32) -> std::result::Result<u32, String> {
+        if self.scale_restore.is_some() {
+            return Err("A volume scale is already active - call restore_all_volumes first".to_string());
+        }
+
+        let originals: HashMap<String, f32> = self.sessions.iter()
+            .filter(|(_, session)| session.controllable)
+            .map(|(session_id, session)| (session_id.clone(), session.volume))
+            .collect();
+
+        if originals.is_empty() {
+            return Err("No controllable sessions to scale".to_string());
+        }
+
+        let mut updated_count = 0;
+        for (session_id, original_volume) in &originals {
+            if let Some(session) = self.sessions.get_mut(session_id) {
+                session.volume = (original_volume * factor).clamp(0.0, 1.0);
+                session.volume_percent = scalar_to_percent(session.volume);
+                updated_count += 1;
+            }
+        }
+
+        self.scale_restore = Some(originals);
+        Ok(updated_count)
+    }
+
+    pub fn restore_all_volumes(&mut self) -> std::result::Result<u32, String> {
+        let originals = self.scale_restore.take()
+            .ok_or("No volume scale is active to restore")?;
+
+        let mut updated_count = 0;
+        for (session_id, original_volume) in &originals {
+            if let Some(session) = self.sessions.get_mut(session_id) {
+                session.volume = *original_volume;
+                session.volume_percent = scalar_to_percent(session.volume);
+                updated_count += 1;
+            }
+        }
+
+        Ok(updated_count)
+    }
+
+    pub fn clear_clearcomms_mutes(&mut self) -> std::result::Result<Vec<String>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn play_test_tone(_device_id: &str, _frequency_hz: f32, _seconds: f32, _level: f32) -> std::result::Result<(), String> {
+        Ok(())
+    }
+
+    pub fn duplicate_session_to_device(_session_id: &str, _target_device_id: &str) -> std::result::Result<(), String> {
+        Err("Session duplication isn't mocked in dev-mock mode".to_string())
+    }
+
+    pub fn stop_session_duplication() {}
+
+    pub fn get_diagnostics(_device_id: Option<&str>) -> std::result::Result<AudioDiagnostics, String> {
+        Err("Diagnostics aren't mocked in dev-mock mode".to_string())
+    }
+
+    pub fn get_capture_features(_device_id: &str) -> std::result::Result<CaptureFeatureSupport, String> {
+        Err("Capture features aren't mocked in dev-mock mode".to_string())
+    }
+
+    pub fn set_capture_feature(_device_id: &str, _feature: CaptureFeature, _value: bool) -> std::result::Result<(), String> {
+        Err("Capture features aren't mocked in dev-mock mode".to_string())
+    }
+
+    /// Dumps the same `MOCK_SESSION_PROCESSES` fixture `new()` populates, with every cast
+    /// reported as succeeding - there's nothing to actually fail to cast against in-memory state.
+    pub fn debug_dump_sessions() -> std::result::Result<Vec<DebugSessionDump>, String> {
+        Ok(MOCK_SESSION_PROCESSES.iter().enumerate().map(|(index, (process_name, _display_name))| {
+            DebugSessionDump {
+                data_flow: "Render".to_string(),
+                session_index: index as u32,
+                session_id: Some(format!("mock-session-{}", index)),
+                instance_id: Some(format!("mock-session-{}", index)),
+                process_id: Some(1000 + index as u32),
+                process_name: Some(process_name.to_string()),
+                state: Some("Active".to_string()),
+                grouping_param: None,
+                control2_cast_ok: true,
+                simple_volume_cast_ok: true,
+                channel_volume_cast_ok: true,
+            }
+        }).collect())
+    }
+
+    pub fn set_exclusive_mode_allowed(_device_id: &str, _allowed: bool) -> std::result::Result<(), String> {
+        Err("Exclusive mode isn't mocked in dev-mock mode".to_string())
+    }
+
+    pub fn get_exclusive_mode_allowed(_device_id: &str) -> std::result::Result<bool, String> {
+        Err("Exclusive mode isn't mocked in dev-mock mode".to_string())
+    }
+
+    /// Reports `per_app_routing` and `capture_control` "on" - there's no real Windows version
+    /// or hardware to probe in `dev-mock` mode, so this lets frontend development exercise the
+    /// "capability available" code paths. `mic_boost` and `exclusive_mode_toggle` report "off",
+    /// matching the "isn't mocked" errors of the commands behind them above, and
+    /// `simconnect_available` is always off here.
+    pub fn capabilities() -> Capabilities {
+        Capabilities {
+            per_app_routing: true,
+            capture_control: true,
+            mic_boost: false,
+            exclusive_mode_toggle: false,
+            simconnect_available: false,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl AudioManager {
+    /// Explicit cleanup method for proper resource management
+    pub fn cleanup(&mut self) {
+        tracing::info!("[Audio] Cleaning up audio manager resources...");
+        
+        // Clear internal caches
+        self.sessions.clear();
+        // Release memory back to the system
+        self.sessions.shrink_to_fit();
+        
+        // Reset counters
+        self.enumerate_calls = 0;
+        self.last_logged_counts = None;
+        
+        // Reset device ID to release string memory
+        self.current_device_id = String::new();
+        self.pending_device_id = None;
+
+        tracing::info!("[Audio] Audio manager cleanup complete");
+    }
+}
+
+impl Drop for AudioManager {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        {
+            tracing::debug!("[Audio] Dropping audio manager...");
+            self.cleanup();
+            // COM is no longer uninitialized here - with commands dispatched across Tauri's
+            // whole blocking worker pool (see `ensure_com_initialized`), a single `AudioManager`
+            // instance no longer maps to a single COM-initialized thread, so uninitializing
+            // it here could tear down COM on a thread that's still using it for something else.
+            // Each thread's own `ComGuard` uninitializes it when that thread ends instead.
+            tracing::debug!("[Audio] Audio manager dropped");
+        }
+    }
+}
+
+/// Tauri-managed slot for the audio manager, installed via `app.manage(...)` in `main.rs`'s
+/// `setup`. Ties the manager's lifetime to the app (so `Drop` runs on shutdown) instead of a
+/// process-lifetime static, without changing the "not initialised until `init_audio_manager`
+/// runs" behavior any command relies on.
+pub type AudioManagerState = Mutex<Option<AudioManager>>;
+
+/// Acquire the managed `AudioManagerState`'s lock, recovering from a poisoned mutex instead of propagating it -
+/// a command handler that panics while holding this lock would otherwise brick every audio
+/// command with "Failed to lock audio manager mutex" until the app restarts. Logs the
+/// recovery so the underlying panic is still visible in the logs.
+pub(crate) fn lock_audio_manager(state: &AudioManagerState) -> std::sync::MutexGuard<'_, Option<AudioManager>> {
+    state.lock().unwrap_or_else(|e| {
+        tracing::error!("[Audio] Recovered from poisoned audio manager mutex: {}", e);
+        e.into_inner()
+    })
+}
+
+/// Take the managed audio manager out of its slot and drop it, which clears its session cache
+/// via `cleanup`. COM itself stays initialised until each thread's `ComGuard` drops (see
+/// `Drop for AudioManager`), and no session/endpoint notifications are registered that would
+/// need releasing. Safe to call even if the manager was never initialised.
+pub fn shutdown(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let mut lock = lock_audio_manager(&app.state::<AudioManagerState>());
+    if let Some(manager) = lock.take() {
+        tracing::info!("[Audio] Shutting down audio manager...");
+        drop(manager);
+    }
+}
+
+/// Initialize the audio manager
+#[tauri::command]
+pub fn init_audio_manager(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<String, String> {
+    tracing::info!("[Audio] Initialising audio manager...");
+    let manager = AudioManager::new()?;
+
+    let mut lock = lock_audio_manager(&state);
+
+    *lock = Some(manager);
+
+    tracing::info!("[Audio] Audio manager ready");
+    Ok("Audio manager initialised successfully".to_string())
+}
+
+/// Get all active audio sessions. Emits `"session-discovered"`/`"enumeration-complete"`
+/// events as enumeration progresses so the UI can populate incrementally, plus
+/// `"session-volume-changed"`/`"session-mute-changed"` for anything that moved since the
+/// last call without going through our own `set_session_volume`/`set_session_mute`; the
+/// returned vec still contains everything for callers that just want the final result.
+#[tauri::command]
+pub fn get_audio_sessions(app: tauri::AppHandle, state: tauri::State<'_, AudioManagerState>) -> std::result::Result<Vec<AudioSession>, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    let mut sessions = manager.enumerate_sessions(Some(&app))?;
+    overlay_discord_voice_label(&app, &mut sessions);
+    Ok(sessions)
+}
+
+/// Enrich Discord's session `display_name` with its current voice-channel state - see
+/// `crate::discord_presence`. Best-effort: if the `discord-rpc` feature isn't built in,
+/// `init_discord_presence` was never called, or Discord isn't in a call, sessions are left
+/// exactly as `enumerate_sessions` reported them.
+fn overlay_discord_voice_label(app: &tauri::AppHandle, sessions: &mut [AudioSession]) {
+    use tauri::Manager;
+
+    let label = app.try_state::<crate::discord_presence::DiscordPresenceManagerState>()
+        .and_then(|state| crate::discord_presence::current_voice_label(&state));
+
+    let Some(label) = label else { return; };
+
+    for session in sessions.iter_mut() {
+        if session.process_name.eq_ignore_ascii_case("Discord.exe") {
+            session.display_name = format!("{} — {}", session.display_name, label);
+        }
+    }
+}
+
+/// Only the sessions actually producing sound right now (`IAudioSessionControl::GetState
+/// == AudioSessionStateActive`), for a "now playing" readout - a tray tooltip or compact
+/// view wants what's making noise, not every session that's ever registered. Reuses the
+/// same enumeration `get_audio_sessions` does rather than a second COM walk: `inactive_since_ms`
+/// is `None` exactly when that same `GetState` call found the session active, so filtering
+/// on it here is equivalent to checking the state again.
+#[tauri::command]
+pub fn get_active_sessions(app: tauri::AppHandle, state: tauri::State<'_, AudioManagerState>) -> std::result::Result<Vec<AudioSession>, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    let sessions = manager.enumerate_sessions(Some(&app))?;
+    Ok(sessions.into_iter().filter(|s| s.inactive_since_ms.is_none()).collect())
+}
+
+/// Get render and capture sessions grouped by process name into one combined entry per app
+/// (see `CombinedSession`), for a UI that wants a single "Discord" strip with separate
+/// in/out sliders instead of two unrelated entries.
+#[tauri::command]
+pub fn get_combined_sessions(app: tauri::AppHandle, state: tauri::State<'_, AudioManagerState>) -> std::result::Result<Vec<CombinedSession>, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.get_combined_sessions(Some(&app))
+}
+
+/// Group the current render-session enumeration by `process_name`, for a UI that needs to
+/// know upfront whether an app has one session instance or several (a multi-process app like
+/// Chrome, or a multi-stream one) before deciding to show one combined strip or several -
+/// unlike `get_combined_sessions`, which only ever merges render+capture into a single entry
+/// per process and doesn't expose how many underlying instances that was.
 #[tauri::command]
-pub fn set_session_volume(session_id: String, volume: f32) -> std::result::Result<(), String> {
-    let mut lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn get_sessions_by_process(app: tauri::AppHandle, state: tauri::State<'_, AudioManagerState>) -> std::result::Result<HashMap<String, Vec<AudioSession>>, String> {
+    let mut lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_mut()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
+
+    let sessions = manager.enumerate_sessions(Some(&app))?;
+    let mut grouped: HashMap<String, Vec<AudioSession>> = HashMap::new();
+    for session in sessions {
+        grouped.entry(session.process_name.clone()).or_default().push(session);
+    }
+    Ok(grouped)
+}
+
+/// Get the last `PEAK_HISTORY_LENGTH` peak-value samples for a session, oldest first, for a
+/// small activity sparkline in the channel strip - see `AudioManager::session_peak_history`.
+/// One sample is added per `enumerate_sessions` call (the frontend's `get_audio_sessions` poll
+/// and `binding_poller`'s once-a-second session refresh both drive it), so a session that's
+/// never been enumerated yet - or that's new since recently - comes back with less than a full
+/// buffer.
+#[tauri::command]
+pub fn get_session_peak_history(state: tauri::State<'_, AudioManagerState>, session_id: String) -> std::result::Result<Vec<f32>, String> {
+    let lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    Ok(manager.session_peak_history(&session_id))
+}
+
+/// Diff `previous` (a snapshot the caller took earlier, e.g. via `get_audio_sessions`) against
+/// a fresh enumeration, computed server-side so automation scripts (stream setup scene
+/// switching, "notify me when Discord launches") don't reimplement session matching themselves.
+#[tauri::command]
+pub fn diff_sessions(app: tauri::AppHandle, state: tauri::State<'_, AudioManagerState>, previous: Vec<AudioSession>) -> std::result::Result<SessionDiff, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    let current = manager.enumerate_sessions(Some(&app))?;
+    Ok(compute_session_diff(&previous, &current))
+}
+
+/// Set volume for a specific audio session
+#[tauri::command]
+pub fn set_session_volume(state: tauri::State<'_, AudioManagerState>, session_id: String, volume: f32) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
     manager.set_session_volume(&session_id, volume)
 }
 
+/// Nudge a specific audio session's volume by `delta` relative to its current value - see
+/// `AudioManager::adjust_session_volume`. Returns the resulting clamped volume so a caller
+/// (e.g. `binding_poller`'s accelerating volume buttons) can report/log it without a second
+/// round-trip to read it back.
+#[tauri::command]
+pub fn adjust_session_volume(state: tauri::State<'_, AudioManagerState>, session_id: String, delta: f32) -> std::result::Result<f32, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.adjust_session_volume(&session_id, delta)
+}
+
+/// Set volume for a specific audio session as a whole percent (0-100)
+#[tauri::command]
+pub fn set_session_volume_percent(state: tauri::State<'_, AudioManagerState>, session_id: String, pct: u8) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.set_session_volume_percent(&session_id, pct)
+}
+
+/// Request a volume above unity ("gain") for a specific audio session. Clamps at 1.0 and
+/// reports it via `SessionGainResult::at_ceiling` - see `AudioManager::set_session_gain` for
+/// why true per-session gain isn't implemented.
+#[tauri::command]
+pub fn set_session_gain(state: tauri::State<'_, AudioManagerState>, session_id: String, gain: f32) -> std::result::Result<SessionGainResult, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.set_session_gain(&session_id, gain)
+}
+
+/// Map a raw SimVar reading into a session's volume, for an external SimConnect/SimVar
+/// poller to call on every update - there's no such poller wired up in this codebase yet
+/// (see `show_main_window_for_sim_state` in `main.rs`), this is the hook one would use.
+/// SimVar ranges vary wildly (0-1 boolean, 0-100 percent, 0-16 knob detents), so `value`
+/// is mapped linearly from `[input_min, input_max]` into `0.0-1.0` before being applied -
+/// without this, a 0-16 knob bound as if it were already 0-1 would barely move the volume.
+/// `clamp` (default `true`) clamps the mapped value to `0.0-1.0` same as any other volume
+/// set; `invert` (default `false`) flips it first, same sense as `AxisMapping::inverted`.
+/// Returns the volume actually applied. Errors if `input_min == input_max`, since that
+/// range can't be mapped from at all.
+#[tauri::command]
+pub fn map_simvar_to_session(
+    state: tauri::State<'_, AudioManagerState>,
+    session_id: String,
+    value: f32,
+    input_min: f32,
+    input_max: f32,
+    clamp: Option<bool>,
+    invert: Option<bool>,
+) -> std::result::Result<f32, String> {
+    if (input_max - input_min).abs() < f32::EPSILON {
+        return Err("input_min and input_max must differ".to_string());
+    }
+
+    let mut mapped = (value - input_min) / (input_max - input_min);
+    if invert.unwrap_or(false) {
+        mapped = 1.0 - mapped;
+    }
+    if clamp.unwrap_or(true) {
+        mapped = mapped.clamp(0.0, 1.0);
+    }
+
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.set_session_volume(&session_id, mapped)?;
+    Ok(mapped)
+}
+
+/// Set volume for every session belonging to `root_process_name` or a descendant process of
+/// it (e.g. Chrome/Electron helper processes), so controlling a multi-process app doesn't
+/// require binding each of its PIDs separately. Returns how many sessions were updated.
+#[tauri::command]
+pub fn set_volume_by_process_tree(state: tauri::State<'_, AudioManagerState>, root_process_name: String, volume: f32) -> std::result::Result<u32, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.set_volume_by_process_tree(&root_process_name, volume)
+}
+
+/// Scale every controllable session's current volume by `factor` (e.g. `0.5` for a quick
+/// "everything a bit quieter" during a phone call) in one COM pass, remembering the originals
+/// for `restore_all_volumes` - see `AudioManager::scale_all_volumes`. Errors if a scale is
+/// already active.
+#[tauri::command]
+pub fn scale_all_volumes(state: tauri::State<'_, AudioManagerState>, factor: f32) -> std::result::Result<u32, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.scale_all_volumes(factor)
+}
+
+/// Undo the most recent `scale_all_volumes`, restoring each session's exact prior volume.
+/// Errors if no scale is currently active.
+#[tauri::command]
+pub fn restore_all_volumes(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<u32, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.restore_all_volumes()
+}
+
 /// Mute or unmute a specific audio session
 #[tauri::command]
-pub fn set_session_mute(session_id: String, muted: bool) -> std::result::Result<(), String> {
-    let mut lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn set_session_mute(state: tauri::State<'_, AudioManagerState>, session_id: String, muted: bool) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_mut()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
+
     manager.set_session_mute(&session_id, muted)
 }
 
-/// Check if the default audio device has changed
-/// Returns true if changed, false otherwise
+/// Unmute every session ClearComms itself muted, recovering from a crash that
+/// left mutes applied with no UI state tracking them. Returns the process names
+/// that were recovered.
 #[tauri::command]
-pub fn check_default_device_changed() -> std::result::Result<bool, String> {
-    let mut lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn clear_clearcomms_mutes(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<Vec<String>, String> {
+    let mut lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_mut()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
+
+    manager.clear_clearcomms_mutes()
+}
+
+/// Undo everything ClearComms has changed: unmute every session it muted, restore every
+/// session's pre-ClearComms volume, and back out of priority mode / an in-progress session
+/// monitor if either is active - see `AudioManager::restore_windows_state`. Leaves bindings and
+/// other saved configuration untouched.
+#[tauri::command]
+pub fn restore_windows_state(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.restore_windows_state()
+}
+
+/// Tag `process_name` (e.g. "ATC", "alarms") so `activate_priority_mode` leaves it alone
+/// instead of muting it. Pass an empty `tag` to remove a previously-set one.
+#[tauri::command]
+pub fn set_session_tag(process_name: String, tag: String) -> std::result::Result<(), String> {
+    crate::settings::update(|s| {
+        if tag.is_empty() {
+            s.session_tags.remove(&process_name);
+        } else {
+            s.session_tags.insert(process_name.clone(), tag);
+        }
+    });
+    Ok(())
+}
+
+/// Every process name currently tagged, and its tag - for a priority-mode settings UI to
+/// show which sessions are exempt.
+#[tauri::command]
+pub fn get_session_tags() -> std::result::Result<HashMap<String, String>, String> {
+    Ok(crate::settings::get().session_tags)
+}
+
+/// Set `process_name`'s hard volume ceiling (0.0-1.0) - see `settings::session_volume_caps`.
+/// Enforced inside `AudioManager::set_session_volume` and its process-tree/scale batch
+/// counterparts, so a binding or scene can't push a capped channel (alarms, ATC, etc.) past it
+/// through any path. Pass `cap: None` to remove the ceiling.
+#[tauri::command]
+pub fn set_session_volume_cap(process_name: String, cap: Option<f32>) -> std::result::Result<(), String> {
+    crate::settings::set_session_volume_cap(&process_name, cap);
+    Ok(())
+}
+
+/// Every process name with a volume ceiling set, and its cap - for a settings UI to show which
+/// sessions are currently restricted.
+#[tauri::command]
+pub fn get_session_volume_caps() -> std::result::Result<HashMap<String, f32>, String> {
+    Ok(crate::settings::get().session_volume_caps)
+}
+
+/// Pin `process_name` to `device_id` (an `AudioSession::device_id`), so `set_session_volume`/
+/// `set_session_mute` scope to the session instance on that device instead of every instance
+/// with a matching `process_id` - see `settings::device_pins`. Looks up and stores the
+/// device's current friendly name alongside its id, so `reattach_pinned_sessions` can still
+/// find it by name if `device_id` changes on a later replug. An empty `device_id` is rejected;
+/// call `unpin_session_device` to remove a pin.
+#[tauri::command]
+pub fn pin_session_to_device(process_name: String, device_id: String) -> std::result::Result<(), String> {
+    if device_id.is_empty() {
+        return Err("device_id cannot be empty - call unpin_session_device to remove a pin".to_string());
+    }
+    let device_name = AudioManager::list_audio_devices()?
+        .into_iter()
+        .find(|d| d.device_id == device_id)
+        .map(|d| d.device_name)
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    crate::settings::update(|s| {
+        s.device_pins.insert(process_name, crate::settings::PinnedDevice { device_id, device_name });
+    });
+    Ok(())
+}
+
+/// Remove a previously-set `pin_session_to_device` pin for `process_name`, if any.
+#[tauri::command]
+pub fn unpin_session_device(process_name: String) -> std::result::Result<(), String> {
+    crate::settings::update(|s| {
+        s.device_pins.remove(&process_name);
+    });
+    Ok(())
+}
+
+/// Every process name currently pinned to a device, and which device - for a routing
+/// settings UI to show and manage existing pins.
+#[tauri::command]
+pub fn get_device_pins() -> std::result::Result<HashMap<String, crate::settings::PinnedDevice>, String> {
+    Ok(crate::settings::get().device_pins)
+}
+
+/// Find an active render device by its exact friendly name (e.g. "Headset Earphone
+/// (USB Audio Device)"), for reattaching a pin after the endpoint id a USB device was
+/// assigned before a replug no longer matches anything. `None` (not an error) when nothing
+/// currently active has that name.
+#[tauri::command]
+pub fn get_device_by_name(name: String) -> std::result::Result<Option<AudioDeviceInfo>, String> {
+    Ok(AudioManager::list_audio_devices()?.into_iter().find(|d| d.device_name == name))
+}
+
+/// Re-resolve every `settings::device_pins` entry whose `device_id` is no longer among the
+/// currently active devices against `get_device_by_name`, updating the pin's `device_id` in
+/// place when a device with the same friendly name is found - the fix for a USB headset
+/// losing its pinned sessions on every unplug/replug because Windows re-enumerates it under a
+/// new endpoint id. Returns the process names actually reattached; a pin whose device hasn't
+/// come back yet is left as-is rather than dropped, so it reattaches on a later call instead
+/// of being lost.
+#[tauri::command]
+pub fn reattach_pinned_sessions() -> std::result::Result<Vec<String>, String> {
+    let pins = crate::settings::get().device_pins;
+    let active_devices = AudioManager::list_audio_devices()?;
+    let active_ids: std::collections::HashSet<&str> = active_devices.iter().map(|d| d.device_id.as_str()).collect();
+
+    let mut reattached = Vec::new();
+    for (process_name, pin) in pins {
+        if active_ids.contains(pin.device_id.as_str()) {
+            continue;
+        }
+        if let Some(found) = active_devices.iter().find(|d| d.device_name == pin.device_name) {
+            let new_device_id = found.device_id.clone();
+            crate::settings::update(|s| {
+                if let Some(pin) = s.device_pins.get_mut(&process_name) {
+                    pin.device_id = new_device_id.clone();
+                }
+            });
+            reattached.push(process_name);
+        }
+    }
+
+    Ok(reattached)
+}
+
+/// Mute every session without a tag (see `set_session_tag`), recording each one's prior mute
+/// state first so `deactivate_priority_mode` can restore exactly what was there rather than
+/// blindly unmuting everything. Errors if priority mode is already active.
+#[tauri::command]
+pub fn activate_priority_mode(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    if crate::settings::get().priority_mode_prior_mutes.is_some() {
+        return Err("Priority mode is already active".to_string());
+    }
+
+    let tags = crate::settings::get().session_tags;
+    let prior_mutes = manager.activate_priority_mode(&tags)?;
+
+    crate::settings::update(|s| s.priority_mode_prior_mutes = Some(prior_mutes));
+    Ok(())
+}
+
+/// Restore the mute state every session had before `activate_priority_mode` ran. Errors if
+/// priority mode isn't active.
+#[tauri::command]
+pub fn deactivate_priority_mode(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    let prior_mutes = crate::settings::get().priority_mode_prior_mutes
+        .ok_or("Priority mode is not active")?;
+
+    manager.deactivate_priority_mode(prior_mutes)?;
+
+    crate::settings::update(|s| s.priority_mode_prior_mutes = None);
+    Ok(())
+}
+
+/// Play a short sine-wave test tone through a specific output device, for calibrating
+/// relative levels between devices (e.g. headset vs speakers) without routing through
+/// an application session.
+#[tauri::command]
+pub fn play_test_tone(device_id: String, frequency_hz: f32, seconds: f32, level: f32) -> std::result::Result<(), String> {
+    AudioManager::play_test_tone(&device_id, frequency_hz, seconds, level)
+}
+
+/// Experimental: duplicate `session_id`'s output to a second device via a WASAPI loopback
+/// capture + render bridge, for streamers who want the same audio in their headset and
+/// captured for OBS at once - see `AudioManager::duplicate_session_to_device` for exactly
+/// what is and isn't isolated. Starting a new bridge replaces any bridge already running.
+#[tauri::command]
+pub fn duplicate_session_to_device(session_id: String, target_device_id: String) -> std::result::Result<(), String> {
+    AudioManager::duplicate_session_to_device(&session_id, &target_device_id)
+}
+
+/// Stop the experimental session-duplication bridge started by `duplicate_session_to_device`,
+/// if one is running.
+#[tauri::command]
+pub fn stop_session_duplication() -> std::result::Result<(), String> {
+    AudioManager::stop_session_duplication();
+    Ok(())
+}
+
+/// Briefly make `session_id` audible (and optionally route it to `target_device` or solo it)
+/// to confirm which app a strip belongs to - see `AudioManager::start_monitor_session`. Call
+/// `stop_monitor_session` to put everything back; starting a second monitor before that
+/// returns an error rather than silently replacing the first.
+#[tauri::command]
+pub fn start_monitor_session(
+    session_id: String,
+    target_device: Option<String>,
+    solo: bool,
+    state: tauri::State<'_, AudioManagerState>,
+) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.start_monitor_session(&session_id, target_device.as_deref(), solo)
+}
+
+/// Undo the most recent `start_monitor_session`.
+#[tauri::command]
+pub fn stop_monitor_session(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<(), String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
+    manager.stop_monitor_session()
+}
+
+/// Get stream health diagnostics (latency, format, buffer size) for an endpoint. Defaults to
+/// the current default render device when `device_id` is omitted.
+#[tauri::command]
+pub fn get_audio_diagnostics(device_id: Option<String>) -> std::result::Result<AudioDiagnostics, String> {
+    AudioManager::get_diagnostics(device_id.as_deref())
+}
+
+/// Dump every session Windows' enumerator reports on every render and capture endpoint,
+/// with no filtering applied - see `AudioManager::debug_dump_sessions`. For diagnosing
+/// "app X doesn't appear" reports against what `get_audio_sessions` actually shows.
+#[tauri::command]
+pub fn debug_dump_sessions() -> std::result::Result<Vec<DebugSessionDump>, String> {
+    AudioManager::debug_dump_sessions()
+}
+
+/// Report which optional capture-chain features (boost, AGC, noise suppression) a
+/// capture device's driver exposes via its topology parts
+#[tauri::command]
+pub fn get_capture_features(device_id: String) -> std::result::Result<CaptureFeatureSupport, String> {
+    AudioManager::get_capture_features(&device_id)
+}
+
+/// Toggle a capture-chain feature (boost, AGC, noise suppression) on a capture device
+#[tauri::command]
+pub fn set_capture_feature(device_id: String, feature: CaptureFeature, value: bool) -> std::result::Result<(), String> {
+    AudioManager::set_capture_feature(&device_id, feature, value)
+}
+
+/// Toggle "Allow applications to take exclusive control of this device" (Sound Control
+/// Panel, device Properties > Advanced) for an endpoint - see
+/// `AudioManager::set_exclusive_mode_allowed`. Needs ClearComms running elevated.
+#[tauri::command]
+pub fn set_exclusive_mode_allowed(device_id: String, allowed: bool) -> std::result::Result<(), String> {
+    AudioManager::set_exclusive_mode_allowed(&device_id, allowed)
+}
+
+/// Current state of the exclusive-mode checkbox for an endpoint - see
+/// `set_exclusive_mode_allowed`.
+#[tauri::command]
+pub fn get_exclusive_mode_allowed(device_id: String) -> std::result::Result<bool, String> {
+    AudioManager::get_exclusive_mode_allowed(&device_id)
+}
+
+/// Runtime-probed feature support for the current system - see `Capabilities`. Doesn't need
+/// an initialised `AudioManager` (same reason `get_exclusive_mode_allowed` doesn't), so the
+/// frontend can call this before `init_audio_manager` to decide what to even show.
+#[tauri::command]
+pub fn get_capabilities() -> std::result::Result<Capabilities, String> {
+    Ok(AudioManager::capabilities())
+}
+
+/// Set how the channel strip orders sessions returned by `get_audio_sessions`
+#[tauri::command]
+pub fn set_session_sort(mode: SortMode) -> std::result::Result<(), String> {
+    crate::settings::update(|s| s.sort_mode = mode);
+    Ok(())
+}
+
+/// Persist the channel strip's manual drag-and-drop order, keyed by process name rather
+/// than session id so it survives session id churn (a process closing and reopening, a
+/// device switch re-enumerating everything). Doesn't switch `sort_mode` to `SortMode::Manual`
+/// itself - call `set_session_sort(SortMode::Manual)` to actually apply it, same as any other
+/// sort mode change. See `sort_sessions` for how `manual_session_order` is applied.
+#[tauri::command]
+pub fn set_session_order(order: Vec<String>) -> std::result::Result<(), String> {
+    crate::settings::update(|s| s.manual_session_order = order);
+    Ok(())
+}
+
+/// Current manual drag-and-drop order, for the channel strip to restore its layout on
+/// startup - see `set_session_order`.
+#[tauri::command]
+pub fn get_session_order() -> std::result::Result<Vec<String>, String> {
+    Ok(crate::settings::get().manual_session_order)
+}
+
+/// Check if the default audio device has changed. Debounced against transient device-switch
+/// noise (see `AudioManager::check_device_changed`), so returns `None` until a new endpoint
+/// has been stable across two consecutive polls, and `Some` with its ID and friendly name once it has.
+#[tauri::command]
+pub fn check_default_device_changed(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<Option<DefaultDeviceChange>, String> {
+    let mut lock = lock_audio_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
+
     manager.check_device_changed()
 }
 
+/// Get the given role's default audio device's endpoint ID and friendly name, so a caller
+/// can compare against a previously-fetched value deterministically instead of relying
+/// solely on `check_default_device_changed`'s polling debounce (which only tracks the
+/// console role). Defaults to `DeviceRole::Console` when `role` is omitted.
+#[tauri::command]
+pub fn get_default_device(role: Option<DeviceRole>) -> std::result::Result<DefaultDeviceInfo, String> {
+    AudioManager::get_default_device(role.unwrap_or_default())
+}
+
+/// List every active render device with a friendly name, for a device picker in the UI
+/// rather than just the system default.
+#[tauri::command]
+pub fn list_audio_devices() -> std::result::Result<Vec<AudioDeviceInfo>, String> {
+    AudioManager::list_audio_devices()
+}
+
+/// List every render endpoint regardless of state, for a device picker that also needs to
+/// show a pinned device while it's disabled/unplugged/not present - see
+/// `AudioManager::list_all_devices`. `list_audio_devices` remains the one to call for "devices
+/// a session could actually be routed to right now".
+#[tauri::command]
+pub fn list_all_devices() -> std::result::Result<Vec<AudioEndpointInfo>, String> {
+    AudioManager::list_all_devices()
+}
+
 /// Clean up audio manager resources
 #[tauri::command]
-pub fn cleanup_audio_manager() -> std::result::Result<String, String> {
-    let mut lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn cleanup_audio_manager(state: tauri::State<'_, AudioManagerState>) -> std::result::Result<String, String> {
+    let mut lock = lock_audio_manager(&state);
+
     match lock.as_mut() {
         Some(manager) => {
             manager.cleanup();
@@ -679,58 +3938,55 @@ pub fn cleanup_audio_manager() -> std::result::Result<String, String> {
     }
 }
 
-/// Get the system (device endpoint) master volume level
+/// Get the given role's default device's master volume level. Defaults to
+/// `DeviceRole::Console` (today's behaviour) when `role` is omitted, so a headset set as the
+/// communications default can still be targeted by passing `DeviceRole::Communications`.
 #[tauri::command]
-pub fn get_system_volume() -> std::result::Result<f32, String> {
-    let lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn get_system_volume(state: tauri::State<'_, AudioManagerState>, role: Option<DeviceRole>) -> std::result::Result<f32, String> {
+    let lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_ref()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
-    manager.get_system_volume()
+
+    manager.get_system_volume(role.unwrap_or_default())
 }
 
-/// Get the system (device endpoint) mute state
+/// Get the given role's default device's mute state. Defaults to `DeviceRole::Console`
+/// when `role` is omitted - see `get_system_volume`.
 #[tauri::command]
-pub fn get_system_mute() -> std::result::Result<bool, String> {
-    let lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn get_system_mute(state: tauri::State<'_, AudioManagerState>, role: Option<DeviceRole>) -> std::result::Result<bool, String> {
+    let lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_ref()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
-    manager.get_system_mute()
+
+    manager.get_system_mute(role.unwrap_or_default())
 }
 
-/// Set the system (device endpoint) master volume level
+/// Set the given role's default device's master volume level. Defaults to
+/// `DeviceRole::Console` when `role` is omitted - see `get_system_volume`.
 #[tauri::command]
-pub fn set_system_volume(volume: f32) -> std::result::Result<(), String> {
-    let lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn set_system_volume(state: tauri::State<'_, AudioManagerState>, volume: f32, role: Option<DeviceRole>) -> std::result::Result<(), String> {
+    let lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_ref()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
-    manager.set_system_volume(volume)
+
+    manager.set_system_volume(role.unwrap_or_default(), volume)
 }
 
-/// Set the system (device endpoint) mute state
+/// Set the given role's default device's mute state. Defaults to `DeviceRole::Console`
+/// when `role` is omitted - see `get_system_volume`.
 #[tauri::command]
-pub fn set_system_mute(muted: bool) -> std::result::Result<(), String> {
-    let lock = AUDIO_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock audio manager mutex: {}", e))?;
-    
+pub fn set_system_mute(state: tauri::State<'_, AudioManagerState>, muted: bool, role: Option<DeviceRole>) -> std::result::Result<(), String> {
+    let lock = lock_audio_manager(&state);
+
     let manager = lock
         .as_ref()
         .ok_or("Audio manager not initialised. Call init_audio_manager first.")?;
-    
-    manager.set_system_mute(muted)
+
+    manager.set_system_mute(role.unwrap_or_default(), muted)
 }
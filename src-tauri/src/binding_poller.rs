@@ -0,0 +1,880 @@
+//! Background binding poller
+//!
+//! ClearComms lives in the tray with its window usually hidden, and a hidden/unfocused
+//! WebView2 webview throttles `setInterval` timers - so axis/button bindings applied purely
+//! from the frontend's own poll loop would stop working exactly when they're needed most.
+//! This runs the same axis->volume and button->mute/keystroke logic on its own Rust thread
+//! (spawned unconditionally from `main.rs`'s `setup`, the same way the theme-change and
+//! auto-hide pollers are), so bindings keep firing regardless of window visibility.
+//!
+//! The frontend still owns binding CRUD/persistence (`AxisMapping`/`ButtonMapping` live in
+//! its own `localStorage`-backed state) - it just pushes the current lists down here via
+//! `sync_axis_mappings`/`sync_button_mappings` whenever they change, the same "frontend owns
+//! the data, Rust owns the live-applying" split `INPUT_LOCKED` already uses.
+//!
+//! Deliberately out of scope for this first pass: the frontend's `bindingsDryRun` toggle (a
+//! UI-only debug aid) and the smooth hardware-volume animation `startHardwareVolumeInterpolation`
+//! drives - both only matter while the window is actually visible, and applying a value
+//! instantly is the more correct behaviour for a background thread anyway.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::audio_management::AudioManagerState;
+use crate::hardware_input::{self, strip_instance_suffix, AxisCurve, AxisData, InputManagerState};
+use crate::midi_input::{self, MidiInputManagerState};
+
+/// How often the poller re-reads hardware/MIDI axis data and applies bindings - matches the
+/// frontend's own `audioMonitorInterval`-independent axis poll cadence.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often the poller refreshes its own audio-session snapshot. Session enumeration is
+/// comparatively expensive (it walks the live COM session list), so this runs far less often
+/// than the axis poll - same 1s cadence the frontend's `audioMonitorInterval` already uses.
+const SESSION_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How far an axis has to move from its last-seen value before a freshly-loaded binding
+/// "activates" and starts applying - matches the frontend's own activation threshold, so a
+/// lever left mid-travel on launch doesn't instantly snap a session's volume to wherever it
+/// happens to be sitting.
+const AXIS_ACTIVATION_THRESHOLD: f32 = 0.05;
+
+/// Smallest axis change worth re-applying, once activated - matches the frontend's threshold.
+const AXIS_CHANGE_THRESHOLD: f32 = 0.01;
+
+/// Axis values within this distance of 0.0/1.0 are snapped flat - matches the frontend's deadzone.
+const AXIS_DEADZONE: f32 = 0.01;
+
+/// Minimum gap between `"binding-applied"` events for the same binding, so a continuous axis
+/// sweep doesn't flood the frontend with one event per 50ms poll tick - the committed
+/// volume/mute change itself still applies every tick; only the UI-facing event is throttled.
+/// Looser than `audio_management`'s `VOLUME_EMIT_THROTTLE` since this feeds a human-readable
+/// activity log rather than a live meter.
+const BINDING_APPLIED_THROTTLE: Duration = Duration::from_millis(150);
+
+/// Payload for the `"binding-applied"` event, emitted once per binding each time the poller
+/// actually commits a volume/mute change to the audio API - not on every poll tick, see
+/// `BINDING_APPLIED_THROTTLE`. Lets a visible activity log show what the hardware is doing,
+/// and lets a user (or developer) trace an unexpected volume change back to whichever
+/// binding caused it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingApplied {
+    /// Identifies the binding the same way the poller's own activation tracking does - see
+    /// `apply_axis_mappings`'s `mapping_key`/`apply_button_mappings`'s `binding_key`. Bindings
+    /// have no separate persisted ID of their own, so this composite key is the only stable
+    /// handle available.
+    pub binding_id: String,
+    pub session_id: String,
+    pub process_name: String,
+    /// "volume" or "mute" - which audio API call this binding just drove.
+    pub kind: String,
+    pub old_value: f32,
+    pub new_value: f32,
+    /// The hardware axis position (0.0-1.0) that drove this change. `None` for button-driven
+    /// mute toggles, which have no underlying axis value.
+    pub axis_value: Option<f32>,
+}
+
+#[derive(Default)]
+struct BindingEmitState {
+    last_emit: Option<Instant>,
+    pending: Option<BindingApplied>,
+}
+
+/// Per-binding throttle state for `emit_binding_applied_coalesced`, shared with the deferred
+/// flush thread it spawns - same shape as `audio_management`'s `volume_emit_state`.
+type BindingEmitCache = Arc<Mutex<HashMap<String, BindingEmitState>>>;
+
+/// Emit `"binding-applied"` for `applied`, throttled and coalesced per binding the same way
+/// `audio_management::emit_volume_change_coalesced` throttles session-volume events - see
+/// `BINDING_APPLIED_THROTTLE`. Emits immediately if this binding hasn't fired within the
+/// throttle window; otherwise stashes `applied` as the latest pending value and, if nothing's
+/// already scheduled, spawns a one-shot thread to flush it once the window closes, so the
+/// final value from a burst of axis movement is always delivered rather than dropped.
+fn emit_binding_applied_coalesced(cache: &BindingEmitCache, app: &tauri::AppHandle, applied: BindingApplied) {
+    let mut states = cache.lock().unwrap();
+    let now = Instant::now();
+
+    let entry = states.entry(applied.binding_id.clone()).or_default();
+    let elapsed_since_last = entry.last_emit.map(|last| now.duration_since(last));
+
+    if elapsed_since_last.map_or(true, |elapsed| elapsed >= BINDING_APPLIED_THROTTLE) {
+        entry.last_emit = Some(now);
+        entry.pending = None;
+        drop(states);
+        let _ = app.emit("binding-applied", applied);
+        return;
+    }
+
+    let already_scheduled = entry.pending.is_some();
+    entry.pending = Some(applied.clone());
+    if already_scheduled {
+        return;
+    }
+
+    let delay = BINDING_APPLIED_THROTTLE.saturating_sub(elapsed_since_last.expect("checked above"));
+    drop(states);
+
+    let cache = cache.clone();
+    let app = app.clone();
+    let binding_id = applied.binding_id;
+    thread::spawn(move || {
+        thread::sleep(delay);
+        let mut states = cache.lock().unwrap();
+        if let Some(entry) = states.get_mut(&binding_id) {
+            if let Some(final_applied) = entry.pending.take() {
+                entry.last_emit = Some(Instant::now());
+                drop(states);
+                let _ = app.emit("binding-applied", final_applied);
+            }
+        }
+    });
+}
+
+/// What an `AxisMapping` does to its session if its axis's device stops reporting data mid-poll
+/// (unplugged, or - in principle - a disconnected SimVar source once one exists) - formalizes
+/// what was previously just whatever `apply_axis_mappings` happened to do when `resolve_device`
+/// came back empty: nothing, i.e. `Hold`. Applied once per disconnect, not every poll tick, so
+/// `Default`/`Unmute` don't fight a user who's deliberately changed the session since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectPolicy {
+    /// Leave the session at whatever value the binding last applied - the behaviour every
+    /// binding had before this field existed.
+    Hold,
+    /// Snap the session to `AxisMapping::disconnect_default_value` (0.0 if unset) and unmute it.
+    Default,
+    /// Force the session unmuted, leaving its volume wherever it was.
+    Unmute,
+}
+
+impl Default for DisconnectPolicy {
+    fn default() -> Self {
+        DisconnectPolicy::Hold
+    }
+}
+
+/// The subset of `AxisMapping` (see the frontend's `src/lib/types/index.ts`) the poller needs
+/// to apply a binding. `#[serde(rename_all = "camelCase")]` so this deserialises straight from
+/// the same objects the frontend already has in `axisMappings`, without a re-shaping step on
+/// either side; any other fields (`sessionId`, `deviceName`, ...) are simply ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisMapping {
+    pub device_handle: String,
+    pub device_key: Option<String>,
+    pub axis_name: String,
+    pub process_name: String,
+    pub inverted: bool,
+    pub curve: Option<AxisCurve>,
+    /// Axis sub-range `[range_min, range_max]` this binding only fires within - e.g. the
+    /// reverse travel past a throttle's idle detent. `None` (the default for either bound)
+    /// means the full 0.0-1.0 range, unchanged from before this field existed. The position
+    /// inside the zone is rescaled back to 0.0-1.0 before `curve`/volume are applied, so a
+    /// narrow zone still gets the binding's full sweep rather than a clamp of the raw value -
+    /// see `zone_value`.
+    #[serde(default)]
+    pub range_min: Option<f32>,
+    #[serde(default)]
+    pub range_max: Option<f32>,
+    /// Button (on the same device as `axis_name`) that must be held for this binding to fire -
+    /// e.g. a detent-engage button gating a reverse/afterburner zone. `None` (the default)
+    /// means always gated on, unchanged from before this field existed.
+    #[serde(default)]
+    pub gate_button_name: Option<String>,
+    /// What to do to the session this binding controls if its axis's device disconnects -
+    /// see `DisconnectPolicy`. `Hold` (the default) reproduces every binding's behaviour from
+    /// before this field existed.
+    #[serde(default)]
+    pub on_disconnect: DisconnectPolicy,
+    /// Volume `DisconnectPolicy::Default` snaps the session to - ignored for every other
+    /// policy. `None` (the default) falls back to silence.
+    #[serde(default)]
+    pub disconnect_default_value: Option<f32>,
+}
+
+/// Mirrors `ButtonMapping.actionType` - see the frontend's `src/lib/types/index.ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonActionType {
+    Mute,
+    Keystroke,
+    MomentaryMute,
+    ToggleInputLock,
+    VolumeUp,
+    VolumeDown,
+}
+
+impl Default for ButtonActionType {
+    fn default() -> Self {
+        ButtonActionType::Mute
+    }
+}
+
+/// The subset of `ButtonMapping` the poller needs - see `AxisMapping`'s doc comment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ButtonMapping {
+    pub device_handle: String,
+    pub device_key: Option<String>,
+    pub button_name: String,
+    pub process_name: String,
+    #[serde(default)]
+    pub action_type: ButtonActionType,
+    #[serde(default)]
+    pub keystroke_vk_codes: Vec<u16>,
+}
+
+/// The frontend's current binding lists, pushed down via `sync_axis_mappings`/
+/// `sync_button_mappings` - see the module doc comment.
+#[derive(Default)]
+pub struct BindingCache {
+    axis_mappings: Vec<AxisMapping>,
+    button_mappings: Vec<ButtonMapping>,
+}
+
+pub type BindingCacheState = Mutex<BindingCache>;
+
+/// Replace the axis mappings the background poller applies. Called by the frontend on load
+/// and every time `axisMappings` changes, so the poller never acts on a stale list.
+#[tauri::command]
+pub fn sync_axis_mappings(state: tauri::State<'_, BindingCacheState>, mappings: Vec<AxisMapping>) -> Result<(), String> {
+    let mut cache = state.lock().map_err(|e| format!("Failed to lock binding cache mutex: {}", e))?;
+    cache.axis_mappings = mappings;
+    Ok(())
+}
+
+/// Replace the button mappings the background poller applies - see `sync_axis_mappings`.
+#[tauri::command]
+pub fn sync_button_mappings(state: tauri::State<'_, BindingCacheState>, mappings: Vec<ButtonMapping>) -> Result<(), String> {
+    let mut cache = state.lock().map_err(|e| format!("Failed to lock binding cache mutex: {}", e))?;
+    cache.button_mappings = mappings;
+    Ok(())
+}
+
+/// A template binding resolved against a concrete device and a currently running session,
+/// returned by `apply_binding_template`. Shaped to match the frontend's `AxisMapping` (minus
+/// `gateButtonName`, which is a physical-device detail a template doesn't capture, and the
+/// `onDisconnect`/`disconnectDefaultValue` disconnect policy) so the
+/// frontend can save it into `axisMappings` the same way as any other binding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedTemplateBinding {
+    pub device_handle: String,
+    pub device_key: String,
+    pub device_name: String,
+    pub axis_name: String,
+    pub session_id: String,
+    pub session_name: String,
+    pub process_id: u32,
+    pub process_name: String,
+    pub inverted: bool,
+    pub curve: Option<AxisCurve>,
+    pub range_min: Option<f32>,
+    pub range_max: Option<f32>,
+}
+
+/// Capture the given axis mappings as a reusable template under `name`, keyed by each mapping's
+/// axis label (set via `hardware_input::set_axis_label`) and target process name rather than
+/// its concrete `device_key`/session - so `apply_binding_template` can later replay the same
+/// shape against a different device exposing equivalently-labelled axes. Mappings whose bound
+/// axis has no custom label are skipped, since a raw DirectInput name like "Z Axis" isn't a
+/// stable enough identity to match against on a different controller. Overwrites any existing
+/// template with the same name. Returns how many of the given mappings were actually captured.
+#[tauri::command]
+pub fn save_binding_template(name: String, mappings: Vec<AxisMapping>) -> Result<usize, String> {
+    if name.trim().is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+
+    let axis_labels = crate::settings::get().axis_labels;
+    let mut bindings = Vec::new();
+
+    for mapping in &mappings {
+        let Some(device_key) = &mapping.device_key else { continue };
+        let identity = strip_instance_suffix(device_key);
+        let Some(label) = axis_labels.get(identity).and_then(|labels| labels.get(&mapping.axis_name)) else { continue };
+
+        bindings.push(crate::settings::TemplateAxisBinding {
+            axis_label: label.clone(),
+            target_role: mapping.process_name.clone(),
+            inverted: mapping.inverted,
+            curve: mapping.curve,
+            range_min: mapping.range_min,
+            range_max: mapping.range_max,
+        });
+    }
+
+    if bindings.is_empty() {
+        return Err("None of the given mappings have a labelled axis to save".to_string());
+    }
+
+    let captured = bindings.len();
+    crate::settings::update(|s| {
+        s.binding_templates.insert(name, crate::settings::BindingTemplate { bindings });
+    });
+    Ok(captured)
+}
+
+/// Instantiate the template named `name` against `device_id` (see `hardware_input::DeviceInfo`)
+/// and whatever sessions are currently running: each captured `axis_label` is matched against
+/// that device's own labelled axes, and each `target_role` is matched by process name against
+/// `AudioManager`'s cached sessions. Bindings whose axis label or target process aren't
+/// currently present are silently dropped rather than erroring the whole call, since a template
+/// built for one aircraft's control layout won't always fully apply to another. Returns the
+/// resolved bindings for the frontend to merge into `axisMappings` and persist itself - this
+/// command only produces candidates, it doesn't touch frontend storage or call
+/// `sync_axis_mappings`.
+#[tauri::command]
+pub fn apply_binding_template(
+    name: String,
+    device_id: u32,
+    input_state: tauri::State<'_, InputManagerState>,
+    audio_state: tauri::State<'_, AudioManagerState>,
+) -> Result<Vec<ResolvedTemplateBinding>, String> {
+    let template = crate::settings::get().binding_templates.get(&name).cloned()
+        .ok_or_else(|| format!("No binding template named \"{}\"", name))?;
+
+    let devices = hardware_input::get_all_axis_values(input_state)?;
+    let device = devices.iter().find(|d| d.device_handle == device_id.to_string())
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let audio_lock = crate::audio_management::lock_audio_manager(&audio_state);
+    let sessions = audio_lock.as_ref()
+        .ok_or("Audio manager not initialised")?
+        .cached_sessions();
+
+    let mut resolved = Vec::new();
+    for binding in &template.bindings {
+        let Some((axis_name, _)) = device.axis_labels.iter().find(|(_, label)| *label == &binding.axis_label) else { continue };
+        let Some(session) = sessions.values().find(|s| s.process_name == binding.target_role) else { continue };
+
+        resolved.push(ResolvedTemplateBinding {
+            device_handle: device.device_handle.clone(),
+            device_key: device.device_key.clone(),
+            device_name: device.device_name.clone(),
+            axis_name: axis_name.clone(),
+            session_id: session.session_id.clone(),
+            session_name: session.display_name.clone(),
+            process_id: session.process_id,
+            process_name: session.process_name.clone(),
+            inverted: binding.inverted,
+            curve: binding.curve,
+            range_min: binding.range_min,
+            range_max: binding.range_max,
+        });
+    }
+
+    Ok(resolved)
+}
+
+/// Remove a saved binding template. No error if `name` doesn't exist.
+#[tauri::command]
+pub fn delete_binding_template(name: String) -> Result<(), String> {
+    crate::settings::update(|s| { s.binding_templates.remove(&name); });
+    Ok(())
+}
+
+/// List the names of all saved binding templates, for a template picker UI.
+#[tauri::command]
+pub fn list_binding_templates() -> Result<Vec<String>, String> {
+    Ok(crate::settings::get().binding_templates.keys().cloned().collect())
+}
+
+/// Timing summary from `measure_binding_latency`, in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyMeasurement {
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+    pub iterations: u32,
+}
+
+/// Iterations `measure_binding_latency` runs, and the two volumes it alternates the probe
+/// between so each call is a genuine change rather than a no-op `SetMasterVolume`.
+const LATENCY_PROBE_ITERATIONS: u32 = 10;
+const LATENCY_PROBE_VOLUMES: [f32; 2] = [0.2, 0.8];
+
+/// Time how long the binding named by `binding_id` (the same `{deviceHandle}-{axisName}-
+/// {processName}` key `apply_axis_mappings` uses for `BindingApplied.binding_id`) takes to
+/// apply a volume change, end-to-end from the curve-adjusted value to the confirmed
+/// `ISimpleAudioVolume::SetMasterVolume` return. There's no way to inject a value into an
+/// actual hardware axis read, so this measures the software/COM leg only - which is the part
+/// `POLL_INTERVAL` and the binding-applied throttle actually have any control over - by
+/// alternating the bound session's volume between two known probe values a few times and
+/// timing each `set_session_volume` call directly. Restores the session's original
+/// volume/mute once done.
+#[tauri::command]
+pub fn measure_binding_latency(
+    binding_id: String,
+    audio_state: tauri::State<'_, AudioManagerState>,
+    cache_state: tauri::State<'_, BindingCacheState>,
+) -> Result<LatencyMeasurement, String> {
+    let mapping = {
+        let cache = cache_state.lock().map_err(|e| format!("Failed to lock binding cache mutex: {}", e))?;
+        cache.axis_mappings.iter()
+            .find(|m| format!("{}-{}-{}", m.device_handle, m.axis_name, m.process_name) == binding_id)
+            .cloned()
+            .ok_or_else(|| format!("No axis binding matches \"{}\"", binding_id))?
+    };
+
+    let mut lock = crate::audio_management::lock_audio_manager(&audio_state);
+    let manager = lock.as_mut().ok_or("Audio manager not initialised")?;
+
+    let session = manager.cached_sessions().values()
+        .find(|s| s.process_name == mapping.process_name)
+        .cloned()
+        .ok_or_else(|| format!("No running session for \"{}\"", mapping.process_name))?;
+
+    let mut durations_ms = Vec::with_capacity(LATENCY_PROBE_ITERATIONS as usize);
+    for i in 0..LATENCY_PROBE_ITERATIONS {
+        let probe = apply_volume_curve(LATENCY_PROBE_VOLUMES[(i % 2) as usize], mapping.curve);
+        let start = Instant::now();
+        manager.set_session_volume(&session.session_id, probe)?;
+        durations_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    let _ = manager.set_session_volume(&session.session_id, session.volume);
+    let _ = manager.set_session_mute(&session.session_id, session.is_muted);
+
+    let min_ms = durations_ms.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max_ms = durations_ms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let avg_ms = durations_ms.iter().sum::<f64>() / durations_ms.len() as f64;
+
+    Ok(LatencyMeasurement { min_ms, avg_ms, max_ms, iterations: LATENCY_PROBE_ITERATIONS })
+}
+
+/// Find the `AxisData` entry a binding targets, preferring the stable `device_key` when the
+/// mapping has one and falling back to the Windows joystick slot - mirrors the frontend's own
+/// `resolveBindingDevice`.
+fn resolve_device<'a>(devices: &'a [AxisData], device_key: &Option<String>, device_handle: &str) -> Option<&'a AxisData> {
+    if let Some(key) = device_key {
+        if let Some(found) = devices.iter().find(|d| &d.device_key == key) {
+            return Some(found);
+        }
+    }
+    devices.iter().find(|d| d.device_handle == device_handle)
+}
+
+/// Mirrors the frontend's `applyVolumeCurve`.
+fn apply_volume_curve(value: f32, curve: Option<AxisCurve>) -> f32 {
+    match curve {
+        Some(AxisCurve::Logarithmic) => value * value,
+        _ => value,
+    }
+}
+
+/// Rescale `value` to 0.0-1.0 within a binding's `[range_min, range_max]` zone, or pass it
+/// through unchanged when the binding has no zone set. Returns `None` when `value` falls
+/// outside the zone, so a reverse/afterburner detent binding only fires across its own slice
+/// of travel instead of the axis's full range - see `AxisMapping::range_min`.
+fn zone_value(value: f32, range_min: Option<f32>, range_max: Option<f32>) -> Option<f32> {
+    match (range_min, range_max) {
+        (Some(min), Some(max)) if max > min => {
+            if value < min || value > max {
+                None
+            } else {
+                Some((value - min) / (max - min))
+            }
+        }
+        _ => Some(value),
+    }
+}
+
+/// Read the latest axis/button data from every input source (joysticks plus any connected
+/// MIDI port), the same merged list `+page.svelte`'s `getAxisValues` builds for the frontend.
+fn read_axis_data(app: &tauri::AppHandle) -> Vec<AxisData> {
+    let mut devices = hardware_input::get_all_axis_values(app.state::<InputManagerState>()).unwrap_or_default();
+    devices.extend(midi_input::get_all_midi_axis_values(app.state::<MidiInputManagerState>()).unwrap_or_default());
+    devices
+}
+
+/// Re-enumerate audio sessions into `AudioManager`'s own cache (without emitting any of the
+/// discovery/volume-changed events `enumerate_sessions` normally fires for the frontend's
+/// benefit), so `find_session_by_process` has something current to search even while the
+/// frontend's own `audioMonitorInterval` poll is throttled.
+fn refresh_sessions(app: &tauri::AppHandle) {
+    let mut lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+    if let Some(manager) = lock.as_mut() {
+        let _ = manager.enumerate_sessions(None);
+    }
+    drop(lock);
+    reattach_pinned_sessions(app);
+}
+
+/// Re-match any device pin whose endpoint id has gone stale against the currently active
+/// devices by friendly name - there's no real device-arrival notification anywhere in
+/// ClearComms, so this piggybacks on the same 1s cadence as the rest of `refresh_sessions`
+/// rather than reacting only once when a USB device is replugged.
+fn reattach_pinned_sessions(app: &tauri::AppHandle) {
+    match crate::audio_management::reattach_pinned_sessions() {
+        Ok(reattached) => {
+            for process_name in reattached {
+                tracing::info!("[Binding] Reattached pinned device for {}", process_name);
+                let _ = app.emit("device-pin-reattached", &process_name);
+            }
+        }
+        Err(e) => tracing::warn!("[Binding] Failed to reattach pinned sessions: {}", e),
+    }
+}
+
+fn find_session_by_process(app: &tauri::AppHandle, process_name: &str) -> Option<crate::audio_management::AudioSession> {
+    let lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+    let manager = lock.as_ref()?;
+    manager.cached_sessions().values().find(|s| s.process_name == process_name).cloned()
+}
+
+/// Apply every axis mapping against the current poll's axis data - the background-thread
+/// counterpart to `+page.svelte`'s `applyAxisMappings`.
+fn apply_axis_mappings(
+    app: &tauri::AppHandle,
+    devices: &[AxisData],
+    mappings: &[AxisMapping],
+    last_values: &mut HashMap<String, f32>,
+    activated: &mut HashMap<String, bool>,
+    disconnected: &mut HashMap<String, bool>,
+    binding_emit_cache: &BindingEmitCache,
+) {
+    for mapping in mappings {
+        let mapping_key = format!("{}-{}-{}", mapping.device_handle, mapping.axis_name, mapping.process_name);
+
+        let device = resolve_device(devices, &mapping.device_key, &mapping.device_handle);
+        let raw = device.and_then(|device| device.axes.get(&mapping.axis_name).copied());
+
+        let (Some(device), Some(raw)) = (device, raw) else {
+            if !disconnected.get(&mapping_key).copied().unwrap_or(false) {
+                apply_disconnect_policy(app, mapping, &mapping_key, binding_emit_cache);
+                disconnected.insert(mapping_key, true);
+            }
+            continue;
+        };
+        disconnected.insert(mapping_key.clone(), false);
+
+        let mut value = if mapping.inverted { 1.0 - raw } else { raw };
+        if value < AXIS_DEADZONE {
+            value = 0.0;
+        } else if value > 1.0 - AXIS_DEADZONE {
+            value = 1.0;
+        }
+
+        if let Some(gate) = &mapping.gate_button_name {
+            if !device.buttons.get(gate).copied().unwrap_or(false) {
+                continue;
+            }
+        }
+
+        let Some(value) = zone_value(value, mapping.range_min, mapping.range_max) else { continue };
+
+        let Some(&last_value) = last_values.get(&mapping_key) else {
+            last_values.insert(mapping_key.clone(), value);
+            activated.insert(mapping_key, false);
+            continue;
+        };
+
+        let is_activated = activated.get(&mapping_key).copied().unwrap_or(false);
+        if !is_activated {
+            if (value - last_value).abs() > AXIS_ACTIVATION_THRESHOLD {
+                activated.insert(mapping_key.clone(), true);
+            } else {
+                continue;
+            }
+        }
+
+        if (last_value - value).abs() <= AXIS_CHANGE_THRESHOLD {
+            continue;
+        }
+
+        let Some(session) = find_session_by_process(app, &mapping.process_name) else { continue };
+
+        let volume = apply_volume_curve(value, mapping.curve);
+        let muted = value == 0.0;
+
+        let old_volume = session.volume;
+        let mut lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+        if let Some(manager) = lock.as_mut() {
+            let _ = manager.set_session_volume(&session.session_id, volume);
+            let _ = manager.set_session_mute(&session.session_id, muted);
+        }
+        drop(lock);
+
+        emit_binding_applied_coalesced(binding_emit_cache, app, BindingApplied {
+            binding_id: mapping_key.clone(),
+            session_id: session.session_id.clone(),
+            process_name: mapping.process_name.clone(),
+            kind: "volume".to_string(),
+            old_value: old_volume,
+            new_value: volume,
+            axis_value: Some(value),
+        });
+
+        last_values.insert(mapping_key, value);
+    }
+}
+
+/// Apply `mapping.on_disconnect` the moment its axis's device stops reporting - see
+/// `DisconnectPolicy`. `Hold` is a no-op, reproducing the binding's behaviour from before this
+/// field existed; `Default`/`Unmute` reuse the same audio calls `apply_axis_mappings` itself makes.
+fn apply_disconnect_policy(app: &tauri::AppHandle, mapping: &AxisMapping, mapping_key: &str, binding_emit_cache: &BindingEmitCache) {
+    if mapping.on_disconnect == DisconnectPolicy::Hold {
+        return;
+    }
+
+    let Some(session) = find_session_by_process(app, &mapping.process_name) else { return };
+    let old_volume = session.volume;
+    let new_volume = match mapping.on_disconnect {
+        DisconnectPolicy::Default => mapping.disconnect_default_value.unwrap_or(0.0),
+        DisconnectPolicy::Unmute => old_volume,
+        DisconnectPolicy::Hold => unreachable!("returned above"),
+    };
+
+    let mut lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+    if let Some(manager) = lock.as_mut() {
+        let _ = manager.set_session_volume(&session.session_id, new_volume);
+        let _ = manager.set_session_mute(&session.session_id, false);
+    }
+    drop(lock);
+
+    tracing::info!(
+        "[Binding] Source disconnected for {} - applying {:?}",
+        mapping_key, mapping.on_disconnect
+    );
+
+    emit_binding_applied_coalesced(binding_emit_cache, app, BindingApplied {
+        binding_id: mapping_key.to_string(),
+        session_id: session.session_id.clone(),
+        process_name: mapping.process_name.clone(),
+        kind: "volume".to_string(),
+        old_value: old_volume,
+        new_value: new_volume,
+        axis_value: None,
+    });
+}
+
+/// How long a `VolumeUp`/`VolumeDown` button has been held, for `volume_step_for_hold_duration`
+/// to grow the step size the longer it stays pressed - tracked per binding key, reset on
+/// release so letting go and pressing again always starts back at the smallest step.
+struct HeldVolumeState {
+    first_held: Instant,
+    last_step: Instant,
+}
+
+/// Base step a single tap of a `VolumeUp`/`VolumeDown` button applies.
+const VOLUME_STEP_BASE: f32 = 0.01;
+/// How often a held button repeats its step, once past the first tap - independent of
+/// `POLL_INTERVAL` so acceleration reads as deliberate key-repeat-style steps rather than
+/// 20 tiny nudges a second.
+const VOLUME_STEP_REPEAT_INTERVAL: Duration = Duration::from_millis(120);
+
+/// The step size a `VolumeUp`/`VolumeDown` button applies after being held for `held_for` -
+/// grows in a few discrete stages (1% -> 5% -> 10%) rather than a continuous ramp, so the
+/// user gets a predictable feel for how long to hold for a given swing.
+fn volume_step_for_hold_duration(held_for: Duration) -> f32 {
+    if held_for >= Duration::from_millis(1500) {
+        0.10
+    } else if held_for >= Duration::from_millis(600) {
+        0.05
+    } else {
+        VOLUME_STEP_BASE
+    }
+}
+
+/// Apply every button mapping against the current poll's axis data - the background-thread
+/// counterpart to `+page.svelte`'s `applyButtonMappings`.
+fn apply_button_mappings(
+    app: &tauri::AppHandle,
+    devices: &[AxisData],
+    mappings: &[ButtonMapping],
+    locked: bool,
+    previous_states: &mut HashMap<(String, String), bool>,
+    momentary_prior_mute: &mut HashMap<String, bool>,
+    held_volume: &mut HashMap<String, HeldVolumeState>,
+    binding_emit_cache: &BindingEmitCache,
+) {
+    for mapping in mappings {
+        let Some(device) = resolve_device(devices, &mapping.device_key, &mapping.device_handle) else { continue };
+        let Some(&current) = device.buttons.get(&mapping.button_name) else { continue };
+
+        let key = (mapping.device_handle.clone(), mapping.button_name.clone());
+        let previous = previous_states.get(&key).copied().unwrap_or(false);
+        previous_states.insert(key, current);
+
+        if mapping.action_type == ButtonActionType::ToggleInputLock {
+            // Always live, even while locked - otherwise a button bound to unlock could
+            // never fire. See `crate::set_input_lock`.
+            if !previous && current {
+                crate::set_input_lock_impl(app, !crate::input_lock_engaged());
+            }
+            continue;
+        }
+
+        if locked {
+            continue;
+        }
+
+        match mapping.action_type {
+            ButtonActionType::Keystroke => {
+                if current != previous && !mapping.keystroke_vk_codes.is_empty() {
+                    let _ = crate::send_keystroke_impl(&mapping.keystroke_vk_codes, current);
+                }
+            }
+            ButtonActionType::MomentaryMute => {
+                if current == previous {
+                    continue;
+                }
+                let Some(session) = find_session_by_process(app, &mapping.process_name) else { continue };
+                let binding_key = format!("{}-{}-{}", mapping.device_handle, mapping.button_name, mapping.process_name);
+
+                let mut lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+                let Some(manager) = lock.as_mut() else { continue };
+
+                if current {
+                    momentary_prior_mute.insert(binding_key.clone(), session.is_muted);
+                    if !session.is_muted {
+                        let _ = manager.set_session_mute(&session.session_id, true);
+                        drop(lock);
+                        emit_binding_applied_coalesced(binding_emit_cache, app, BindingApplied {
+                            binding_id: binding_key,
+                            session_id: session.session_id,
+                            process_name: mapping.process_name.clone(),
+                            kind: "mute".to_string(),
+                            old_value: 0.0,
+                            new_value: 1.0,
+                            axis_value: None,
+                        });
+                    }
+                } else {
+                    let prior_muted = momentary_prior_mute.remove(&binding_key).unwrap_or(false);
+                    if session.is_muted != prior_muted {
+                        let _ = manager.set_session_mute(&session.session_id, prior_muted);
+                        drop(lock);
+                        emit_binding_applied_coalesced(binding_emit_cache, app, BindingApplied {
+                            binding_id: binding_key,
+                            session_id: session.session_id,
+                            process_name: mapping.process_name.clone(),
+                            kind: "mute".to_string(),
+                            old_value: 1.0,
+                            new_value: if prior_muted { 1.0 } else { 0.0 },
+                            axis_value: None,
+                        });
+                    }
+                }
+            }
+            ButtonActionType::VolumeUp | ButtonActionType::VolumeDown => {
+                let binding_key = format!("{}-{}-{}", mapping.device_handle, mapping.button_name, mapping.process_name);
+
+                if !current {
+                    held_volume.remove(&binding_key);
+                    continue;
+                }
+
+                let now = Instant::now();
+                let step = if !previous {
+                    held_volume.insert(binding_key.clone(), HeldVolumeState { first_held: now, last_step: now });
+                    VOLUME_STEP_BASE
+                } else {
+                    let Some(held) = held_volume.get_mut(&binding_key) else { continue };
+                    if now.duration_since(held.last_step) < VOLUME_STEP_REPEAT_INTERVAL {
+                        continue;
+                    }
+                    let step = volume_step_for_hold_duration(now.duration_since(held.first_held));
+                    held.last_step = now;
+                    step
+                };
+
+                let Some(session) = find_session_by_process(app, &mapping.process_name) else { continue };
+                let delta = if mapping.action_type == ButtonActionType::VolumeUp { step } else { -step };
+
+                let mut lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+                let Some(manager) = lock.as_mut() else { continue };
+                let Ok(new_volume) = manager.adjust_session_volume(&session.session_id, delta) else { continue };
+                drop(lock);
+
+                emit_binding_applied_coalesced(binding_emit_cache, app, BindingApplied {
+                    binding_id: binding_key,
+                    session_id: session.session_id,
+                    process_name: mapping.process_name.clone(),
+                    kind: "volume".to_string(),
+                    old_value: session.volume,
+                    new_value: new_volume,
+                    axis_value: None,
+                });
+            }
+            _ => {
+                // Default "mute" action: toggle on press.
+                if !previous && current {
+                    if let Some(session) = find_session_by_process(app, &mapping.process_name) {
+                        let binding_key = format!("{}-{}-{}", mapping.device_handle, mapping.button_name, mapping.process_name);
+                        let new_muted = !session.is_muted;
+                        let mut lock = crate::audio_management::lock_audio_manager(&app.state::<AudioManagerState>());
+                        if let Some(manager) = lock.as_mut() {
+                            let _ = manager.set_session_mute(&session.session_id, new_muted);
+                            drop(lock);
+                            emit_binding_applied_coalesced(binding_emit_cache, app, BindingApplied {
+                                binding_id: binding_key,
+                                session_id: session.session_id,
+                                process_name: mapping.process_name.clone(),
+                                kind: "mute".to_string(),
+                                old_value: if session.is_muted { 1.0 } else { 0.0 },
+                                new_value: if new_muted { 1.0 } else { 0.0 },
+                                axis_value: None,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Spawn the background thread that applies axis/button bindings directly, independent of the
+/// frontend's own render loop - see the module doc comment for why this matters while the
+/// window is hidden. Runs for the lifetime of the app, the same as the theme-change and
+/// auto-hide pollers in `main.rs`'s `setup`.
+pub fn spawn(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut last_axis_values: HashMap<String, f32> = HashMap::new();
+        let mut axis_activated: HashMap<String, bool> = HashMap::new();
+        let mut axis_disconnected: HashMap<String, bool> = HashMap::new();
+        let mut previous_button_states: HashMap<(String, String), bool> = HashMap::new();
+        let mut momentary_prior_mute: HashMap<String, bool> = HashMap::new();
+        let mut held_volume: HashMap<String, HeldVolumeState> = HashMap::new();
+        let binding_emit_cache: BindingEmitCache = Arc::new(Mutex::new(HashMap::new()));
+        let mut last_session_refresh = Instant::now() - SESSION_REFRESH_INTERVAL;
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if last_session_refresh.elapsed() >= SESSION_REFRESH_INTERVAL {
+                refresh_sessions(&app);
+                last_session_refresh = Instant::now();
+            }
+
+            // Not skipped when empty (e.g. every joystick unplugged at once) - an empty list
+            // still needs to reach `apply_axis_mappings` so `resolve_device` keeps coming back
+            // empty for every binding and `on_disconnect` actually gets a chance to fire.
+            let devices = read_axis_data(&app);
+
+            let (axis_mappings, button_mappings) = {
+                let cache = app.state::<BindingCacheState>();
+                match cache.lock() {
+                    Ok(cache) => (cache.axis_mappings.clone(), cache.button_mappings.clone()),
+                    Err(_) => continue,
+                }
+            };
+
+            let locked = crate::input_lock_engaged();
+
+            if !locked {
+                apply_axis_mappings(&app, &devices, &axis_mappings, &mut last_axis_values, &mut axis_activated, &mut axis_disconnected, &binding_emit_cache);
+            }
+
+            apply_button_mappings(&app, &devices, &button_mappings, locked, &mut previous_button_states, &mut momentary_prior_mute, &mut held_volume, &binding_emit_cache);
+        }
+    });
+}
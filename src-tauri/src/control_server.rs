@@ -0,0 +1,232 @@
+//! Control server
+//!
+//! Optional, local-only WebSocket control surface so an external controller (a Stream
+//! Deck plugin, a macro tool, a second monitor dashboard) can drive the same volume/mute
+//! commands the UI does, without embedding a full Tauri IPC client. Off by default;
+//! toggled on with a port via `set_control_server`. Bound to 127.0.0.1 only - this is a
+//! local integration point, not a remote one.
+
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tungstenite::{Message, WebSocket};
+
+use crate::audio_management::{lock_audio_manager, AudioManagerState};
+
+/// Bumped on every `set_control_server` call, so an accept-loop thread that's been
+/// superseded (disabled, or restarted on a new port) notices and exits instead of needing
+/// an explicit shutdown signal - same pattern as `hardware_input::AXIS_GRAPH_GENERATION`.
+static CONTROL_SERVER_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// How often the accept loop checks whether it's been superseded while idle.
+const ACCEPT_POLL_INTERVAL_MS: u64 = 200;
+
+/// Tracks the port the control server is currently bound to, if running at all.
+pub type ControlServerState = Mutex<Option<u16>>;
+
+/// Current control server state, returned by `get_control_server_status` and
+/// `set_control_server` so the settings UI can show the address/token to paste into an
+/// external controller's config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlServerStatus {
+    pub enabled: bool,
+    pub port: Option<u16>,
+    pub token: Option<String>,
+}
+
+/// One control message sent by a connected client. `command` selects which field(s) are
+/// read; unused fields are simply absent rather than us defining a separate struct per
+/// command, since every command here is a thin passthrough to an existing Tauri command.
+#[derive(Debug, Deserialize)]
+struct ControlRequest {
+    token: String,
+    command: String,
+    session_id: Option<String>,
+    volume: Option<f32>,
+    muted: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct ControlResponse {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ControlResponse {
+    fn ok() -> Self {
+        Self { ok: true, error: None }
+    }
+
+    fn err(message: impl Into<String>) -> Self {
+        Self { ok: false, error: Some(message.into()) }
+    }
+}
+
+/// Generate an auth token for a newly-enabled control server. Not cryptographically
+/// strong - this guards a loopback-only socket against other local processes, not a
+/// network attacker - but unique enough that pasting it into a Stream Deck plugin is a
+/// one-time setup step rather than something guessable.
+fn generate_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Handle one connected client: complete the WebSocket handshake, then read JSON control
+/// messages until the client disconnects or sends something we can't parse. Every message
+/// must carry the correct `token` or the connection is dropped - there's no handshake-time
+/// auth here since we're speaking plain WebSocket, not a scheme with custom headers.
+fn handle_connection(app: tauri::AppHandle, stream: TcpStream, token: String) {
+    let mut socket: WebSocket<TcpStream> = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::warn!("[ControlServer] WebSocket handshake failed: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        let message = match socket.read() {
+            Ok(message) => message,
+            Err(_) => break,
+        };
+
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let response = match serde_json::from_str::<ControlRequest>(&text) {
+            Ok(request) if request.token != token => ControlResponse::err("Invalid token"),
+            Ok(request) => dispatch(&app, request),
+            Err(e) => ControlResponse::err(format!("Invalid request: {}", e)),
+        };
+
+        let Ok(payload) = serde_json::to_string(&response) else { continue };
+        if socket.send(Message::Text(payload.into())).is_err() {
+            break;
+        }
+    }
+}
+
+/// Run `request.command` against the real Tauri commands it maps to.
+fn dispatch(app: &tauri::AppHandle, request: ControlRequest) -> ControlResponse {
+    let state = app.state::<AudioManagerState>();
+    let mut lock = lock_audio_manager(&state);
+    let Some(manager) = lock.as_mut() else {
+        return ControlResponse::err("Audio manager not initialised. Call init_audio_manager first.");
+    };
+
+    match request.command.as_str() {
+        "set_session_volume" => {
+            let (Some(session_id), Some(volume)) = (request.session_id, request.volume) else {
+                return ControlResponse::err("set_session_volume requires session_id and volume");
+            };
+            match manager.set_session_volume(&session_id, volume) {
+                Ok(()) => ControlResponse::ok(),
+                Err(e) => ControlResponse::err(e),
+            }
+        }
+        "set_session_mute" => {
+            let (Some(session_id), Some(muted)) = (request.session_id, request.muted) else {
+                return ControlResponse::err("set_session_mute requires session_id and muted");
+            };
+            match manager.set_session_mute(&session_id, muted) {
+                Ok(()) => ControlResponse::ok(),
+                Err(e) => ControlResponse::err(e),
+            }
+        }
+        // Scenes and solo aren't real commands anywhere in ClearComms yet - nothing to map
+        // these onto until they exist, so be explicit about that rather than pretending.
+        "scene" | "solo" => ControlResponse::err(format!(
+            "'{}' isn't supported - ClearComms has no scene/solo commands yet",
+            request.command
+        )),
+        other => ControlResponse::err(format!("Unknown command: {}", other)),
+    }
+}
+
+/// Enable or disable the control server. Enabling while already running on a different
+/// port replaces it; the previous accept loop notices its generation is stale and exits.
+/// A token is generated the first time the server is enabled and then persisted, so an
+/// external controller's config doesn't need updating across ClearComms restarts.
+#[tauri::command]
+pub fn set_control_server(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ControlServerState>,
+    enabled: bool,
+    port: u16,
+) -> std::result::Result<ControlServerStatus, String> {
+    let generation = CONTROL_SERVER_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let mut bound_port = state.lock().map_err(|e| format!("Failed to lock control server mutex: {}", e))?;
+
+    if !enabled {
+        *bound_port = None;
+        return Ok(ControlServerStatus { enabled: false, port: None, token: None });
+    }
+
+    let token = crate::settings::get().control_server_token.unwrap_or_else(|| {
+        let token = generate_token();
+        crate::settings::update(|s| s.control_server_token = Some(token.clone()));
+        token
+    });
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to bind control server to 127.0.0.1:{}: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure control server listener: {}", e))?;
+
+    *bound_port = Some(port);
+
+    let accept_token = token.clone();
+    std::thread::spawn(move || {
+        tracing::info!("[ControlServer] Listening on 127.0.0.1:{}", port);
+
+        loop {
+            if CONTROL_SERVER_GENERATION.load(Ordering::SeqCst) != generation {
+                tracing::info!("[ControlServer] Stopping listener on port {}", port);
+                break;
+            }
+
+            match listener.accept() {
+                Ok((stream, _addr)) => {
+                    let app = app.clone();
+                    let token = accept_token.clone();
+                    std::thread::spawn(move || handle_connection(app, stream, token));
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(ACCEPT_POLL_INTERVAL_MS));
+                }
+                Err(e) => {
+                    tracing::warn!("[ControlServer] Accept failed: {}", e);
+                    std::thread::sleep(Duration::from_millis(ACCEPT_POLL_INTERVAL_MS));
+                }
+            }
+        }
+    });
+
+    Ok(ControlServerStatus { enabled: true, port: Some(port), token: Some(token) })
+}
+
+/// Fetch the current control server state, for the settings UI to show the
+/// address/token to paste into an external controller's config.
+#[tauri::command]
+pub fn get_control_server_status(state: tauri::State<'_, ControlServerState>) -> std::result::Result<ControlServerStatus, String> {
+    let bound_port = state.lock().map_err(|e| format!("Failed to lock control server mutex: {}", e))?;
+
+    Ok(match *bound_port {
+        Some(port) => ControlServerStatus {
+            enabled: true,
+            port: Some(port),
+            token: crate::settings::get().control_server_token,
+        },
+        None => ControlServerStatus { enabled: false, port: None, token: None },
+    })
+}
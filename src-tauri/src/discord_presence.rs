@@ -0,0 +1,202 @@
+//! Discord voice-channel presence
+//!
+//! Optional integration, feature-gated behind the `discord-rpc` Cargo feature, that connects
+//! to Discord's local IPC pipe and watches for voice channel changes so the Discord session in
+//! `get_audio_sessions` can show richer context than "Discord.exe" - see that command's Discord
+//! overlay in `audio_management`.
+//!
+//! Connects over the same named-pipe protocol Discord's own RPC client library uses
+//! (`\\.\pipe\discord-ipc-N`, a handshake frame followed by length-prefixed JSON frames) and
+//! subscribes to the `VOICE_CHANNEL_SELECT` event. That event only ever carries a
+//! `channel_id`/`guild_id` pair, never a human-readable name - resolving the actual channel
+//! name requires an authenticated RPC session (an OAuth consent flow against a registered
+//! Discord application), which is out of scope for a local, no-setup integration. So the label
+//! produced here is coarse ("In Voice Call") rather than the specific channel name; callers
+//! fall back to the plain process name whenever this module isn't built in, isn't connected,
+//! or Discord isn't running at all.
+
+use std::sync::Mutex;
+
+/// Third-party RPC clients normally register their own application at
+/// https://discord.com/developers/applications and use that app's ID here. ClearComms
+/// doesn't have one registered yet, so the handshake uses a placeholder - swap this for a
+/// real client ID once one exists; until then Discord will reject the handshake and this
+/// module behaves exactly like Discord not running (`current_label` stays `None`).
+#[cfg(feature = "discord-rpc")]
+const CLIENT_ID: &str = "0";
+
+#[cfg(feature = "discord-rpc")]
+mod backend {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::sync::Arc;
+    use serde_json::{json, Value};
+
+    const OP_HANDSHAKE: u32 = 0;
+    const OP_FRAME: u32 = 1;
+    const OP_CLOSE: u32 = 2;
+
+    /// Live connection to Discord's local IPC pipe, with a background thread feeding the
+    /// latest voice-channel label into `label` as `VOICE_CHANNEL_SELECT` events arrive.
+    pub struct DiscordPresenceManager {
+        label: Arc<Mutex<Option<String>>>,
+        _pipe: std::fs::File,
+    }
+
+    impl DiscordPresenceManager {
+        pub fn connect() -> Result<Self, String> {
+            let mut pipe = open_pipe()?;
+
+            send_frame(&mut pipe, OP_HANDSHAKE, &json!({ "v": 1, "client_id": CLIENT_ID }))?;
+            // Discord answers the handshake with a READY dispatch before accepting anything
+            // else - read and discard it, we only care that it arrived.
+            read_frame(&mut pipe)?;
+
+            send_frame(&mut pipe, OP_FRAME, &json!({
+                "cmd": "SUBSCRIBE",
+                "evt": "VOICE_CHANNEL_SELECT",
+                "nonce": "clearcomms-voice-subscribe",
+            }))?;
+
+            let label = Arc::new(Mutex::new(None));
+            let thread_pipe = pipe.try_clone()
+                .map_err(|e| format!("Failed to duplicate Discord IPC handle: {}", e))?;
+            let thread_label = label.clone();
+
+            std::thread::spawn(move || watch_voice_channel(thread_pipe, thread_label));
+
+            Ok(Self { label, _pipe: pipe })
+        }
+
+        pub fn current_label(&self) -> Option<String> {
+            self.label.lock().ok().and_then(|guard| guard.clone())
+        }
+    }
+
+    /// Runs on its own thread for the lifetime of the connection, blocking on each read - the
+    /// pipe has nothing else writing to it, so there's no poll loop to share this with.
+    fn watch_voice_channel(mut pipe: std::fs::File, label: Arc<Mutex<Option<String>>>) {
+        loop {
+            match read_frame(&mut pipe) {
+                Ok((OP_FRAME, payload)) => {
+                    if payload.get("evt").and_then(Value::as_str) != Some("VOICE_CHANNEL_SELECT") {
+                        continue;
+                    }
+
+                    let in_channel = payload.get("data")
+                        .and_then(|data| data.get("channel_id"))
+                        .map(|channel_id| !channel_id.is_null())
+                        .unwrap_or(false);
+
+                    if let Ok(mut current) = label.lock() {
+                        *current = if in_channel { Some("In Voice Call".to_string()) } else { None };
+                    }
+                }
+                Ok((OP_CLOSE, _)) | Err(_) => return,
+                Ok(_) => {}
+            }
+        }
+    }
+
+    /// Discord's pipe index isn't fixed - it picks the first free one, so probe a handful
+    /// rather than assuming `discord-ipc-0`.
+    fn open_pipe() -> Result<std::fs::File, String> {
+        for index in 0..10 {
+            let path = format!(r"\\.\pipe\discord-ipc-{}", index);
+            if let Ok(pipe) = std::fs::OpenOptions::new().read(true).write(true).open(&path) {
+                return Ok(pipe);
+            }
+        }
+        Err("No running Discord client found".to_string())
+    }
+
+    fn send_frame(pipe: &mut std::fs::File, opcode: u32, payload: &Value) -> Result<(), String> {
+        let body = serde_json::to_vec(payload)
+            .map_err(|e| format!("Failed to encode Discord IPC frame: {}", e))?;
+
+        pipe.write_all(&opcode.to_le_bytes())
+            .and_then(|_| pipe.write_all(&(body.len() as u32).to_le_bytes()))
+            .and_then(|_| pipe.write_all(&body))
+            .map_err(|e| format!("Failed to write Discord IPC frame: {}", e))
+    }
+
+    fn read_frame(pipe: &mut std::fs::File) -> Result<(u32, Value), String> {
+        let mut header = [0u8; 8];
+        pipe.read_exact(&mut header)
+            .map_err(|e| format!("Failed to read Discord IPC frame header: {}", e))?;
+
+        let opcode = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+
+        let mut body = vec![0u8; len];
+        pipe.read_exact(&mut body)
+            .map_err(|e| format!("Failed to read Discord IPC frame body: {}", e))?;
+
+        serde_json::from_slice(&body)
+            .map(|payload| (opcode, payload))
+            .map_err(|e| format!("Failed to parse Discord IPC frame: {}", e))
+    }
+}
+
+#[cfg(not(feature = "discord-rpc"))]
+mod backend {
+    use super::*;
+
+    /// Stub used when the binary was built without the `discord-rpc` feature - mirrors
+    /// `midi_input`'s stub, so callers don't need to know whether Discord support compiled in.
+    pub struct DiscordPresenceManager;
+
+    impl DiscordPresenceManager {
+        pub fn connect() -> Result<Self, String> {
+            Err("Discord presence support was not built into this binary".to_string())
+        }
+
+        pub fn current_label(&self) -> Option<String> {
+            None
+        }
+    }
+}
+
+use backend::DiscordPresenceManager;
+
+/// Tauri-managed slot for the Discord presence connection, installed via `app.manage(...)` in
+/// `main.rs`'s `setup` - mirrors `midi_input::MidiInputManagerState`.
+pub type DiscordPresenceManagerState = Mutex<Option<DiscordPresenceManager>>;
+
+/// Connect to Discord's local IPC pipe and start watching for voice channel changes - see the
+/// module doc comment for what `get_discord_voice_label` can and can't report.
+#[tauri::command]
+pub fn init_discord_presence(state: tauri::State<'_, DiscordPresenceManagerState>) -> Result<String, String> {
+    let manager = DiscordPresenceManager::connect()?;
+
+    let mut lock = state.lock().map_err(|e| format!("Failed to lock Discord presence mutex: {}", e))?;
+    *lock = Some(manager);
+
+    Ok("Discord presence connected".to_string())
+}
+
+/// Current best-effort voice-channel label, or `None` if not connected or not currently in a
+/// call. Takes the state directly (rather than a `tauri::State`) so `audio_management`'s
+/// Discord overlay can call it without going through IPC - see
+/// `audio_management::get_audio_sessions`.
+pub fn current_voice_label(state: &DiscordPresenceManagerState) -> Option<String> {
+    state.lock().ok().and_then(|lock| lock.as_ref().and_then(|manager| manager.current_label()))
+}
+
+/// Current best-effort voice-channel label, or `None` if not connected or not currently in a
+/// call - see `current_voice_label`.
+#[tauri::command]
+pub fn get_discord_voice_label(state: tauri::State<'_, DiscordPresenceManagerState>) -> Result<Option<String>, String> {
+    Ok(current_voice_label(&state))
+}
+
+/// Disconnect from Discord's local IPC pipe, if connected.
+#[tauri::command]
+pub fn cleanup_discord_presence(state: tauri::State<'_, DiscordPresenceManagerState>) -> Result<String, String> {
+    let mut lock = state.lock().map_err(|e| format!("Failed to lock Discord presence mutex: {}", e))?;
+
+    Ok(match lock.take() {
+        Some(_) => "Discord presence disconnected".to_string(),
+        None => "Discord presence not connected".to_string(),
+    })
+}
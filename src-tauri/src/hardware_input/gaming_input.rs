@@ -0,0 +1,163 @@
+//! Windows.Gaming.Input backend
+//!
+//! Optional alternative to the legacy `winmm`/DirectInput path `HidInputManager` uses by
+//! default, feature-gated behind the `gaming-input` Cargo feature. `winmm.dll` (the legacy
+//! Windows Joystick API `read_device_axes` falls back to) has shown up as a faulting module in
+//! at least one crash report, and is generally the older, less actively maintained of the two
+//! APIs for modern controllers - `Windows.Gaming.Input`'s `RawGameController` reports a
+//! device's actual axis/button/switch counts directly rather than assuming the legacy API's
+//! fixed six-axis `JOYCAPSW` layout, and identifies devices by a stable ID across reconnects
+//! instead of a reused joystick slot number.
+//!
+//! Selected per `init_direct_input` call via `InputBackendKind`, not silently: an existing
+//! install's bindings are keyed against DirectInput's axis names, so switching backends for
+//! everyone by default would desync them. If `RawGameController` enumeration fails, or the
+//! feature wasn't built in, `HidInputManager::enumerate_devices` falls back to the legacy
+//! backend automatically.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::DeviceInfo;
+
+/// Which backend `init_direct_input` should read controllers through - see the module doc
+/// comment above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InputBackendKind {
+    #[default]
+    DirectInput,
+    GamingInput,
+}
+
+/// Virtual device IDs handed to enumerated `RawGameController`s start here, clear of the
+/// legacy Windows Joystick API's `JOYSTICKID1..JOYSTICKID16` (0-15) range, so `DeviceInfo::id`
+/// can never collide between the two backends.
+const GAMING_INPUT_ID_BASE: u32 = 1000;
+
+#[cfg(feature = "gaming-input")]
+mod backend {
+    use super::*;
+    use windows::Gaming::Input::{GameControllerSwitchPosition, RawGameController};
+
+    /// One connected `RawGameController`, tracked alongside the axis/button/switch counts
+    /// `enumerate` already queried, so `read` doesn't have to re-query capability counts (and
+    /// re-allocate the reading buffers) on every poll.
+    pub struct GamingInputDevice {
+        controller: RawGameController,
+        axis_count: usize,
+        button_count: usize,
+        switch_count: usize,
+    }
+
+    /// Enumerate every currently-connected `RawGameController`, assigning each a virtual
+    /// `DeviceInfo::id` based on its position in the returned vector - `RawGameControllers()`
+    /// doesn't promise a reconnect keeps the same index, the same caveat the legacy joystick
+    /// slot numbers already carry.
+    pub fn enumerate() -> Result<(Vec<DeviceInfo>, HashMap<u32, GamingInputDevice>), String> {
+        let controllers = RawGameController::RawGameControllers()
+            .map_err(|e| format!("Failed to enumerate Windows.Gaming.Input controllers: {}", e))?;
+
+        let count = controllers.Size()
+            .map_err(|e| format!("Failed to read Windows.Gaming.Input controller count: {}", e))?;
+
+        let mut devices = Vec::new();
+        let mut states = HashMap::new();
+
+        for index in 0..count {
+            let Ok(controller) = controllers.GetAt(index) else { continue };
+
+            let axis_count = controller.AxisCount().unwrap_or(0).max(0) as usize;
+            let button_count = controller.ButtonCount().unwrap_or(0).max(0) as usize;
+            let switch_count = controller.SwitchCount().unwrap_or(0).max(0) as usize;
+            let name = controller.DisplayName().map(|n| n.to_string_lossy()).unwrap_or_else(|_| "Game Controller".to_string());
+
+            let device_id = GAMING_INPUT_ID_BASE + index;
+
+            devices.push(DeviceInfo {
+                id: device_id,
+                device_key: format!("gaming-input-{}-{}", index, name),
+                name,
+                manufacturer: String::new(),
+                vendor_id: 0,
+                product_id: 0,
+                num_axes: axis_count as u32,
+                num_buttons: button_count as u32,
+            });
+
+            states.insert(device_id, GamingInputDevice { controller, axis_count, button_count, switch_count });
+        }
+
+        Ok((devices, states))
+    }
+
+    /// Read one `RawGameController`'s current axis/button state. `RawGameController` exposes no
+    /// per-object names the way DirectInput's enumerated objects do, only flat counts, so axes
+    /// and buttons are keyed positionally (`"Axis1"`, `"Button1"`, ...) - a user can still give
+    /// one a friendlier label via `set_axis_label` like any other axis. Switches (POV-style
+    /// hats) are reported on the same degrees-from-up/360.0 scale as DirectInput's POV hats
+    /// (`HidInputManager::read_device_axes`'s `pov_angle / 360.0`) - Up=0.0, Right=0.25, Down=0.5,
+    /// Left=0.75 - plus `_Up`/`_Right`/`_Down`/`_Left` directional buttons, so a saved hat-axis
+    /// binding doesn't misfire just because the backend switched. `GameControllerSwitchPosition`
+    /// itself is a clockwise-from-up enum ordinal (Center=0, Up=1, UpRight=2, ...), not that
+    /// angle, so it's converted rather than divided directly. Centered has no angle to report -
+    /// mirrors the legacy path leaving the axis absent and setting `POV_Centered` instead.
+    pub fn read(device: &GamingInputDevice) -> Result<(HashMap<String, f32>, HashMap<String, bool>), String> {
+        let mut button_states = vec![false; device.button_count];
+        let mut switch_states = vec![GameControllerSwitchPosition::Center; device.switch_count];
+        let mut axis_states = vec![0.0f64; device.axis_count];
+
+        device.controller.GetCurrentReading(&mut button_states, &mut switch_states, &mut axis_states)
+            .map_err(|e| format!("Failed to read Windows.Gaming.Input controller state: {}", e))?;
+
+        let mut axes: HashMap<String, f32> = axis_states.iter()
+            .enumerate()
+            .map(|(i, &v)| (format!("Axis{}", i + 1), v as f32))
+            .collect();
+
+        let mut buttons: HashMap<String, bool> = button_states.iter()
+            .enumerate()
+            .map(|(i, &v)| (format!("Button{}", i + 1), v))
+            .collect();
+
+        for (i, &switch) in switch_states.iter().enumerate() {
+            let prefix = format!("Switch{}", i + 1);
+            buttons.insert(format!("{}_Up", prefix), matches!(switch, GameControllerSwitchPosition::Up | GameControllerSwitchPosition::UpLeft | GameControllerSwitchPosition::UpRight));
+            buttons.insert(format!("{}_Right", prefix), matches!(switch, GameControllerSwitchPosition::Right | GameControllerSwitchPosition::UpRight | GameControllerSwitchPosition::DownRight));
+            buttons.insert(format!("{}_Down", prefix), matches!(switch, GameControllerSwitchPosition::Down | GameControllerSwitchPosition::DownLeft | GameControllerSwitchPosition::DownRight));
+            buttons.insert(format!("{}_Left", prefix), matches!(switch, GameControllerSwitchPosition::Left | GameControllerSwitchPosition::UpLeft | GameControllerSwitchPosition::DownLeft));
+
+            if switch == GameControllerSwitchPosition::Center {
+                buttons.insert(format!("{}_Centered", prefix), true);
+            } else {
+                // Ordinal is clockwise from Up starting at 1 (Up=1, UpRight=2, ... UpLeft=8),
+                // 45 degrees apart - convert to the same degrees-from-up/360.0 scale the legacy
+                // POV path uses rather than dividing the ordinal itself.
+                let degrees_from_up = (switch.0 - 1) as f32 * 45.0;
+                axes.insert(prefix, degrees_from_up / 360.0);
+            }
+        }
+
+        Ok((axes, buttons))
+    }
+}
+
+#[cfg(not(feature = "gaming-input"))]
+mod backend {
+    use super::*;
+
+    /// Stand-in with no real controller handle - `enumerate` always errors before one could
+    /// ever be constructed, so its fields are never read.
+    pub struct GamingInputDevice;
+
+    pub fn enumerate() -> Result<(Vec<DeviceInfo>, HashMap<u32, GamingInputDevice>), String> {
+        Err("Windows.Gaming.Input support was not built into this binary (requires the \"gaming-input\" feature)".to_string())
+    }
+
+    pub fn read(_device: &GamingInputDevice) -> Result<(HashMap<String, f32>, HashMap<String, bool>), String> {
+        Err("Windows.Gaming.Input support was not built into this binary (requires the \"gaming-input\" feature)".to_string())
+    }
+}
+
+pub use backend::{enumerate, read, GamingInputDevice};
@@ -1,14 +1,33 @@
 use std::sync::Mutex;
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, HashSet};
 use serde::{Serialize, Deserialize};
+#[cfg(windows)]
 use hidapi::HidApi;
+use tauri::{Emitter, Manager};
 
 #[cfg(windows)]
 use windows::Win32::Media::Multimedia::{
-    joyGetDevCapsW, joyGetPosEx, JOYCAPSW, JOYINFOEX, 
+    joyGetDevCapsW, joyGetPosEx, JOYCAPSW, JOYINFOEX,
     JOY_USEDEADZONE, JOYERR_NOERROR,
 };
 
+#[cfg(windows)]
+use windows::Win32::Devices::HumanInterfaceDevice::{
+    DirectInput8Create, IDirectInput8W, IDirectInputDevice8W, DIDEVICEINSTANCEW,
+    DIDEVICEOBJECTINSTANCEW, DIJOYSTATE2, DIPROPRANGE, DIPROPHEADER, DIPH_BYID,
+    DIPROP_RANGE, c_dfDIJoystick2, DISCL_BACKGROUND, DISCL_NONEXCLUSIVE,
+    DIEDFL_ATTACHEDONLY, DIENUM_CONTINUE, DI8DEVCLASS_GAMECTRL, DIDFT_AXIS,
+    DIDFT_POV, DIRECTINPUT_VERSION,
+};
+#[cfg(windows)]
+use windows::Win32::UI::WindowsAndMessaging::GetDesktopWindow;
+#[cfg(windows)]
+use windows::core::Interface;
+
+mod gaming_input;
+pub use gaming_input::InputBackendKind;
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Constants
 // ─────────────────────────────────────────────────────────────────────────────
@@ -32,18 +51,74 @@ const INITIAL_HID_DEVICE_CAPACITY: usize = 32;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AxisData {
     pub device_handle: String,
+    /// Stable identity (see `device_identity_key`) bindings should match against instead
+    /// of `device_handle`, which is just the current Windows joystick slot and can shift
+    /// across reboots when devices enumerate in a different order.
+    pub device_key: String,
     pub device_name: String,
     pub manufacturer: String,
     pub product_id: u16,
     pub vendor_id: u16,
-    pub axes: HashMap<String, f32>, // axis name -> normalised value (0.0-1.0)
+    pub axes: HashMap<String, f32>, // axis/hat/slider name -> normalised value (0.0-1.0)
     pub buttons: HashMap<String, bool>, // button name -> pressed state
+    /// User-assigned friendly names (e.g. "Throttle") for entries in `axes`, set via
+    /// `set_axis_label` and stored in settings against this device's stable identity.
+    /// Keyed by the same raw axis name used in `axes`; entries without a custom label
+    /// are simply absent, so the UI falls back to the raw name.
+    pub axis_labels: HashMap<String, String>,
+    /// Unwrapped position for axes flagged rotary (see `set_axis_rotary`), accumulated across
+    /// wrap-arounds rather than read as an absolute 0.0-1.0 value. Keyed by the same raw axis
+    /// name used in `axes`; only axes flagged rotary for this device have an entry.
+    pub rotary_position: HashMap<String, f32>,
+}
+
+/// A stable identity for a device across enumeration-order changes and reboots, used to key
+/// per-device settings like custom axis labels. `device_handle`/`DeviceInfo::id` is just the
+/// current Windows joystick slot, which can shift when devices are plugged in a different order.
+pub fn device_identity_key(vendor_id: u16, product_id: u16, name: &str) -> String {
+    format!("{:04x}:{:04x}:{}", vendor_id, product_id, name)
+}
+
+/// Strip the `#2`/`#3`/... instance suffix `enumerate_devices` appends to `device_identity_key`
+/// for the second and later device sharing the same vendor/product/name, recovering the plain
+/// identity key that `axis_labels`/`rotary_axes`/`disabled_devices` are actually keyed against.
+/// A no-op for a key with no such suffix.
+pub fn strip_instance_suffix(device_key: &str) -> &str {
+    match device_key.rsplit_once('#') {
+        Some((base, suffix)) if !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()) => base,
+        _ => device_key,
+    }
+}
+
+/// Look up any custom axis labels stored for this device's identity.
+fn axis_labels_for(vendor_id: u16, product_id: u16, name: &str) -> HashMap<String, String> {
+    let key = device_identity_key(vendor_id, product_id, name);
+    crate::settings::get().axis_labels.get(&key).cloned().unwrap_or_default()
+}
+
+/// Look up which axes are flagged rotary (continuously wrapping) for this device's identity.
+fn rotary_axes_for(vendor_id: u16, product_id: u16, name: &str) -> HashSet<String> {
+    let key = device_identity_key(vendor_id, product_id, name);
+    crate::settings::get().rotary_axes.get(&key).cloned().unwrap_or_default()
+}
+
+/// Whether this device's identity has been excluded from polling - see `set_device_polling`.
+/// Checked up front in `read_all_axes` so a disabled device gets no `joyGetPosEx`/DirectInput
+/// call at all, not just a discarded result.
+fn is_device_disabled(vendor_id: u16, product_id: u16, name: &str) -> bool {
+    let key = device_identity_key(vendor_id, product_id, name);
+    crate::settings::get().disabled_devices.contains(&key)
 }
 
 /// Information about a discovered input device
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceInfo {
     pub id: u32,
+    /// Stable identity for this device, computed in `enumerate_devices` from
+    /// `device_identity_key` plus an instance suffix (`#2`, `#3`, ...) for the second and
+    /// later device sharing the same vendor/product/name. Bindings should persist this
+    /// instead of `id`, which is just the current Windows joystick slot - see `AxisData::device_key`.
+    pub device_key: String,
     pub name: String,
     pub manufacturer: String,
     pub vendor_id: u16,
@@ -57,63 +132,304 @@ impl DeviceInfo {
     /// Convert device info to a human-readable string
     pub fn to_display_string(&self) -> String {
         if !self.manufacturer.is_empty() {
-            format!("{} {} (VID:{:04X} PID:{:04X})", 
+            format!("{} {} (VID:{:04X} PID:{:04X})",
                 self.manufacturer, self.name, self.vendor_id, self.product_id)
         } else {
-            format!("{} (VID:{:04X} PID:{:04X})", 
+            format!("{} (VID:{:04X} PID:{:04X})",
                 self.name, self.vendor_id, self.product_id)
         }
     }
 }
 
-/// Manages game controller input using Windows Joystick API + HID for device names
+/// A single DirectInput-reported device object (axis, hat, or slider), keyed by
+/// its real device-reported name rather than the legacy fixed X/Y/Z/R/U/V set.
+#[cfg(windows)]
+#[derive(Debug, Clone)]
+struct DirectInputObject {
+    name: String,
+    offset: u32,
+    is_pov: bool,
+    min: i32,
+    max: i32,
+}
+
+/// A DirectInput device handle plus its enumerated objects, tracked alongside
+/// the legacy joystick device it corresponds to.
+#[cfg(windows)]
+struct DirectInputDevice {
+    device: IDirectInputDevice8W,
+    objects: Vec<DirectInputObject>,
+}
+
+/// Per-axis (min, max) range reported by `joyGetDevCapsW`, for normalising the legacy
+/// Windows Joystick API's readings - some drivers report 8-bit or 12-bit ranges rather
+/// than the full 16-bit span, so a fixed `65535.0` divisor under-reads those axes.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy)]
+struct JoyAxisRanges {
+    x: (u32, u32),
+    y: (u32, u32),
+    z: (u32, u32),
+    r: (u32, u32),
+    u: (u32, u32),
+    v: (u32, u32),
+}
+
+/// Manages game controller input using DirectInput (preferred, for full axis/hat/slider
+/// coverage) with the legacy Windows Joystick API as a fallback for devices DirectInput
+/// couldn't bind, and HID for device names.
 pub struct HidInputManager {
     devices: Vec<DeviceInfo>,
     axis_cache: HashMap<u32, HashMap<String, f32>>,
     button_cache: HashMap<u32, HashMap<String, bool>>,
+    /// Which backend `enumerate_devices`/`read_device_axes` prefers, set once at construction
+    /// by `init_direct_input` - see `gaming_input::InputBackendKind`.
+    backend: InputBackendKind,
+    #[cfg(windows)]
     hid_api: HidApi,
+    #[cfg(windows)]
+    direct_input: Option<IDirectInput8W>,
+    #[cfg(windows)]
+    di_devices: HashMap<u32, DirectInputDevice>,
+    /// Axis ranges reported by `joyGetDevCapsW`, keyed by device ID, used to normalise the
+    /// legacy Windows Joystick API fallback in `read_all_axes` - see `JoyAxisRanges`.
+    #[cfg(windows)]
+    joy_axis_ranges: HashMap<u32, JoyAxisRanges>,
+    /// `RawGameController` handles for devices found by the `gaming_input` backend, keyed by
+    /// the same virtual `DeviceInfo::id` used everywhere else - see `gaming_input::enumerate`.
+    /// Empty whenever `backend` isn't `GamingInput`, or when enumeration fell back to the
+    /// legacy backend.
+    #[cfg(windows)]
+    gaming_devices: HashMap<u32, gaming_input::GamingInputDevice>,
+    /// Last raw (wrapped) 0.0-1.0 reading per (device id, axis name), for axes flagged rotary -
+    /// used to tell a real wrap-around from a real jump in `unwrap_rotary_axis`.
+    rotary_last_raw: HashMap<(u32, String), f32>,
+    /// Running unwrapped position per (device id, axis name), accumulated across wraps. Not
+    /// clamped to 0.0-1.0 - a rotary encoder turned three full revolutions reads as `3.0`.
+    rotary_position: HashMap<(u32, String), f32>,
 }
 
 #[cfg(windows)]
 impl HidInputManager {
     /// Create a new input manager instance
-    pub fn new() -> Result<Self, String> {
+    pub fn new(backend: InputBackendKind) -> Result<Self, String> {
         let hid_api = HidApi::new()
             .map_err(|e| format!("Failed to initialise HID API: {}", e))?;
-        
+
+        let direct_input = unsafe {
+            let mut di: Option<IDirectInput8W> = None;
+            let hinstance = windows::Win32::System::LibraryLoader::GetModuleHandleW(None)
+                .map(|h| windows::Win32::Foundation::HINSTANCE(h.0))
+                .unwrap_or_default();
+            match DirectInput8Create(
+                hinstance,
+                DIRECTINPUT_VERSION,
+                &IDirectInput8W::IID,
+                &mut di as *mut _ as *mut _,
+                None,
+            ) {
+                Ok(()) => di,
+                Err(e) => {
+                    tracing::warn!("[Input] DirectInput8Create failed, falling back to legacy joystick axes only: {}", e);
+                    None
+                }
+            }
+        };
+
         Ok(Self {
             devices: Vec::with_capacity(INITIAL_DEVICE_CAPACITY), // Pre-allocate for typical device count
             axis_cache: HashMap::with_capacity(INITIAL_DEVICE_CAPACITY),
             button_cache: HashMap::with_capacity(INITIAL_DEVICE_CAPACITY),
+            backend,
             hid_api,
+            direct_input,
+            di_devices: HashMap::with_capacity(INITIAL_DEVICE_CAPACITY),
+            joy_axis_ranges: HashMap::with_capacity(INITIAL_DEVICE_CAPACITY),
+            gaming_devices: HashMap::new(),
+            rotary_last_raw: HashMap::new(),
+            rotary_position: HashMap::new(),
         })
     }
-    
+
     /// Clean up resources and caches
     pub fn cleanup(&mut self) {
         tracing::info!("[Input] Cleaning up HID input manager resources...");
-        
+
         // Clear all caches
         self.devices.clear();
         self.axis_cache.clear();
         self.button_cache.clear();
-        
+        self.di_devices.clear();
+        self.gaming_devices.clear();
+        self.rotary_last_raw.clear();
+        self.rotary_position.clear();
+
         // Release allocated memory back to the system
         self.devices.shrink_to_fit();
         self.axis_cache.shrink_to_fit();
         self.button_cache.shrink_to_fit();
-        
+
         tracing::info!("[Input] HID input manager cleanup complete");
     }
 
+    /// Bind a DirectInput device to the given legacy joystick device by matching
+    /// `guidProduct`, which DirectInput derives from the VID/PID for HID joysticks,
+    /// then enumerate its axis/hat/slider objects by their real reported names.
+    fn bind_direct_input_device(&self, device: &DeviceInfo) -> Option<DirectInputDevice> {
+        let di = self.direct_input.as_ref()?;
+
+        struct EnumCtx {
+            vendor_id: u16,
+            product_id: u16,
+            found: Option<DIDEVICEINSTANCEW>,
+        }
+
+        unsafe extern "system" fn enum_devices_cb(
+            lpddi: *mut DIDEVICEINSTANCEW,
+            pvref: *mut core::ffi::c_void,
+        ) -> i32 {
+            let ctx = &mut *(pvref as *mut EnumCtx);
+            let guid = (*lpddi).guidProduct;
+            // DirectInput encodes VID/PID for HID devices as the first two u16s
+            // of guidProduct: Data1 low/high words.
+            let vid = (guid.data1 & 0xFFFF) as u16;
+            let pid = ((guid.data1 >> 16) & 0xFFFF) as u16;
+            if vid == ctx.vendor_id && pid == ctx.product_id {
+                ctx.found = Some(*lpddi);
+                return windows::Win32::Devices::HumanInterfaceDevice::DIENUM_STOP.0;
+            }
+            DIENUM_CONTINUE.0
+        }
+
+        let mut ctx = EnumCtx {
+            vendor_id: device.vendor_id,
+            product_id: device.product_id,
+            found: None,
+        };
+
+        unsafe {
+            let _ = di.EnumDevices(
+                DI8DEVCLASS_GAMECTRL,
+                Some(enum_devices_cb),
+                &mut ctx as *mut _ as *mut _,
+                DIEDFL_ATTACHEDONLY,
+            );
+        }
+
+        let instance = ctx.found?;
+
+        let di_device: IDirectInputDevice8W = unsafe {
+            let mut out: Option<IDirectInputDevice8W> = None;
+            di.CreateDevice(&instance.guidInstance, &mut out, None).ok()?;
+            out?
+        };
+
+        unsafe {
+            let _ = di_device.SetDataFormat(&c_dfDIJoystick2);
+            let hwnd = GetDesktopWindow();
+            let _ = di_device.SetCooperativeLevel(hwnd, (DISCL_NONEXCLUSIVE.0 | DISCL_BACKGROUND.0) as u32);
+        }
+
+        struct ObjCtx {
+            objects: Vec<DirectInputObject>,
+            di_device: IDirectInputDevice8W,
+        }
+
+        unsafe extern "system" fn enum_objects_cb(
+            lpddoi: *mut DIDEVICEOBJECTINSTANCEW,
+            pvref: *mut core::ffi::c_void,
+        ) -> i32 {
+            let ctx = &mut *(pvref as *mut ObjCtx);
+            let info = &*lpddoi;
+            let is_axis = (info.dwType & DIDFT_AXIS.0 as u32) != 0;
+            let is_pov = (info.dwType & DIDFT_POV.0 as u32) != 0;
+            if !is_axis && !is_pov {
+                return DIENUM_CONTINUE.0;
+            }
+
+            let name = String::from_utf16_lossy(&info.tszName)
+                .trim_end_matches('\0')
+                .to_string();
+
+            let (mut min, mut max) = (0, 65535);
+            if is_axis {
+                let mut range = DIPROPRANGE {
+                    diph: DIPROPHEADER {
+                        dwSize: std::mem::size_of::<DIPROPRANGE>() as u32,
+                        dwHeaderSize: std::mem::size_of::<DIPROPHEADER>() as u32,
+                        dwObj: info.dwType,
+                        dwHow: DIPH_BYID.0 as u32,
+                    },
+                    lMin: 0,
+                    lMax: 65535,
+                };
+                if ctx.di_device
+                    .GetProperty(&DIPROP_RANGE as *const _ as *const _, &mut range.diph as *mut _)
+                    .is_ok()
+                {
+                    min = range.lMin;
+                    max = range.lMax;
+                }
+            }
+
+            ctx.objects.push(DirectInputObject {
+                name,
+                offset: info.dwOfs,
+                is_pov,
+                min,
+                max,
+            });
+
+            DIENUM_CONTINUE.0
+        }
+
+        let mut obj_ctx = ObjCtx {
+            objects: Vec::new(),
+            di_device: di_device.clone(),
+        };
+
+        unsafe {
+            let _ = di_device.EnumObjects(
+                Some(enum_objects_cb),
+                &mut obj_ctx as *mut _ as *mut _,
+                (DIDFT_AXIS.0 | DIDFT_POV.0) as u32,
+            );
+            let _ = di_device.Acquire();
+        }
+
+        Some(DirectInputDevice {
+            device: di_device,
+            objects: obj_ctx.objects,
+        })
+    }
+
     /// Enumerate all connected game controllers with improved memory management
     pub fn enumerate_devices(&mut self) -> Result<(), String> {
         self.devices.clear();
-        
+        self.di_devices.clear();
+        self.joy_axis_ranges.clear();
+        self.gaming_devices.clear();
+
+        if self.backend == InputBackendKind::GamingInput {
+            match gaming_input::enumerate() {
+                Ok((devices, gaming_devices)) if !devices.is_empty() => {
+                    self.devices = devices;
+                    self.gaming_devices = gaming_devices;
+                    return Ok(());
+                }
+                Ok(_) => {
+                    tracing::warn!("[Input] Windows.Gaming.Input reported no controllers, falling back to the legacy backend");
+                }
+                Err(e) => {
+                    tracing::warn!("[Input] Windows.Gaming.Input enumeration failed, falling back to the legacy backend: {}", e);
+                }
+            }
+        }
+
         // Refresh HID device list
         self.hid_api.refresh_devices()
             .map_err(|e| format!("Failed to refresh HID devices: {}", e))?;
-        
+
         // Build a map of joystick devices from HID (for names)
         let mut hid_devices: HashMap<(u16, u16), (String, String)> = HashMap::with_capacity(INITIAL_HID_DEVICE_CAPACITY);
         for device in self.hid_api.device_list() {
@@ -129,44 +445,25 @@ impl HidInputManager {
                 }
             }
         }
-        
-        // Windows supports up to MAX_JOYSTICK_DEVICES joysticks (JOYSTICKID1 through JOYSTICKID16)
+
+        // Tracks how many devices sharing a base identity key have been seen so far this
+        // enumeration, so two identical controllers (same vendor/product/name) get distinct
+        // `device_key`s rather than colliding - see `DeviceInfo::device_key`.
+        let mut device_key_counts: HashMap<String, u32> = HashMap::with_capacity(INITIAL_DEVICE_CAPACITY);
+
+        // Windows supports up to MAX_JOYSTICK_DEVICES joysticks (JOYSTICKID1 through JOYSTICKID16).
+        // Each probe runs behind `catch_unwind` - a misbehaving driver shouldn't be able to take
+        // the whole enumeration down, just lose its own slot for this pass - see `read_all_axes`
+        // for the same guard on the hot poll path.
         for joy_id in 0..MAX_JOYSTICK_DEVICES {
-            unsafe {
-                let mut caps: JOYCAPSW = std::mem::zeroed();
-                let result = joyGetDevCapsW(
-                    joy_id as usize,
-                    &mut caps as *mut JOYCAPSW,
-                    std::mem::size_of::<JOYCAPSW>() as u32,
-                );
-                
-                if result == JOYERR_NOERROR {
-                    // Get VID/PID from capabilities
-                    let vendor_id = caps.wMid;
-                    let product_id = caps.wPid;
-                    
-                    // Try to get real device name from HID
-                    let (name, manufacturer) = hid_devices
-                        .get(&(vendor_id, product_id))
-                        .cloned()
-                        .unwrap_or_else(|| {
-                            // Fallback to caps name if not found in HID
-                            let name_buf = caps.szPname;
-                            let fallback_name = String::from_utf16_lossy(&name_buf)
-                                .trim_end_matches('\0')
-                                .to_string();
-                            (fallback_name, String::new())
-                        });
-                    
-                    self.devices.push(DeviceInfo {
-                        id: joy_id,
-                        name,
-                        manufacturer,
-                        vendor_id,
-                        product_id,
-                        num_axes: caps.wNumAxes as u32,
-                        num_buttons: caps.wNumButtons as u32,
-                    });
+            let probe = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                self.probe_joystick_device(joy_id, &hid_devices, &mut device_key_counts)
+            }));
+
+            match probe {
+                Ok(()) => {}
+                Err(_) => {
+                    tracing::warn!("[Input] Panic probing joystick slot {}, skipping it for this enumeration", joy_id);
                 }
             }
         }
@@ -178,103 +475,343 @@ impl HidInputManager {
         Ok(())
     }
 
+    /// Probe one joystick slot via `joyGetDevCapsW` and, if a device answers, bind its
+    /// DirectInput surface and register it - the body `enumerate_devices` ran inline before
+    /// this was split out so each slot's probe could be wrapped in `catch_unwind`.
+    fn probe_joystick_device(
+        &mut self,
+        joy_id: u32,
+        hid_devices: &HashMap<(u16, u16), (String, String)>,
+        device_key_counts: &mut HashMap<String, u32>,
+    ) {
+        unsafe {
+            let mut caps: JOYCAPSW = std::mem::zeroed();
+            let result = joyGetDevCapsW(
+                joy_id as usize,
+                &mut caps as *mut JOYCAPSW,
+                std::mem::size_of::<JOYCAPSW>() as u32,
+            );
+
+            if result != JOYERR_NOERROR {
+                return;
+            }
+
+            // Get VID/PID from capabilities
+            let vendor_id = caps.wMid;
+            let product_id = caps.wPid;
+
+            // Try to get real device name from HID
+            let (name, manufacturer) = hid_devices
+                .get(&(vendor_id, product_id))
+                .cloned()
+                .unwrap_or_else(|| {
+                    // Fallback to caps name if not found in HID
+                    let name_buf = caps.szPname;
+                    let fallback_name = String::from_utf16_lossy(&name_buf)
+                        .trim_end_matches('\0')
+                        .to_string();
+                    (fallback_name, String::new())
+                });
+
+            let base_key = device_identity_key(vendor_id, product_id, &name);
+            let occurrence = device_key_counts.entry(base_key.clone()).or_insert(0);
+            let device_key = if *occurrence == 0 {
+                base_key
+            } else {
+                format!("{}#{}", base_key, *occurrence + 1)
+            };
+            *occurrence += 1;
+
+            let info = DeviceInfo {
+                id: joy_id,
+                device_key,
+                name,
+                manufacturer,
+                vendor_id,
+                product_id,
+                num_axes: caps.wNumAxes as u32,
+                num_buttons: caps.wNumButtons as u32,
+            };
+
+            // Bind a DirectInput device for this joystick so we can report
+            // its full native object surface (multiple POV hats, sliders, etc.)
+            if let Some(di_device) = self.bind_direct_input_device(&info) {
+                if !di_device.objects.is_empty() {
+                    self.di_devices.insert(joy_id, di_device);
+                }
+            }
+
+            self.joy_axis_ranges.insert(joy_id, JoyAxisRanges {
+                x: (caps.wXmin, caps.wXmax),
+                y: (caps.wYmin, caps.wYmax),
+                z: (caps.wZmin, caps.wZmax),
+                r: (caps.wRmin, caps.wRmax),
+                u: (caps.wUmin, caps.wUmax),
+                v: (caps.wVmin, caps.wVmax),
+            });
+
+            self.devices.push(info);
+        }
+    }
+
     /// Get the list of discovered devices
     pub fn get_devices(&self) -> &[DeviceInfo] {
         &self.devices
     }
 
+    /// Snapshot of the last-read axis values per device ID - see `get_input_debug`.
+    pub fn axis_cache_snapshot(&self) -> HashMap<u32, HashMap<String, f32>> {
+        self.axis_cache.clone()
+    }
+
+    /// Read a device's axes/hats/sliders via DirectInput, keyed by their real
+    /// device-reported object names rather than the fixed X/Y/Z/R/U/V set.
+    fn read_direct_input_axes(&self, di_device: &DirectInputDevice) -> Option<(HashMap<String, f32>, HashMap<String, bool>)> {
+        unsafe {
+            let _ = di_device.device.Poll();
+
+            let mut state: DIJOYSTATE2 = std::mem::zeroed();
+            di_device
+                .device
+                .GetDeviceState(std::mem::size_of::<DIJOYSTATE2>() as u32, &mut state as *mut _ as *mut _)
+                .ok()?;
+
+            let base = &state as *const DIJOYSTATE2 as *const u8;
+            let mut axes = HashMap::with_capacity(di_device.objects.len());
+
+            for object in &di_device.objects {
+                let raw = *(base.add(object.offset as usize) as *const i32);
+                if object.is_pov {
+                    if raw == -1 || raw as u32 == 0xFFFF {
+                        axes.insert(format!("{} (Centered)", object.name), -1.0);
+                    } else {
+                        let degrees = raw as f32 / 100.0;
+                        axes.insert(object.name.clone(), (degrees / 360.0).clamp(0.0, 1.0));
+                    }
+                } else {
+                    let span = (object.max - object.min).max(1) as f32;
+                    let normalised = ((raw - object.min) as f32 / span).clamp(0.0, 1.0);
+                    axes.insert(object.name.clone(), normalised);
+                }
+            }
+
+            // Buttons come from the same DIJOYSTATE2 snapshot; DirectInput reports up
+            // to 128 but we keep parity with the legacy button count for now.
+            let mut buttons = HashMap::with_capacity(MAX_BUTTONS_PER_DEVICE as usize);
+            for (i, &pressed) in state.rgbButtons.iter().take(MAX_BUTTONS_PER_DEVICE as usize).enumerate() {
+                buttons.insert(format!("Button{}", i + 1), pressed != 0);
+            }
+
+            Some((axes, buttons))
+        }
+    }
+
+    /// For axes flagged rotary on this device, correct for wrap-around (e.g. a raw reading
+    /// jumping 0.98 -> 0.02) by treating any single-poll jump bigger than half the axis range
+    /// as a wrap rather than real movement, and accumulating the corrected delta into a
+    /// running unwrapped position instead of the absolute 0.0-1.0 reading.
+    fn unwrap_rotary_axes(&mut self, device_id: u32, rotary_axes: &HashSet<String>, axes: &HashMap<String, f32>) -> HashMap<String, f32> {
+        let mut unwrapped = HashMap::with_capacity(rotary_axes.len());
+
+        for axis_name in rotary_axes {
+            let Some(&raw) = axes.get(axis_name) else { continue };
+            let key = (device_id, axis_name.clone());
+
+            let mut position = self.rotary_position.get(&key).copied().unwrap_or(raw);
+            if let Some(&prev_raw) = self.rotary_last_raw.get(&key) {
+                let mut delta = raw - prev_raw;
+                if delta > 0.5 {
+                    delta -= 1.0;
+                } else if delta < -0.5 {
+                    delta += 1.0;
+                }
+                position += delta;
+            }
+
+            self.rotary_last_raw.insert(key.clone(), raw);
+            self.rotary_position.insert(key.clone(), position);
+            unwrapped.insert(axis_name.clone(), position);
+        }
+
+        unwrapped
+    }
+
     /// Read axis values from all devices with memory management
     pub fn read_all_axes(&mut self) -> Result<Vec<AxisData>, String> {
         let mut all_axes = Vec::with_capacity(self.devices.len());
-        
-        for device in &self.devices {
-            unsafe {
-                let mut joy_info: JOYINFOEX = std::mem::zeroed();
-                joy_info.dwSize = std::mem::size_of::<JOYINFOEX>() as u32;
-                joy_info.dwFlags = 0xFFu32 | (JOY_USEDEADZONE as u32); // Request all axes
-                
-                let result = joyGetPosEx(device.id, &mut joy_info as *mut JOYINFOEX);
-                
-                if result == JOYERR_NOERROR {
-                    let mut axes = HashMap::new();
-                    let mut buttons = HashMap::new();
-                    
-                    // Windows Joystick API provides raw values (typically 0-65535)
-                    // Normalise to 0.0-1.0
-                    
-                    // X axis
-                    axes.insert("X".to_string(), (joy_info.dwXpos as f32 / MAX_AXIS_VALUE).clamp(0.0, 1.0));
-                    
-                    // Y axis
-                    axes.insert("Y".to_string(), (joy_info.dwYpos as f32 / MAX_AXIS_VALUE).clamp(0.0, 1.0));
-                    
-                    // Z axis (throttle on many devices)
-                    axes.insert("Z".to_string(), (joy_info.dwZpos as f32 / MAX_AXIS_VALUE).clamp(0.0, 1.0));
-                    
-                    // R axis (rudder/twist)
-                    axes.insert("R".to_string(), (joy_info.dwRpos as f32 / MAX_AXIS_VALUE).clamp(0.0, 1.0));
-                    
-                    // U axis
-                    axes.insert("U".to_string(), (joy_info.dwUpos as f32 / MAX_AXIS_VALUE).clamp(0.0, 1.0));
-                    
-                    // V axis
-                    axes.insert("V".to_string(), (joy_info.dwVpos as f32 / MAX_AXIS_VALUE).clamp(0.0, 1.0));
-                    
-                    // Read button states (up to MAX_BUTTONS_PER_DEVICE buttons)
-                    let button_mask = joy_info.dwButtons;
-                    for btn_num in 0..MAX_BUTTONS_PER_DEVICE {
-                        let is_pressed = (button_mask & (1 << btn_num)) != 0;
-                        if is_pressed || btn_num < device.num_buttons {
-                            // Only include buttons that exist or are currently pressed
-                            buttons.insert(format!("Button{}", btn_num + 1), is_pressed);
-                        }
-                    }
-                    
-                    // POV Hat switch (returns angle in hundredths of degrees, 0-35900, or 0xFFFF for centered)
-                    if joy_info.dwPOV != 0xFFFF {
-                        let pov_angle = joy_info.dwPOV as f32 / 100.0; // Convert to degrees
-                        axes.insert("POV".to_string(), pov_angle / 360.0); // Normalize to 0.0-1.0
-                        
-                        // Also provide discrete POV directions as buttons for convenience
-                        buttons.insert("POV_Up".to_string(), pov_angle >= 315.0 || pov_angle <= 45.0);
-                        buttons.insert("POV_Right".to_string(), (45.0..=135.0).contains(&pov_angle));
-                        buttons.insert("POV_Down".to_string(), (135.0..=225.0).contains(&pov_angle));
-                        buttons.insert("POV_Left".to_string(), (225.0..=315.0).contains(&pov_angle));
-                    } else {
-                        buttons.insert("POV_Centered".to_string(), true);
-                    }
-                    
-                    // Cache and add to results
-                    self.axis_cache.insert(device.id, axes.clone());
-                    self.button_cache.insert(device.id, buttons.clone());
-                    
-                    all_axes.push(AxisData {
-                        device_handle: device.id.to_string(),
-                        device_name: device.name.clone(),
-                        manufacturer: device.manufacturer.clone(),
-                        product_id: device.product_id,
-                        vendor_id: device.vendor_id,
-                        axes,
-                        buttons,
-                    });
-                } else if let Some(cached_axes) = self.axis_cache.get(&device.id) {
-                    // Use cached values if read failed
-                    let cached_buttons = self.button_cache.get(&device.id).cloned().unwrap_or_default();
-                    all_axes.push(AxisData {
-                        device_handle: device.id.to_string(),
-                        device_name: device.name.clone(),
-                        manufacturer: device.manufacturer.clone(),
-                        product_id: device.product_id,
-                        vendor_id: device.vendor_id,
-                        axes: cached_axes.clone(),
-                        buttons: cached_buttons,
-                    });
+
+        // Cloned up front so `unwrap_rotary_axes` (which needs `&mut self`) can be called
+        // inside the loop without fighting the borrow checker over `self.devices`.
+        let devices = self.devices.clone();
+
+        // Each device's read runs behind `catch_unwind` - this is a hot poll loop, and one
+        // misbehaving driver panicking (e.g. on a bogus DirectInput object offset) shouldn't
+        // take every other bound device down with it for that tick.
+        for device in &devices {
+            if is_device_disabled(device.vendor_id, device.product_id, &device.name) {
+                continue;
+            }
+
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.read_device_axes(device))) {
+                Ok(Some(axis_data)) => all_axes.push(axis_data),
+                Ok(None) => {}
+                Err(_) => {
+                    tracing::warn!("[Input] Panic reading device {} ('{}'), skipping it for this poll", device.id, device.name);
                 }
             }
         }
-        
+
         Ok(all_axes)
     }
+
+    /// Read one device's axes/hats/sliders/buttons, trying DirectInput first and falling back
+    /// to the legacy Windows Joystick API (or, failing that, cached values) - split out of
+    /// `read_all_axes` so each device's read can be wrapped in `catch_unwind` independently.
+    fn read_device_axes(&mut self, device: &DeviceInfo) -> Option<AxisData> {
+        if let Some(gaming_device) = self.gaming_devices.get(&device.id) {
+            if let Ok((axes, buttons)) = gaming_input::read(gaming_device) {
+                self.axis_cache.insert(device.id, axes.clone());
+                self.button_cache.insert(device.id, buttons.clone());
+
+                let rotary_axes = rotary_axes_for(device.vendor_id, device.product_id, &device.name);
+                let rotary_position = self.unwrap_rotary_axes(device.id, &rotary_axes, &axes);
+
+                return Some(AxisData {
+                    device_handle: device.id.to_string(),
+                    device_key: device.device_key.clone(),
+                    device_name: device.name.clone(),
+                    manufacturer: device.manufacturer.clone(),
+                    product_id: device.product_id,
+                    vendor_id: device.vendor_id,
+                    axis_labels: axis_labels_for(device.vendor_id, device.product_id, &device.name),
+                    rotary_position,
+                    axes,
+                    buttons,
+                });
+            }
+        }
+
+        if let Some(di_device) = self.di_devices.get(&device.id) {
+            if let Some((axes, buttons)) = self.read_direct_input_axes(di_device) {
+                self.axis_cache.insert(device.id, axes.clone());
+                self.button_cache.insert(device.id, buttons.clone());
+
+                let rotary_axes = rotary_axes_for(device.vendor_id, device.product_id, &device.name);
+                let rotary_position = self.unwrap_rotary_axes(device.id, &rotary_axes, &axes);
+
+                return Some(AxisData {
+                    device_handle: device.id.to_string(),
+                    device_key: device.device_key.clone(),
+                    device_name: device.name.clone(),
+                    manufacturer: device.manufacturer.clone(),
+                    product_id: device.product_id,
+                    vendor_id: device.vendor_id,
+                    axis_labels: axis_labels_for(device.vendor_id, device.product_id, &device.name),
+                    rotary_position,
+                    axes,
+                    buttons,
+                });
+            }
+        }
+
+        // Fall back to the legacy Windows Joystick API for devices DirectInput
+        // couldn't bind (e.g. very old drivers), or use cached values on read failure.
+        unsafe {
+            let mut joy_info: JOYINFOEX = std::mem::zeroed();
+            joy_info.dwSize = std::mem::size_of::<JOYINFOEX>() as u32;
+            joy_info.dwFlags = 0xFFu32 | (JOY_USEDEADZONE as u32); // Request all axes
+
+            let result = joyGetPosEx(device.id, &mut joy_info as *mut JOYINFOEX);
+
+            if result == JOYERR_NOERROR {
+                let mut axes = HashMap::new();
+                let mut buttons = HashMap::new();
+
+                // Normalise against this device's own reported range rather than assuming
+                // every driver reports a full 16-bit span - see `JoyAxisRanges`.
+                let ranges = self.joy_axis_ranges.get(&device.id).copied();
+                let normalise = |raw: u32, range: Option<(u32, u32)>| -> f32 {
+                    let (min, max) = range.unwrap_or((0, MAX_AXIS_VALUE as u32));
+                    let span = max.saturating_sub(min).max(1) as f32;
+                    ((raw.saturating_sub(min)) as f32 / span).clamp(0.0, 1.0)
+                };
+
+                // Only report axes the device actually claims to have, in Windows' own
+                // X/Y/Z/R/U/V precedence order - a pure button box reports wNumAxes == 0
+                // but joyGetPosEx still fills in all six fields with whatever garbage
+                // centered values the driver happens to return, which would otherwise show
+                // up as six phantom half-value axes to bind against.
+                let legacy_axes: [(&str, u32, Option<(u32, u32)>); 6] = [
+                    ("X", joy_info.dwXpos, ranges.map(|r| r.x)),
+                    ("Y", joy_info.dwYpos, ranges.map(|r| r.y)),
+                    ("Z", joy_info.dwZpos, ranges.map(|r| r.z)),
+                    ("R", joy_info.dwRpos, ranges.map(|r| r.r)),
+                    ("U", joy_info.dwUpos, ranges.map(|r| r.u)),
+                    ("V", joy_info.dwVpos, ranges.map(|r| r.v)),
+                ];
+                for &(name, raw, range) in legacy_axes.iter().take(device.num_axes as usize) {
+                    axes.insert(name.to_string(), normalise(raw, range));
+                }
+
+                let button_mask = joy_info.dwButtons;
+                for btn_num in 0..MAX_BUTTONS_PER_DEVICE {
+                    let is_pressed = (button_mask & (1 << btn_num)) != 0;
+                    if is_pressed || btn_num < device.num_buttons {
+                        buttons.insert(format!("Button{}", btn_num + 1), is_pressed);
+                    }
+                }
+
+                if joy_info.dwPOV != 0xFFFF {
+                    let pov_angle = joy_info.dwPOV as f32 / 100.0;
+                    axes.insert("POV".to_string(), pov_angle / 360.0);
+
+                    buttons.insert("POV_Up".to_string(), pov_angle >= 315.0 || pov_angle <= 45.0);
+                    buttons.insert("POV_Right".to_string(), (45.0..=135.0).contains(&pov_angle));
+                    buttons.insert("POV_Down".to_string(), (135.0..=225.0).contains(&pov_angle));
+                    buttons.insert("POV_Left".to_string(), (225.0..=315.0).contains(&pov_angle));
+                } else {
+                    buttons.insert("POV_Centered".to_string(), true);
+                }
+
+                self.axis_cache.insert(device.id, axes.clone());
+                self.button_cache.insert(device.id, buttons.clone());
+
+                let rotary_axes = rotary_axes_for(device.vendor_id, device.product_id, &device.name);
+                let rotary_position = self.unwrap_rotary_axes(device.id, &rotary_axes, &axes);
+
+                Some(AxisData {
+                    device_handle: device.id.to_string(),
+                    device_key: device.device_key.clone(),
+                    device_name: device.name.clone(),
+                    manufacturer: device.manufacturer.clone(),
+                    product_id: device.product_id,
+                    vendor_id: device.vendor_id,
+                    axis_labels: axis_labels_for(device.vendor_id, device.product_id, &device.name),
+                    rotary_position,
+                    axes,
+                    buttons,
+                })
+            } else if let Some(cached_axes) = self.axis_cache.get(&device.id).cloned() {
+                let cached_buttons = self.button_cache.get(&device.id).cloned().unwrap_or_default();
+                let rotary_axes = rotary_axes_for(device.vendor_id, device.product_id, &device.name);
+                let rotary_position = self.unwrap_rotary_axes(device.id, &rotary_axes, &cached_axes);
+                Some(AxisData {
+                    device_handle: device.id.to_string(),
+                    device_key: device.device_key.clone(),
+                    device_name: device.name.clone(),
+                    manufacturer: device.manufacturer.clone(),
+                    product_id: device.product_id,
+                    vendor_id: device.vendor_id,
+                    axis_labels: axis_labels_for(device.vendor_id, device.product_id, &device.name),
+                    rotary_position,
+                    axes: cached_axes,
+                    buttons: cached_buttons,
+                })
+            } else {
+                None
+            }
+        }
+    }
 }
 
 #[cfg(windows)]
@@ -286,33 +823,157 @@ impl Drop for HidInputManager {
     }
 }
 
-#[cfg(not(windows))]
+#[cfg(all(not(windows), not(feature = "dev-mock")))]
 impl HidInputManager {
-    pub fn new() -> Result<Self, String> {
+    pub fn new(_backend: InputBackendKind) -> Result<Self, String> {
         Err("Input manager only supported on Windows".to_string())
     }
-    
+
     pub fn enumerate_devices(&mut self) -> Result<(), String> {
         Err("Input manager only supported on Windows".to_string())
     }
-    
+
     pub fn get_devices(&self) -> &[DeviceInfo] {
         &[]
     }
-    
+
+    pub fn axis_cache_snapshot(&self) -> HashMap<u32, HashMap<String, f32>> {
+        HashMap::new()
+    }
+
     pub fn read_all_axes(&mut self) -> Result<Vec<AxisData>, String> {
         Err("Input manager only supported on Windows".to_string())
     }
 }
 
-// Global input manager instance
-static INPUT_MANAGER: Mutex<Option<HidInputManager>> = Mutex::new(None);
+/// Synthetic backend for developing the frontend off Windows - see the `dev-mock` feature
+/// doc comment in `Cargo.toml`. One fake two-axis, four-button stick plus one fake throttle
+/// quadrant, both with in-memory state that moves a little each poll so bindings/meters have
+/// something to actually react to instead of sitting dead flat.
+#[cfg(all(not(windows), feature = "dev-mock"))]
+impl HidInputManager {
+    pub fn new(backend: InputBackendKind) -> Result<Self, String> {
+        Ok(Self {
+            devices: Vec::new(),
+            axis_cache: HashMap::new(),
+            button_cache: HashMap::new(),
+            backend,
+            rotary_last_raw: HashMap::new(),
+            rotary_position: HashMap::new(),
+        })
+    }
+
+    pub fn enumerate_devices(&mut self) -> Result<(), String> {
+        self.devices = vec![
+            DeviceInfo {
+                id: 0,
+                device_key: "mock-0000-0000-Mock Joystick".to_string(),
+                name: "Mock Joystick".to_string(),
+                manufacturer: "ClearComms Dev".to_string(),
+                vendor_id: 0,
+                product_id: 0,
+                num_axes: 2,
+                num_buttons: 4,
+            },
+            DeviceInfo {
+                id: 1,
+                device_key: "mock-0000-0001-Mock Throttle Quadrant".to_string(),
+                name: "Mock Throttle Quadrant".to_string(),
+                manufacturer: "ClearComms Dev".to_string(),
+                vendor_id: 0,
+                product_id: 1,
+                num_axes: 2,
+                num_buttons: 0,
+            },
+        ];
+        Ok(())
+    }
+
+    pub fn get_devices(&self) -> &[DeviceInfo] {
+        &self.devices
+    }
+
+    pub fn axis_cache_snapshot(&self) -> HashMap<u32, HashMap<String, f32>> {
+        self.axis_cache.clone()
+    }
+
+    /// Drifts each mock axis a little every call (a slow sine-ish wobble, not real noise),
+    /// so something in the UI actually moves without needing real hardware connected.
+    pub fn read_all_axes(&mut self) -> Result<Vec<AxisData>, String> {
+        let mut results = Vec::with_capacity(self.devices.len());
+
+        for device in &self.devices {
+            if is_device_disabled(device.vendor_id, device.product_id, &device.name) {
+                continue;
+            }
+
+            let axes = self.axis_cache.entry(device.id).or_default();
+            let tick = axes.entry("_tick".to_string()).or_insert(0.0);
+            *tick += 0.02;
+            let wobble = (tick.sin() + 1.0) / 2.0;
+
+            let mut axis_values = HashMap::new();
+            axis_values.insert("X".to_string(), wobble);
+            axis_values.insert("Y".to_string(), 1.0 - wobble);
+
+            let mut button_values = HashMap::new();
+            for i in 0..device.num_buttons {
+                button_values.insert(format!("Button {}", i + 1), false);
+            }
+
+            results.push(AxisData {
+                device_handle: device.id.to_string(),
+                device_key: device.device_key.clone(),
+                device_name: device.name.clone(),
+                manufacturer: device.manufacturer.clone(),
+                product_id: device.product_id,
+                vendor_id: device.vendor_id,
+                axes: axis_values,
+                buttons: button_values,
+                axis_labels: HashMap::new(),
+                rotary_position: HashMap::new(),
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+/// Tauri-managed slot for the input manager, installed via `app.manage(...)` in `main.rs`'s
+/// `setup`. Ties the manager's lifetime to the app (so `Drop` runs on shutdown) instead of a
+/// process-lifetime static, without changing the "not initialised until `init_direct_input`
+/// runs" behavior any command relies on.
+pub type InputManagerState = Mutex<Option<HidInputManager>>;
+
+/// Acquire the managed `InputManagerState`'s lock, recovering from a poisoned mutex instead of propagating it -
+/// a command handler that panics while holding this lock would otherwise brick every input
+/// command with "Failed to lock input mutex" until the app restarts. Logs the recovery so the
+/// underlying panic is still visible in the logs.
+fn lock_input_manager(state: &InputManagerState) -> std::sync::MutexGuard<'_, Option<HidInputManager>> {
+    state.lock().unwrap_or_else(|e| {
+        tracing::error!("[Input] Recovered from poisoned input manager mutex: {}", e);
+        e.into_inner()
+    })
+}
+
+/// Take the managed input manager out of its slot and drop it, releasing the HID API
+/// handle and any bound DirectInput devices. Safe to call even if the manager was
+/// never initialised.
+pub fn shutdown(app: &tauri::AppHandle) {
+    let mut lock = lock_input_manager(&app.state::<InputManagerState>());
+    if let Some(manager) = lock.take() {
+        tracing::info!("[Input] Shutting down input manager...");
+        drop(manager);
+    }
+}
 
-/// Initialise input system and enumerate devices
+/// Initialise input system and enumerate devices. `backend` selects which API controllers are
+/// read through - see `InputBackendKind` - and defaults to `DirectInput`, the long-standing
+/// behaviour, so existing callers that don't pass it see no change.
 #[tauri::command]
-pub fn init_direct_input() -> Result<String, String> {
+pub fn init_direct_input(state: tauri::State<'_, InputManagerState>, backend: Option<InputBackendKind>) -> Result<String, String> {
     tracing::info!("[Input] Initialising HID input manager...");
-    let mut manager = HidInputManager::new()?;
+    let mut manager = HidInputManager::new(backend.unwrap_or_default())?;
 
     tracing::info!("[Input] Enumerating devices...");
     manager.enumerate_devices()?;
@@ -326,23 +987,19 @@ pub fn init_direct_input() -> Result<String, String> {
             tracing::info!("[Input]   - {}", device.to_display_string());
         }
     }
-    
-    let mut lock = INPUT_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock input mutex: {}", e))?;
-    
+
+    let mut lock = lock_input_manager(&state);
+
     *lock = Some(manager);
-    
+
     Ok(format!("Input initialised successfully ({} controllers found)", device_count))
 }
 
 /// Get the current status of input system
 #[tauri::command]
-pub fn get_direct_input_status() -> Result<String, String> {
-    let lock = INPUT_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock input mutex: {}", e))?;
-    
+pub fn get_direct_input_status(state: tauri::State<'_, InputManagerState>) -> Result<String, String> {
+    let lock = lock_input_manager(&state);
+
     match lock.as_ref() {
         Some(manager) => {
             let device_count = manager.get_devices().len();
@@ -357,49 +1014,88 @@ pub fn get_direct_input_status() -> Result<String, String> {
 
 /// Enumerate all connected game controllers
 #[tauri::command]
-pub fn enumerate_input_devices() -> Result<Vec<String>, String> {
-    let mut lock = INPUT_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock input mutex: {}", e))?;
-    
+pub fn enumerate_input_devices(state: tauri::State<'_, InputManagerState>) -> Result<Vec<String>, String> {
+    let mut lock = lock_input_manager(&state);
+
     let manager = lock
         .as_mut()
         .ok_or("Input not initialised. Call init_direct_input first.")?;
-    
+
     // Re-enumerate devices
     manager.enumerate_devices()?;
-    
+
     // Return device info as human-readable strings
     let device_list: Vec<String> = manager
         .get_devices()
         .iter()
         .map(|dev| dev.to_display_string())
         .collect();
-    
+
     Ok(device_list)
 }
 
+/// Enumerate all connected game controllers as structured data, for UIs that want
+/// the raw fields (e.g. `vendor_id`/`product_id` for a calibration picker) instead
+/// of `enumerate_input_devices`'s pre-formatted display string.
+#[tauri::command]
+pub fn get_input_devices(state: tauri::State<'_, InputManagerState>) -> Result<Vec<DeviceInfo>, String> {
+    let mut lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_mut()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    // Re-enumerate devices
+    manager.enumerate_devices()?;
+
+    Ok(manager.get_devices().to_vec())
+}
+
+/// Full internal input-manager state, for pasting into a bug report when calibration or
+/// axis health looks wrong instead of describing it secondhand - see `get_input_debug`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputDebugState {
+    pub devices: Vec<DeviceInfo>,
+    /// Last-read axis values per device ID, as cached by `read_all_axes` - see
+    /// `HidInputManager::axis_cache`.
+    pub axis_cache: HashMap<u32, HashMap<String, f32>>,
+}
+
+/// Read-only dump of the manager's full internal state - `devices` and a snapshot of
+/// `axis_cache` - for diagnosing input issues from a bug report rather than guessing.
+/// Doesn't re-enumerate first, unlike `get_input_devices`, so it reflects exactly what
+/// the manager already has cached.
+#[tauri::command]
+pub fn get_input_debug(state: tauri::State<'_, InputManagerState>) -> Result<InputDebugState, String> {
+    let lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    Ok(InputDebugState {
+        devices: manager.get_devices().to_vec(),
+        axis_cache: manager.axis_cache_snapshot(),
+    })
+}
+
 /// Get axis values from all game controllers
 #[tauri::command]
-pub fn get_all_axis_values() -> Result<Vec<AxisData>, String> {
-    let mut lock = INPUT_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock input mutex: {}", e))?;
-    
+pub fn get_all_axis_values(state: tauri::State<'_, InputManagerState>) -> Result<Vec<AxisData>, String> {
+    let mut lock = lock_input_manager(&state);
+
     let manager = lock
         .as_mut()
         .ok_or("Input not initialised. Call init_direct_input first.")?;
-    
+
     manager.read_all_axes()
 }
 
 /// Clean up input manager resources
 #[tauri::command]
-pub fn cleanup_input_manager() -> Result<String, String> {
-    let mut lock = INPUT_MANAGER
-        .lock()
-        .map_err(|e| format!("Failed to lock input mutex: {}", e))?;
-    
+pub fn cleanup_input_manager(state: tauri::State<'_, InputManagerState>) -> Result<String, String> {
+    let mut lock = lock_input_manager(&state);
+
     match lock.as_mut() {
         Some(manager) => {
             manager.cleanup();
@@ -407,4 +1103,410 @@ pub fn cleanup_input_manager() -> Result<String, String> {
         }
         None => Ok("Input manager not initialised".to_string())
     }
-}
\ No newline at end of file
+}
+
+/// Get the currently known devices without forcing a re-enumeration, for callers that just
+/// need to match against device identity (e.g. CSV import) rather than read live axis data.
+pub fn list_devices(app: &tauri::AppHandle) -> Result<Vec<DeviceInfo>, String> {
+    let lock = lock_input_manager(&app.state::<InputManagerState>());
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    Ok(manager.get_devices().to_vec())
+}
+
+/// Generation counter for the axis-graph poller. Starting a graph bumps it and captures the
+/// new value; stopping (or starting another graph) bumps it again, which the running poller
+/// thread notices on its next tick and exits. Simpler than threading a cancellation channel
+/// through `start_axis_graph`'s return value, which `#[tauri::command]` can't easily carry.
+static AXIS_GRAPH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Interval between `"axis-graph"` emissions (20Hz).
+const AXIS_GRAPH_INTERVAL_MS: u64 = 50;
+
+/// Start streaming normalised axis values for one device as `"axis-graph"` events, for a
+/// live scope view while calibrating. Runs on its own background thread at a fixed 20Hz
+/// cadence and stops automatically if the device disappears (unplugged) or `stop_axis_graph`
+/// is called.
+#[tauri::command]
+pub fn start_axis_graph(app: tauri::AppHandle, device_id: u32) -> Result<(), String> {
+    let generation = AXIS_GRAPH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let device_handle = device_id.to_string();
+
+    std::thread::spawn(move || {
+        tracing::info!("[Input] Starting axis graph for device {}", device_id);
+
+        loop {
+            if AXIS_GRAPH_GENERATION.load(Ordering::SeqCst) != generation {
+                break;
+            }
+
+            let axes = {
+                let mut lock = lock_input_manager(&app.state::<InputManagerState>());
+
+                match lock.as_mut() {
+                    Some(manager) => manager.read_all_axes().ok(),
+                    None => None,
+                }
+            };
+
+            match axes.and_then(|devices| devices.into_iter().find(|d| d.device_handle == device_handle)) {
+                Some(device_axes) => {
+                    let _ = app.emit("axis-graph", &device_axes);
+                }
+                None => {
+                    tracing::info!("[Input] Axis graph device {} disappeared, stopping", device_id);
+                    break;
+                }
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(AXIS_GRAPH_INTERVAL_MS));
+        }
+    });
+
+    Ok(())
+}
+
+/// Stop any running axis-graph poller.
+#[tauri::command]
+pub fn stop_axis_graph() {
+    AXIS_GRAPH_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// The device/input that triggered `capture_next_input`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedInput {
+    pub device_key: String,
+    pub device_handle: String,
+    pub device_name: String,
+    /// "axis" or "button" - which kind of input triggered the capture.
+    pub kind: String,
+    /// The axis or button name that triggered it (see `kind`).
+    pub name: String,
+}
+
+/// How far an axis must move between polls to count as a deliberate press rather than
+/// noise, matching the frontend's own binding-mode movement threshold.
+const CAPTURE_AXIS_MOVEMENT_THRESHOLD: f32 = 0.05;
+
+/// Poll interval while waiting for input in `capture_next_input`.
+const CAPTURE_POLL_INTERVAL_MS: u64 = 50;
+
+/// Watch all devices for the next button press or significant axis movement - the
+/// standard "press the button you want to bind" UX - and resolve with whichever device
+/// and input triggered it. Blocks the calling thread (Tauri runs non-async commands on
+/// its blocking worker pool, so this doesn't stall the UI), polling at the same cadence
+/// as `start_axis_graph`. Returns a timeout error if nothing happens within `timeout_ms`.
+#[tauri::command]
+pub fn capture_next_input(app: tauri::AppHandle, timeout_ms: u64) -> Result<CapturedInput, String> {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+    let mut baseline: Option<Vec<AxisData>> = None;
+
+    loop {
+        if std::time::Instant::now() >= deadline {
+            return Err("Timed out waiting for input".to_string());
+        }
+
+        let current = {
+            let mut lock = lock_input_manager(&app.state::<InputManagerState>());
+            let manager = lock.as_mut().ok_or("Input not initialised. Call init_direct_input first.")?;
+            manager.read_all_axes()?
+        };
+
+        if let Some(previous) = &baseline {
+            for device in &current {
+                let Some(prev_device) = previous.iter().find(|d| d.device_handle == device.device_handle) else {
+                    continue;
+                };
+
+                for (button_name, &pressed) in &device.buttons {
+                    let was_pressed = prev_device.buttons.get(button_name).copied().unwrap_or(false);
+                    if pressed && !was_pressed {
+                        return Ok(CapturedInput {
+                            device_key: device.device_key.clone(),
+                            device_handle: device.device_handle.clone(),
+                            device_name: device.device_name.clone(),
+                            kind: "button".to_string(),
+                            name: button_name.clone(),
+                        });
+                    }
+                }
+
+                for (axis_name, &value) in &device.axes {
+                    let Some(&prev_value) = prev_device.axes.get(axis_name) else { continue };
+                    if (value - prev_value).abs() > CAPTURE_AXIS_MOVEMENT_THRESHOLD {
+                        return Ok(CapturedInput {
+                            device_key: device.device_key.clone(),
+                            device_handle: device.device_handle.clone(),
+                            device_name: device.device_name.clone(),
+                            kind: "axis".to_string(),
+                            name: axis_name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        baseline = Some(current);
+        std::thread::sleep(std::time::Duration::from_millis(CAPTURE_POLL_INTERVAL_MS));
+    }
+}
+
+/// One axis's observed span over a `get_axis_range_report` capture window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AxisRangeReport {
+    pub min: f32,
+    pub max: f32,
+    /// Whether `min`/`max` reached within `AXIS_RANGE_EXTREME_TOLERANCE` of 0.0/1.0 - the
+    /// basis for a UI's "Throttle: 0.00-1.00 ✓" vs. flagging an axis that never reached
+    /// its calibrated extremes while being swept.
+    pub reaches_extremes: bool,
+}
+
+/// How close to 0.0/1.0 an axis's observed min/max must get to count as reaching that extreme -
+/// a lever rarely bottoms out at exactly 0.0 by hand.
+const AXIS_RANGE_EXTREME_TOLERANCE: f32 = 0.02;
+
+/// Default capture window for `get_axis_range_report`, in ms - long enough to sweep a lever
+/// stop-to-stop a couple of times without the verification step itself feeling slow.
+const AXIS_RANGE_DEFAULT_WINDOW_MS: u64 = 4000;
+
+/// Record each of `device_id`'s axes' min/max normalised value over a short capture window
+/// (default `AXIS_RANGE_DEFAULT_WINDOW_MS`, override with `window_ms`), for confirming a
+/// calibration actually spans the full 0.0-1.0 range after the user sweeps every lever/slider
+/// stop-to-stop. Blocks the calling thread for the window's duration - same reasoning as
+/// `capture_next_input` for why that's fine here.
+#[tauri::command]
+pub fn get_axis_range_report(
+    app: tauri::AppHandle,
+    device_id: u32,
+    window_ms: Option<u64>,
+) -> Result<HashMap<String, AxisRangeReport>, String> {
+    let device_handle = device_id.to_string();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(window_ms.unwrap_or(AXIS_RANGE_DEFAULT_WINDOW_MS));
+    let mut ranges: HashMap<String, (f32, f32)> = HashMap::new();
+
+    loop {
+        let current = {
+            let mut lock = lock_input_manager(&app.state::<InputManagerState>());
+            let manager = lock.as_mut().ok_or("Input not initialised. Call init_direct_input first.")?;
+            manager.read_all_axes()?
+        };
+
+        let device = current
+            .into_iter()
+            .find(|d| d.device_handle == device_handle)
+            .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+        for (axis_name, &value) in &device.axes {
+            ranges
+                .entry(axis_name.clone())
+                .and_modify(|(min, max)| {
+                    *min = min.min(value);
+                    *max = max.max(value);
+                })
+                .or_insert((value, value));
+        }
+
+        if std::time::Instant::now() >= deadline {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(CAPTURE_POLL_INTERVAL_MS));
+    }
+
+    Ok(ranges
+        .into_iter()
+        .map(|(axis_name, (min, max))| {
+            let reaches_extremes = min <= AXIS_RANGE_EXTREME_TOLERANCE && max >= 1.0 - AXIS_RANGE_EXTREME_TOLERANCE;
+            (axis_name, AxisRangeReport { min, max, reaches_extremes })
+        })
+        .collect())
+}
+
+/// Set a user-friendly label for one axis of the given device (e.g. "Throttle" for "Z Axis"),
+/// persisted against the device's stable identity so it survives reboots and enumeration-order
+/// changes. Pass an empty `label` to remove a previously-set override.
+#[tauri::command]
+pub fn set_axis_label(state: tauri::State<'_, InputManagerState>, device_id: u32, axis: String, label: String) -> Result<(), String> {
+    let lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    let device = manager.get_devices().iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let key = device_identity_key(device.vendor_id, device.product_id, &device.name);
+
+    crate::settings::update(|s| {
+        let labels = s.axis_labels.entry(key.clone()).or_default();
+        if label.is_empty() {
+            labels.remove(&axis);
+        } else {
+            labels.insert(axis.clone(), label.clone());
+        }
+    });
+
+    Ok(())
+}
+
+/// Flag (or unflag) one axis of the given device as a continuously-wrapping rotary control,
+/// persisted against the device's stable identity the same way as `set_axis_label`. Flagged
+/// axes get wrap-around correction in `read_all_axes` (`AxisData::rotary_position`) instead of
+/// the normal absolute 0.0-1.0 reading.
+#[tauri::command]
+pub fn set_axis_rotary(state: tauri::State<'_, InputManagerState>, device_id: u32, axis: String, rotary: bool) -> Result<(), String> {
+    let lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    let device = manager.get_devices().iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let key = device_identity_key(device.vendor_id, device.product_id, &device.name);
+
+    crate::settings::update(|s| {
+        let axes = s.rotary_axes.entry(key.clone()).or_default();
+        if rotary {
+            axes.insert(axis.clone());
+        } else {
+            axes.remove(&axis);
+        }
+    });
+
+    Ok(())
+}
+
+/// Remove the stored label and rotary-flag override for one axis of the given device, so it
+/// reverts to its raw DirectInput/joystick name and a normal absolute (non-rotary) reading.
+/// There's no separate manual axis-range calibration step in this codebase - the 0.0-1.0 range
+/// is always derived straight from the device itself (`object.min`/`object.max`, or
+/// `JoyAxisRanges` on the legacy fallback path) - so these two per-axis overrides are what
+/// "reset this axis's calibration" means here.
+#[tauri::command]
+pub fn reset_axis_calibration(state: tauri::State<'_, InputManagerState>, device_id: u32, axis: String) -> Result<(), String> {
+    let lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    let device = manager.get_devices().iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let key = device_identity_key(device.vendor_id, device.product_id, &device.name);
+
+    crate::settings::update(|s| {
+        if let Some(labels) = s.axis_labels.get_mut(&key) {
+            labels.remove(&axis);
+        }
+        if let Some(axes) = s.rotary_axes.get_mut(&key) {
+            axes.remove(&axis);
+        }
+    });
+
+    Ok(())
+}
+
+/// Remove every stored label and rotary-flag override for the given device in one go - the
+/// whole-device counterpart to `reset_axis_calibration`.
+#[tauri::command]
+pub fn reset_device_calibration(state: tauri::State<'_, InputManagerState>, device_id: u32) -> Result<(), String> {
+    let lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    let device = manager.get_devices().iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let key = device_identity_key(device.vendor_id, device.product_id, &device.name);
+
+    crate::settings::update(|s| {
+        s.axis_labels.remove(&key);
+        s.rotary_axes.remove(&key);
+    });
+
+    Ok(())
+}
+
+/// Enable or disable background polling for the given device, persisted against its stable
+/// identity the same way as `set_axis_label`. A disabled device is skipped entirely in
+/// `read_all_axes` - no `joyGetPosEx`/DirectInput call at all - rather than just having its
+/// axes discarded, for hardware the user never binds but doesn't want woken or re-polled
+/// every cycle (e.g. a steering wheel that's plugged in but unused).
+#[tauri::command]
+pub fn set_device_polling(state: tauri::State<'_, InputManagerState>, device_id: u32, enabled: bool) -> Result<(), String> {
+    let lock = lock_input_manager(&state);
+
+    let manager = lock
+        .as_ref()
+        .ok_or("Input not initialised. Call init_direct_input first.")?;
+
+    let device = manager.get_devices().iter()
+        .find(|d| d.id == device_id)
+        .ok_or_else(|| format!("Device not found: {}", device_id))?;
+
+    let key = device_identity_key(device.vendor_id, device.product_id, &device.name);
+
+    crate::settings::update(|s| {
+        if enabled {
+            s.disabled_devices.remove(&key);
+        } else {
+            s.disabled_devices.insert(key.clone());
+        }
+    });
+
+    Ok(())
+}
+
+/// Every device identity currently excluded from polling, for a device-list UI to show which
+/// ones are toggled off - see `set_device_polling`.
+#[tauri::command]
+pub fn get_disabled_devices() -> Result<HashSet<String>, String> {
+    Ok(crate::settings::get().disabled_devices)
+}
+
+/// Volume taper applied to an axis→volume mapping. Per-binding curves travel with each
+/// `AxisMapping` (frontend state, mirrored to `binding_poller` via `sync_axis_mappings`, which
+/// applies them); the setting this backs is only the app-wide default that `createMapping`
+/// inherits when a new binding doesn't specify one explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisCurve {
+    /// Axis position maps straight to volume.
+    Linear,
+    /// Axis position is squared before becoming volume, approximating the perceptually-even
+    /// loudness steps of an audio-taper potentiometer (a true logarithm diverges at zero).
+    Logarithmic,
+}
+
+impl Default for AxisCurve {
+    fn default() -> Self {
+        AxisCurve::Linear
+    }
+}
+
+/// Get the default taper new axis bindings inherit when no explicit curve is given.
+#[tauri::command]
+pub fn get_default_curve() -> AxisCurve {
+    crate::settings::get().default_curve
+}
+
+/// Set the default taper new axis bindings inherit when no explicit curve is given.
+/// Existing bindings keep whatever curve they already have.
+#[tauri::command]
+pub fn set_default_curve(curve: AxisCurve) -> Result<(), String> {
+    crate::settings::update(|s| s.default_curve = curve);
+    Ok(())
+}
@@ -8,4 +8,93 @@
 //! - Connect to Flight Simulator via a WASM bridge
 //! - Read audio panel LVars (cockpit audio controls)
 //! - Subscribe to LVar changes for real-time updates
-//! - Map LVars to audio session volumes
\ No newline at end of file
+//! - Map LVars to audio session volumes
+//!
+//! None of the above is wired up yet - there's no WASM bridge connection in this codebase,
+//! the same gap `audio_management::map_simvar_to_session`'s doc comment describes for raw
+//! SimVars. `get_aircraft_lvar_map` and `set_aircraft_lvar_override` below are the piece that
+//! doesn't depend on that bridge existing: knowing *which* LVar names a given aircraft uses,
+//! so that whenever a subscription mechanism does land, it can look the right names up
+//! per-aircraft instead of every user hand-entering them.
+
+use std::collections::HashMap;
+
+/// One aircraft family's built-in audio-panel LVar names, matched against the sim's
+/// `ATC MODEL`/title by a case-insensitive substring rather than an exact title - third-party
+/// liveries and sim updates routinely rename the full title without changing the panel's
+/// underlying LVars.
+struct AircraftLvarProfile {
+    match_substring: &'static str,
+    lvars: &'static [(&'static str, &'static str)],
+}
+
+/// Built-in registry of known aircraft. Deliberately small - covers the default C172 and the
+/// most common freeware/payware airliner (the FlyByWire A32NX) as a starting point, with
+/// `set_aircraft_lvar_override` as the escape hatch for anything this list doesn't cover yet
+/// rather than waiting on every aircraft to be added here first.
+const BUILTIN_AIRCRAFT_LVAR_REGISTRY: &[AircraftLvarProfile] = &[
+    AircraftLvarProfile {
+        match_substring: "A320",
+        lvars: &[
+            ("com1_volume", "A32NX_AUDIO_RECEIVER_COM1_VOLUME"),
+            ("com2_volume", "A32NX_AUDIO_RECEIVER_COM2_VOLUME"),
+            ("com1_receive", "A32NX_AUDIO_RECEIVER_COM1_RECEIVE"),
+            ("com2_receive", "A32NX_AUDIO_RECEIVER_COM2_RECEIVE"),
+        ],
+    },
+    AircraftLvarProfile {
+        match_substring: "C172",
+        lvars: &[
+            ("com1_volume", "XMLVAR_Audio_Radio_COM1_Volume"),
+            ("com2_volume", "XMLVAR_Audio_Radio_COM2_Volume"),
+            ("com1_receive", "XMLVAR_Audio_Radio_COM1_Receive"),
+            ("com2_receive", "XMLVAR_Audio_Radio_COM2_Receive"),
+        ],
+    },
+];
+
+/// Resolve `aircraft` (the sim's `ATC MODEL`/title) to its audio-panel LVar names, keyed by
+/// role (`"com1_volume"`, `"com2_volume"`, `"com1_receive"`, `"com2_receive"`). Starts from
+/// every built-in profile whose `match_substring` appears in `aircraft`, then layers
+/// `settings::aircraft_lvar_overrides` for that exact `aircraft` string on top so a per-role
+/// override always wins over the built-in registry without needing to replace the whole entry.
+fn lvar_map_for_aircraft(aircraft: &str) -> HashMap<String, String> {
+    let aircraft_lower = aircraft.to_lowercase();
+    let mut map = HashMap::new();
+
+    for profile in BUILTIN_AIRCRAFT_LVAR_REGISTRY {
+        if aircraft_lower.contains(&profile.match_substring.to_lowercase()) {
+            for (role, lvar) in profile.lvars {
+                map.insert(role.to_string(), lvar.to_string());
+            }
+        }
+    }
+
+    if let Some(overrides) = crate::settings::get().aircraft_lvar_overrides.get(aircraft) {
+        for (role, lvar) in overrides {
+            map.insert(role.clone(), lvar.clone());
+        }
+    }
+
+    map
+}
+
+/// Get the audio-panel LVar map for `aircraft` - see `lvar_map_for_aircraft`. Empty (not an
+/// error) when nothing built-in matches and no override exists, so an unrecognised aircraft
+/// just means "nothing to auto-subscribe" rather than a failure.
+#[tauri::command]
+pub fn get_aircraft_lvar_map(aircraft: String) -> std::result::Result<HashMap<String, String>, String> {
+    Ok(lvar_map_for_aircraft(&aircraft))
+}
+
+/// Set a user override for one audio-panel role on `aircraft` (the exact `ATC MODEL`/title
+/// string, not a substring), taking precedence over anything `BUILTIN_AIRCRAFT_LVAR_REGISTRY`
+/// would otherwise resolve for it - for an aircraft this registry gets wrong, or doesn't cover
+/// at all yet.
+#[tauri::command]
+pub fn set_aircraft_lvar_override(aircraft: String, role: String, lvar: String) -> std::result::Result<(), String> {
+    crate::settings::update(|s| {
+        s.aircraft_lvar_overrides.entry(aircraft).or_default().insert(role, lvar);
+    });
+    Ok(())
+}
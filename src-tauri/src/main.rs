@@ -14,25 +14,38 @@
 //! ## Modules
 //!
 //! - [`audio_management`] - Windows Core Audio API integration
+//! - [`binding_poller`] - Background thread that applies axis/button bindings regardless
+//!   of window visibility
+//! - [`control_server`] - Optional local WebSocket control surface for external controllers
+//! - [`discord_presence`] - Optional Discord voice-channel presence (`discord-rpc` feature)
 //! - [`hardware_input`] - RawInput/HID device polling
 //! - [`lvar_input`] - Flight Simulator LVar integration
+//! - [`midi_input`] - Optional MIDI controller input (`midi` feature)
 //! - [`native_menu`] - Windows system tray context menu
+//! - [`settings`] - Persisted user-configurable settings
 //! - [`window_utils`] - Window positioning utilities
 
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use tauri::image::Image;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use tauri::tray::{TrayIconBuilder, TrayIconId, MouseButton, MouseButtonState};
 
 mod audio_management;
+mod binding_poller;
+mod control_server;
+mod discord_presence;
 mod hardware_input;
 mod lvar_input;
+mod midi_input;
+mod mute_key_hook;
 mod native_menu;
+mod settings;
 mod window_utils;
 
 use window_utils::position_window_bottom_right;
@@ -51,24 +64,42 @@ struct LayoutMeasurements {
     channel_gap: u32,
     /// Base window width for single channel (logical pixels)
     base_width: u32,
+    /// Floor on the calculated window width - see `settings::Settings::layout_min_width`.
+    min_width: u32,
+    /// Ceiling on the calculated window width - see `settings::Settings::layout_max_width`.
+    max_width: u32,
 }
 
 impl Default for LayoutMeasurements {
     fn default() -> Self {
+        // Seeded from settings so a user's custom channel layout (see `set_channel_layout`)
+        // survives a restart instead of reverting to the stock theme's measurements until the
+        // frontend's next `update_layout_measurements` call overwrites them.
+        let settings = settings::get();
         LayoutMeasurements {
-            channel_width: 48,   // CSS: max-width: 3rem = 48px at 100% scale
-            channel_gap: 48,     // CSS: gap: 3rem = 48px at 100% scale
-            base_width: 250,     // Standard base width for single channel
+            channel_width: settings.layout_channel_width, // CSS: max-width: 3rem = 48px at 100% scale
+            channel_gap: 48,                               // CSS: gap: 3rem = 48px at 100% scale
+            base_width: settings.layout_base_width,        // Standard base width for single channel
+            min_width: settings.layout_min_width,
+            max_width: settings.layout_max_width,
         }
     }
 }
 
 // Global layout measurements, protected by mutex
 lazy_static::lazy_static! {
-    static ref LAYOUT_MEASUREMENTS: Arc<Mutex<LayoutMeasurements>> = 
+    static ref LAYOUT_MEASUREMENTS: Arc<Mutex<LayoutMeasurements>> =
         Arc::new(Mutex::new(LayoutMeasurements::default()));
 }
 
+/// Scale factor as of the last `ScaleFactorChanged` event (or window creation), used to convert
+/// the window's current physical size back to logical pixels before the new factor is applied.
+/// `window.scale_factor()` can already report the *new* value by the time the event handler
+/// runs, so the old value has to be tracked separately rather than re-queried.
+lazy_static::lazy_static! {
+    static ref LAST_SCALE_FACTOR: Mutex<f64> = Mutex::new(1.0);
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Constants
 // ─────────────────────────────────────────────────────────────────────────────
@@ -89,6 +120,133 @@ const RESIZE_ANIMATION_FRAME_MS: u64 = 8;
 /// Tray icon identifier
 const TRAY_ICON_ID: &str = "clearcomms-tray";
 
+/// How long a `Moved` event must go unsuperseded before it's treated as the end
+/// of a drag and considered for edge-snapping.
+const DRAG_SETTLE_MS: u64 = 150;
+
+/// How often the auto-hide monitor thread checks elapsed idle time against
+/// `auto_hide_after_seconds`. Coarse on purpose - this only ever decides whether to hide a
+/// window the user has already stopped touching for several seconds, so sub-second
+/// precision isn't useful.
+const AUTO_HIDE_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Timestamp of the last user-interaction signal (a show/focus command, or mouse activity
+/// reported by the frontend), used by the auto-hide monitor thread to decide when the
+/// window has gone idle long enough to hide itself, even while pinned.
+lazy_static::lazy_static! {
+    static ref LAST_ACTIVITY: Mutex<Instant> = Mutex::new(Instant::now());
+}
+
+/// Reset the inactivity clock `auto_hide_after_seconds` counts against.
+pub(crate) fn touch_activity() {
+    *LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+}
+
+/// Emit `window-visibility-changed` so frontend features that care about the window's
+/// actual on-screen state (e.g. the focus-duck ramp, see `set_focus_duck`) react
+/// consistently regardless of which code path (tray, pin, auto-hide, focus loss) changed it.
+pub(crate) fn notify_window_visibility(app: &tauri::AppHandle, visible: bool) {
+    let _ = app.emit("window-visibility-changed", visible);
+}
+
+/// Whether the binding poller should ignore hardware input changes - a safety toggle (see
+/// `set_input_lock`) so resting a hand on a lever or button mid-flight doesn't touch any
+/// volume/mute state. Axis/button values still get read and displayed as normal; only the
+/// actual applying of bindings (`binding_poller`, and previously the frontend's own poll
+/// loop) respects this, which is why this lives as a simple flag rather than something
+/// `hardware_input::read_all_axes` itself needs to know about.
+static INPUT_LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Read the current input lock state without going through the `get_input_lock` command -
+/// for `binding_poller`, which isn't itself a command and so has no `tauri::State`/IPC path
+/// to call one.
+pub(crate) fn input_lock_engaged() -> bool {
+    INPUT_LOCKED.load(Ordering::SeqCst)
+}
+
+/// Fill `settings::Settings::tray_tooltip_template`'s placeholders from current state:
+/// `{apps}` and `{controllers}` are live counts (audio sessions and input devices), while
+/// `{profile}` and `{sim}` resolve to fixed fallback text since ClearComms has no per-aircraft
+/// binding profiles or SimConnect/SimVar integration yet - same honesty as
+/// `show_main_window_for_sim_state`'s doc comment about the latter. Set via
+/// `set_tray_tooltip_template`/`set_tray_tooltip_interval`.
+fn format_tray_tooltip(app: &tauri::AppHandle) -> String {
+    let apps = {
+        let lock = audio_management::lock_audio_manager(&app.state::<audio_management::AudioManagerState>());
+        lock.as_ref().map(|manager| manager.cached_sessions().len()).unwrap_or(0)
+    };
+    let controllers = hardware_input::list_devices(app).map(|devices| devices.len()).unwrap_or(0);
+
+    settings::get().tray_tooltip_template
+        .replace("{apps}", &apps.to_string())
+        .replace("{controllers}", &controllers.to_string())
+        .replace("{profile}", "default")
+        .replace("{sim}", "not connected")
+}
+
+/// Tooltip text for the tray icon: the rendered `tray_tooltip_template` plus the input lock
+/// state - see `update_tray_tooltip`.
+fn tray_tooltip_text(app: &tauri::AppHandle) -> String {
+    let mut text = format_tray_tooltip(app);
+    if INPUT_LOCKED.load(Ordering::SeqCst) {
+        text.push_str(" (input locked)");
+    }
+    text
+}
+
+/// Refresh the tray icon's tooltip to reflect current state. Safe to call even if the tray
+/// hasn't been built yet (e.g. before `setup` finishes) or has already been torn down - just
+/// a no-op in that case.
+fn update_tray_tooltip(app: &tauri::AppHandle) {
+    if let Some(tray) = app.tray_by_id(TRAY_ICON_ID) {
+        let _ = tray.set_tooltip(Some(tray_tooltip_text(app)));
+    }
+}
+
+/// Persist the tray tooltip's template - see `settings::Settings::tray_tooltip_template` for
+/// the supported placeholders. Refreshes the tooltip immediately so a change is visible
+/// without waiting for the next periodic tick.
+#[tauri::command]
+fn set_tray_tooltip_template(app: tauri::AppHandle, template: String) -> Result<(), String> {
+    settings::update(|s| s.tray_tooltip_template = template);
+    update_tray_tooltip(&app);
+    Ok(())
+}
+
+/// Persist how often the tray tooltip re-renders itself from current state, in milliseconds -
+/// see `settings::Settings::tray_tooltip_interval_ms`.
+#[tauri::command]
+fn set_tray_tooltip_interval(interval_ms: u64) -> Result<(), String> {
+    settings::update(|s| s.tray_tooltip_interval_ms = interval_ms);
+    Ok(())
+}
+
+/// Engage or release the input lock: while engaged, the background `binding_poller` skips
+/// applying any axis/button mapping (no volume/mute changes) but keeps reading and displaying
+/// live hardware values, so nothing changes if a lever gets bumped while the user isn't
+/// actively adjusting it. Updates the tray tooltip and notifies the frontend via
+/// `"input-lock-changed"` so every UI surface (lock indicator, bound button) stays in sync
+/// regardless of what triggered the change. Factored out of the `set_input_lock` command so
+/// `binding_poller`'s "toggle_input_lock" button action can call it directly from its own
+/// background thread, the same way it calls `send_keystroke_impl` rather than `send_keystroke`.
+pub(crate) fn set_input_lock_impl(app: &tauri::AppHandle, locked: bool) {
+    INPUT_LOCKED.store(locked, Ordering::SeqCst);
+    update_tray_tooltip(app);
+    let _ = app.emit("input-lock-changed", locked);
+}
+
+#[tauri::command]
+fn set_input_lock(app: tauri::AppHandle, locked: bool) -> Result<(), String> {
+    set_input_lock_impl(&app, locked);
+    Ok(())
+}
+
+/// Get the current input lock state - see `set_input_lock`.
+#[tauri::command]
+fn get_input_lock() -> bool {
+    INPUT_LOCKED.load(Ordering::SeqCst)
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Theme Detection (Windows)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -182,19 +340,16 @@ fn load_theme_appropriate_icon() -> Image<'static> {
 /// # Returns
 /// Window width in logical pixels (before DPI scaling)
 fn calculate_window_width(session_count: usize) -> u32 {
-    if session_count == 0 {
-        let measurements = LAYOUT_MEASUREMENTS.lock().unwrap();
-        return measurements.base_width;
-    }
-    
     let measurements = LAYOUT_MEASUREMENTS.lock().unwrap();
-    let increment = measurements.channel_width + measurements.channel_gap;
-    
-    if session_count == 1 {
+
+    let raw_width = if session_count == 0 || session_count == 1 {
         measurements.base_width
     } else {
+        let increment = measurements.channel_width + measurements.channel_gap;
         measurements.base_width + (increment * (session_count - 1) as u32)
-    }
+    };
+
+    raw_width.clamp(measurements.min_width, measurements.max_width)
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -227,10 +382,44 @@ fn update_layout_measurements(
     tracing::debug!("[Layout] Updated measurements: channel={}px, gap={}px, base={}px",
              channel_width, channel_gap, base_width);
     
-    Ok(format!("Layout measurements updated: channel={}px, gap={}px, base={}px", 
+    Ok(format!("Layout measurements updated: channel={}px, gap={}px, base={}px",
                channel_width, channel_gap, base_width))
 }
 
+/// Persist a user-configured channel layout and apply it immediately, rather than waiting for
+/// the frontend's own DPI-measured `update_layout_measurements` call. Unlike that command, this
+/// one is saved to `settings` so a wider custom theme's sizing survives a restart, and it's the
+/// only way to set `min_width`/`max_width` - there's no frontend measurement for those, since
+/// they're a user preference rather than anything rendered.
+///
+/// # Arguments
+/// * `base` - Base window width for a single channel (logical pixels)
+/// * `per_channel` - Width of one channel strip (logical pixels); each channel beyond the first
+///   adds this plus the channel gap
+/// * `min` - Floor the calculated width is clamped to
+/// * `max` - Ceiling the calculated width is clamped to
+#[tauri::command]
+fn set_channel_layout(base: u32, per_channel: u32, min: u32, max: u32) -> Result<(), String> {
+    if min > max {
+        return Err(format!("min_width ({}) cannot exceed max_width ({})", min, max));
+    }
+
+    settings::update(|s| {
+        s.layout_base_width = base;
+        s.layout_channel_width = per_channel;
+        s.layout_min_width = min;
+        s.layout_max_width = max;
+    });
+
+    let mut measurements = LAYOUT_MEASUREMENTS.lock().map_err(|e| format!("Failed to lock measurements: {}", e))?;
+    measurements.base_width = base;
+    measurements.channel_width = per_channel;
+    measurements.min_width = min;
+    measurements.max_width = max;
+
+    Ok(())
+}
+
 /// Resize the main window to accommodate the number of audio channels.
 ///
 /// Calculates the appropriate width based on the number of bound audio sessions
@@ -305,14 +494,9 @@ fn animate_window_resize(window: tauri::WebviewWindow, start_width: u32, target_
             start_width - ((start_width - target_width) as f64 * eased_progress) as u32
         };
         
-        // Set window size using physical pixels
-        let _ = window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
-            width: current_width,
-            height: physical_window_height,
-        }));
-        
-        // Reposition window to stay anchored to bottom-right
-        position_window_bottom_right(&window);
+        // Move and resize together so the window doesn't flash at the old position with
+        // the new size for a frame before snapping to the anchored spot.
+        window_utils::set_bounds_anchored(&window, current_width as i32, physical_window_height as i32);
         
         // Check if animation is complete
         if progress >= 1.0 {
@@ -329,8 +513,11 @@ fn animate_window_resize(window: tauri::WebviewWindow, start_width: u32, target_
 fn show_main_window(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("main") {
         position_window_bottom_right(&window);
+        window_utils::apply_window_opacity(&window, settings::get().window_opacity);
         let _ = window.show();
         let _ = window.set_focus();
+        touch_activity();
+        notify_window_visibility(&app, true);
         Ok(())
     } else {
         Err("Main window not found".to_string())
@@ -342,6 +529,31 @@ fn show_main_window(app: tauri::AppHandle) -> Result<(), String> {
 fn hide_main_window(app: tauri::AppHandle) -> Result<(), String> {
     if let Some(window) = app.get_webview_window("main") {
         let _ = window.hide();
+        notify_window_visibility(&app, false);
+        Ok(())
+    } else {
+        Err("Main window not found".to_string())
+    }
+}
+
+/// Helper function: set the window's always-on-top ("pin") state, leaving visibility and
+/// focus untouched, and emit `window-pin-changed` so other UI (the tray menu, etc.) stays
+/// in sync with whoever changed it. Also persists the state so `setup` can restore it on the
+/// next launch - see the `always_on_top` re-apply there.
+pub fn perform_set_pin(app: &tauri::AppHandle, window: &tauri::WebviewWindow, pinned: bool) {
+    let _ = window.set_always_on_top(pinned);
+    settings::update(|s| s.always_on_top = pinned);
+    if let Err(e) = app.emit("window-pin-changed", pinned) {
+        tracing::error!("[Window] Failed to emit pin state event: {}", e);
+    }
+}
+
+/// Set the main window's pin state directly, without the show/focus side effects of
+/// `toggle_pin_window`.
+#[tauri::command]
+fn set_pin(app: tauri::AppHandle, pinned: bool) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("main") {
+        perform_set_pin(&app, &window, pinned);
         Ok(())
     } else {
         Err("Main window not found".to_string())
@@ -350,14 +562,16 @@ fn hide_main_window(app: tauri::AppHandle) -> Result<(), String> {
 
 /// Helper function: Perform the actual pin toggle operation
 /// Returns the new pin state after toggling
-pub fn perform_pin_toggle(window: &tauri::WebviewWindow) -> Result<bool, String> {
+pub fn perform_pin_toggle(app: &tauri::AppHandle, window: &tauri::WebviewWindow) -> Result<bool, String> {
     position_window_bottom_right(window);
+    window_utils::apply_window_opacity(window, settings::get().window_opacity);
     let _ = window.show();
     let _ = window.set_focus();
-    
-    let current_state = window.is_always_on_top().unwrap_or(false);
-    let new_state = !current_state;
-    let _ = window.set_always_on_top(new_state);
+    touch_activity();
+    notify_window_visibility(app, true);
+
+    let new_state = !window.is_always_on_top().unwrap_or(false);
+    perform_set_pin(app, window, new_state);
     Ok(new_state)
 }
 
@@ -366,7 +580,7 @@ pub fn perform_pin_toggle(window: &tauri::WebviewWindow) -> Result<bool, String>
 #[tauri::command]
 fn toggle_pin_window(app: tauri::AppHandle) -> Result<bool, String> {
     if let Some(window) = app.get_webview_window("main") {
-        perform_pin_toggle(&window)
+        perform_pin_toggle(&app, &window)
     } else {
         Err("Main window not found".to_string())
     }
@@ -411,10 +625,144 @@ async fn restart_application(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Drop the audio and input managers (see `audio_management::shutdown` and
+/// `hardware_input::shutdown`) before exiting, so we don't rely on `std::process::exit`
+/// skipping `Drop` (which can
+/// occasionally contribute to a slow/hung exit on some systems).
+pub fn shutdown_and_exit(app: &tauri::AppHandle) -> ! {
+    tracing::info!("[Shutdown] Releasing audio and input managers...");
+    audio_management::shutdown(app);
+    hardware_input::shutdown(app);
+    std::process::exit(0);
+}
+
 /// Quit the application
 #[tauri::command]
-fn quit_application() {
-    std::process::exit(0);
+fn quit_application(app: tauri::AppHandle) {
+    shutdown_and_exit(&app);
+}
+
+/// Enable or disable widget mode: a persistent always-on-top compact strip that
+/// doesn't steal focus on show and isn't hidden by focus loss, for use on a
+/// secondary touchscreen. Composes the existing pin + hide-on-blur + position
+/// behaviour into one toggle rather than requiring three separate settings.
+#[tauri::command]
+fn set_widget_mode(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(|s| s.widget_mode = enabled);
+
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+
+    if enabled {
+        if let Some((x, y)) = settings::get().widget_position {
+            let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
+        } else {
+            position_window_bottom_right(&window);
+        }
+        let _ = window.set_always_on_top(true);
+        window_utils::apply_window_opacity(&window, settings::get().window_opacity);
+        // Show without focus so widget mode never steals focus from the sim.
+        let _ = window.show();
+        touch_activity();
+        notify_window_visibility(&app, true);
+    }
+
+    Ok(())
+}
+
+/// Explicit activity signal from the frontend (e.g. mouse movement over the window),
+/// resetting the inactivity clock the same way a show/focus command does.
+#[tauri::command]
+fn report_window_activity() {
+    touch_activity();
+}
+
+/// Configure the inactivity auto-hide timer: the main window hides itself (even while
+/// pinned) after this many seconds without a show/focus command or reported frontend
+/// activity. `None` disables the behaviour, which is also the default.
+#[tauri::command]
+fn set_auto_hide_after_seconds(seconds: Option<u32>) -> Result<(), String> {
+    settings::update(|s| s.auto_hide_after_seconds = seconds);
+    Ok(())
+}
+
+/// Re-arm first-run onboarding so it fires again on the next launch - see the
+/// `settings::first_run` doc comment and `setup`'s first-run block.
+#[tauri::command]
+fn reset_first_run() -> Result<(), String> {
+    settings::update(|s| s.first_run = true);
+    Ok(())
+}
+
+/// Persist whether "focus follows sim" is allowed to show the window on its own.
+/// See `show_main_window_for_sim_state` for the explicit show this enables.
+#[tauri::command]
+fn set_show_on_pause(enabled: bool) -> Result<(), String> {
+    settings::update(|s| s.show_on_pause = enabled);
+    Ok(())
+}
+
+/// Explicit show request driven by external sim state (e.g. a SimVar/pause-state
+/// poller), gated on `show_on_pause`. No-op if the setting is off, so a caller can
+/// fire this unconditionally without checking the setting itself first.
+///
+/// This is deliberately separate from `show_main_window`: the focus-loss hide in
+/// `.on_window_event` only reacts to the window losing focus, so a plain `show()`
+/// here never fights it. There's no SimConnect/SimVar integration wired up in this
+/// codebase yet - this command is the hook such a poller would call.
+#[tauri::command]
+fn show_main_window_for_sim_state(app: tauri::AppHandle, paused: bool) -> Result<(), String> {
+    if !paused || !settings::get().show_on_pause {
+        return Ok(());
+    }
+
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    position_window_bottom_right(&window);
+    window_utils::apply_window_opacity(&window, settings::get().window_opacity);
+    let _ = window.show();
+    touch_activity();
+    notify_window_visibility(&app, true);
+    Ok(())
+}
+
+/// Persist whether the frontend's peak-metering loop keeps polling while the main
+/// window is hidden - see `settings::Settings::meter_while_hidden`.
+#[tauri::command]
+fn set_meter_while_hidden(enabled: bool) -> Result<(), String> {
+    settings::update(|s| s.meter_while_hidden = enabled);
+    Ok(())
+}
+
+/// Fetch whether the peak-metering loop keeps polling while the main window is hidden.
+#[tauri::command]
+fn get_meter_while_hidden() -> Result<bool, String> {
+    Ok(settings::get().meter_while_hidden)
+}
+
+/// Configure the "focus duck" session: while the main window is shown, the frontend
+/// ramps this session's volume down to `duck_to` over `ms`, then back to whatever it
+/// was over `ms` again once the window hides. The ramp itself happens in the frontend
+/// (reusing `animateVolumeTo`, the same ramp used for mute/unmute) driven off the
+/// `window-visibility-changed` event this module already emits from every show/hide
+/// path; this command only persists which session and how.
+#[tauri::command]
+fn set_focus_duck(session_id: String, duck_to: f32, ms: u32) -> Result<(), String> {
+    let duck_to = duck_to.clamp(0.0, 1.0);
+    settings::update(|s| s.focus_duck = Some(settings::FocusDuckConfig { session_id, duck_to, ms }));
+    Ok(())
+}
+
+/// Disable the focus duck feature.
+#[tauri::command]
+fn clear_focus_duck() -> Result<(), String> {
+    settings::update(|s| s.focus_duck = None);
+    Ok(())
+}
+
+/// Fetch the current focus duck configuration, if any, for the frontend to apply on
+/// its `window-visibility-changed` listener.
+#[tauri::command]
+fn get_focus_duck() -> Result<Option<settings::FocusDuckConfig>, String> {
+    Ok(settings::get().focus_duck)
 }
 
 /// Open a URL in the default browser and bring it to the foreground
@@ -453,6 +801,180 @@ async fn open_url(url: String) -> Result<(), String> {
     }
 }
 
+/// Send a keystroke via `SendInput`, for translating a joystick button into a keyboard
+/// hotkey (e.g. push-to-talk for comms apps that only expose a global hotkey rather than an
+/// audio session). `down` selects key-down vs key-up so callers can hold a key across a
+/// button-held binding. Factored out of the `send_keystroke` command so `binding_poller` can
+/// call it directly without round-tripping through Tauri's IPC from its own background thread.
+#[cfg(target_os = "windows")]
+pub(crate) fn send_keystroke_impl(vk_codes: &[u16], down: bool) -> Result<(), String> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, VIRTUAL_KEY,
+    };
+
+    let inputs: Vec<INPUT> = vk_codes
+        .iter()
+        .map(|&vk| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: VIRTUAL_KEY(vk),
+                    wScan: 0,
+                    dwFlags: if down { Default::default() } else { KEYEVENTF_KEYUP },
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        })
+        .collect();
+
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+
+    if sent as usize == inputs.len() {
+        Ok(())
+    } else {
+        Err("SendInput failed to deliver all keystrokes".to_string())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn send_keystroke_impl(_vk_codes: &[u16], _down: bool) -> Result<(), String> {
+    Err("Keystroke injection only supported on Windows".to_string())
+}
+
+#[tauri::command]
+fn send_keystroke(vk_codes: Vec<u16>, down: bool) -> Result<(), String> {
+    send_keystroke_impl(&vk_codes, down)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Binding Import
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A single successfully-matched row from an imported bindings CSV.
+#[derive(Debug, Clone, serde::Serialize)]
+struct ImportedBindingRow {
+    device_name: String,
+    device_handle: String,
+    axis_name: String,
+    process_name: String,
+    min: f32,
+    max: f32,
+    inverted: bool,
+}
+
+/// Result of an `import_bindings_csv` call: rows that matched a connected device, and rows
+/// that didn't (with a reason), so a partial import doesn't fail the whole file.
+#[derive(Debug, Clone, serde::Serialize)]
+struct CsvImportResult {
+    imported: Vec<ImportedBindingRow>,
+    unmatched_rows: Vec<String>,
+}
+
+/// Import axis bindings from a simple CSV (device name, axis, target process, min, max,
+/// invert), matching device names against currently enumerated `DeviceInfo`. Rows whose
+/// device can't be matched are reported back rather than failing the whole import, so
+/// simmers migrating a spreadsheet from another tool keep whatever does match.
+#[tauri::command]
+fn import_bindings_csv(app: tauri::AppHandle, path: String) -> Result<CsvImportResult, String> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read CSV '{}': {}", path, e))?;
+
+    let devices = hardware_input::list_devices(&app).unwrap_or_default();
+
+    let mut imported = Vec::new();
+    let mut unmatched_rows = Vec::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if fields.len() < 6 {
+            if line_number == 0 {
+                continue; // likely a header row with a different column count
+            }
+            unmatched_rows.push(format!("Line {}: expected 6 columns, got {}", line_number + 1, fields.len()));
+            continue;
+        }
+
+        let (device_name, axis_name, process_name, min_str, max_str, invert_str) =
+            (fields[0], fields[1], fields[2], fields[3], fields[4], fields[5]);
+
+        let (min, max) = match (min_str.parse::<f32>(), max_str.parse::<f32>()) {
+            (Ok(min), Ok(max)) => (min, max),
+            _ => {
+                if line_number == 0 {
+                    continue; // header row
+                }
+                unmatched_rows.push(format!("Line {}: non-numeric min/max ('{}', '{}')", line_number + 1, min_str, max_str));
+                continue;
+            }
+        };
+
+        let inverted = matches!(invert_str.to_lowercase().as_str(), "1" | "true" | "yes" | "y");
+
+        let matched_device = devices.iter().find(|d| {
+            d.name.eq_ignore_ascii_case(device_name) || d.to_display_string().eq_ignore_ascii_case(device_name)
+        });
+
+        match matched_device {
+            Some(device) => imported.push(ImportedBindingRow {
+                device_name: device_name.to_string(),
+                device_handle: device.id.to_string(),
+                axis_name: axis_name.to_string(),
+                process_name: process_name.to_string(),
+                min,
+                max,
+                inverted,
+            }),
+            None => unmatched_rows.push(format!("Line {}: no connected device matches \"{}\"", line_number + 1, device_name)),
+        }
+    }
+
+    tracing::info!(
+        "[Bindings] Imported {} row(s) from {}, {} unmatched",
+        imported.len(),
+        path,
+        unmatched_rows.len()
+    );
+
+    Ok(CsvImportResult { imported, unmatched_rows })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Settings Validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Check the settings file's per-device/per-process associations against currently connected
+/// devices and running sessions, reporting anything that's drifted out of sync (hardware
+/// unplugged, an app uninstalled) - see `Settings::validate` for what's actually compared.
+/// Combined with `Settings::load`'s corrupt-file backup and `Settings::save`'s atomic write,
+/// this is the "does the config still make sense" half of keeping the settings file resilient.
+#[tauri::command]
+fn validate_settings(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    // Settings are keyed by `device_identity_key` (vendor/product/name, no instance suffix) -
+    // see `hardware_input::set_axis_label` and friends - rather than `DeviceInfo::device_key`,
+    // which does carry a suffix for the second and later device sharing an identity.
+    let known_device_keys: std::collections::HashSet<String> = hardware_input::list_devices(&app)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| hardware_input::device_identity_key(d.vendor_id, d.product_id, &d.name))
+        .collect();
+
+    let known_process_names: std::collections::HashSet<String> = {
+        let state = app.state::<audio_management::AudioManagerState>();
+        let lock = audio_management::lock_audio_manager(&state);
+        lock.as_ref()
+            .map(|manager| manager.cached_sessions().values().map(|s| s.process_name.clone()).collect())
+            .unwrap_or_default()
+    };
+
+    Ok(settings::get().validate(&known_device_keys, &known_process_names))
+}
+
 fn main() {
     #[cfg(debug_assertions)]
     tracing_subscriber::fmt()
@@ -464,8 +986,27 @@ fn main() {
     let last_hidden_for_setup = last_hidden.clone();
     let last_hidden_for_events = last_hidden.clone();
 
+    // Tracks the most recent `Moved` event so the debounce thread spawned from
+    // it can tell whether it's still the latest one once its sleep elapses.
+    let last_moved: Arc<Mutex<Instant>> = Arc::new(Mutex::new(Instant::now()));
+    let last_moved_for_events = last_moved.clone();
+    let last_moved_for_auto_hide = last_moved.clone();
+
     tauri::Builder::default()
         .setup(move |app| {
+            // Managed slots for the audio/input managers. Both start empty; the frontend
+            // explicitly initialises them (`init_audio_manager`/`init_direct_input`) once
+            // the webview is ready, so commands keep returning the same "not initialised"
+            // error as before if called too early - only the storage moved off a static.
+            // On a brand-new install (see `settings::first_run`) they're also initialised
+            // right here, so something shows even if the frontend never gets that far.
+            app.manage(audio_management::AudioManagerState::new(None));
+            app.manage(hardware_input::InputManagerState::new(None));
+            app.manage(control_server::ControlServerState::new(None));
+            app.manage(midi_input::MidiInputManagerState::new(None));
+            app.manage(discord_presence::DiscordPresenceManagerState::new(None));
+            app.manage(binding_poller::BindingCacheState::default());
+
             // Get main window and position it
             if let Some(window) = app.get_webview_window("main") {
                 // Apply Windows Acrylic effect and rounded corners
@@ -501,11 +1042,28 @@ fn main() {
                         );
                     }
                 }
+                // Seed the scale factor baseline so the first real ScaleFactorChanged event
+                // diffs against the monitor we actually started on, not an assumed 100%.
+                if let Ok(initial_scale_factor) = window.scale_factor() {
+                    *LAST_SCALE_FACTOR.lock().unwrap_or_else(|e| e.into_inner()) = initial_scale_factor;
+                }
+
                 // Position window in bottom-right corner
                 position_window_bottom_right(&window);
-                
+
+                // Applied here too, not just on show - the acrylic/rounded-corner setup
+                // above also touches the extended window style.
+                window_utils::apply_window_opacity(&window, settings::get().window_opacity);
+
                 // Don't show window on startup (starts in tray)
                 let _ = window.hide();
+
+                // Restore the always-on-top state from last session - `set_always_on_top`
+                // only touches the window style, not visibility, so this is safe to apply
+                // while still hidden above rather than waiting for the first show.
+                if settings::get().always_on_top {
+                    let _ = window.set_always_on_top(true);
+                }
             }
             
             // Build tray icon with theme-appropriate icon
@@ -521,6 +1079,7 @@ fn main() {
                         tauri::tray::TrayIconEvent::Click {
                             button: MouseButton::Left,
                             button_state: MouseButtonState::Up,
+                            position,
                             ..
                         } => {
                             if let Some(window) = app.get_webview_window("main") {
@@ -539,15 +1098,30 @@ fn main() {
                                     tracing::debug!("[Tray] Hiding window");
                                     let _ = window.set_always_on_top(false);
                                     let _ = window.hide();
+                                    notify_window_visibility(app, false);
                                 } else if just_hidden {
                                     // Window was just hidden by this click's focus loss - do nothing
                                     tracing::debug!("[Tray] Ignoring (just hidden by focus loss)");
                                 } else {
-                                    // Window is hidden and wasn't just hidden - show it
-                                    tracing::debug!("[Tray] Showing window");
-                                    position_window_bottom_right(&window);
-                                    let _ = window.show();
-                                    let _ = window.set_focus();
+                                    // Window is hidden and wasn't just hidden - show it. If a
+                                    // full-screen exclusive app (sim, game) is foreground, show
+                                    // without activating so the tray click doesn't minimize it.
+                                    if settings::get().position_mode == window_utils::PositionMode::TrayRelative {
+                                        window_utils::position_window_near_point(&window, position.x as i32, position.y as i32);
+                                    } else {
+                                        position_window_bottom_right(&window);
+                                    }
+                                    window_utils::apply_window_opacity(&window, settings::get().window_opacity);
+                                    if window_utils::foreground_window_is_fullscreen(&window) {
+                                        tracing::debug!("[Tray] Showing window above full-screen foreground app, without activating");
+                                        window_utils::show_without_activating(&window);
+                                    } else {
+                                        tracing::debug!("[Tray] Showing window");
+                                        let _ = window.show();
+                                        let _ = window.set_focus();
+                                    }
+                                    touch_activity();
+                                    notify_window_visibility(app, true);
                                 }
                             }
                         }
@@ -602,15 +1176,153 @@ fn main() {
                     }
                 }
             });
-            
+
+            // Periodically re-render the tray tooltip from `format_tray_tooltip` so it reflects
+            // live session/device counts rather than only updating on an input-lock change -
+            // see `settings::Settings::tray_tooltip_template`. Polls on a short fixed interval
+            // and checks elapsed time against the configurable one, the same "sleep short,
+            // re-read the setting" shape `binding_poller::spawn` uses for its own refresh.
+            let app_handle_for_tooltip = app.handle().clone();
+            std::thread::spawn(move || {
+                let mut last_refresh = Instant::now();
+                loop {
+                    std::thread::sleep(Duration::from_millis(250));
+
+                    let interval = Duration::from_millis(settings::get().tray_tooltip_interval_ms);
+                    if last_refresh.elapsed() < interval {
+                        continue;
+                    }
+                    last_refresh = Instant::now();
+                    update_tray_tooltip(&app_handle_for_tooltip);
+                }
+            });
+
+            // Apply axis/button bindings from this background thread rather than relying on
+            // the frontend's own poll loop - see the `binding_poller` module doc comment for
+            // why that matters while the window is hidden.
+            binding_poller::spawn(app.handle().clone());
+
+            // Inactivity auto-hide: periodically checks elapsed idle time against
+            // `auto_hide_after_seconds` and, if it's been exceeded, hides the window even
+            // while pinned. Disabled (the default) whenever the setting is `None`.
+            let app_handle_for_auto_hide = app.handle().clone();
+            std::thread::spawn(move || {
+                loop {
+                    std::thread::sleep(Duration::from_millis(AUTO_HIDE_POLL_INTERVAL_MS));
+
+                    let Some(threshold_secs) = settings::get().auto_hide_after_seconds else {
+                        continue;
+                    };
+                    if threshold_secs == 0 {
+                        continue;
+                    }
+
+                    let idle_for = LAST_ACTIVITY.lock().unwrap_or_else(|e| e.into_inner()).elapsed();
+                    if idle_for < Duration::from_secs(threshold_secs as u64) {
+                        continue;
+                    }
+
+                    // Don't fire mid-drag - a `Moved` event still settling isn't inactivity.
+                    let dragging = last_moved_for_auto_hide.lock()
+                        .map(|last| last.elapsed() < Duration::from_millis(DRAG_SETTLE_MS))
+                        .unwrap_or(false);
+                    if dragging {
+                        continue;
+                    }
+
+                    if let Some(window) = app_handle_for_auto_hide.get_webview_window("main") {
+                        if window.is_visible().unwrap_or(false) {
+                            tracing::debug!("[Window] Auto-hiding after {}s of inactivity", threshold_secs);
+                            let _ = window.hide();
+                            notify_window_visibility(&app_handle_for_auto_hide, false);
+                        }
+                    }
+                }
+            });
+
+            // First-run onboarding: auto-init both managers and tell the frontend, so a
+            // brand-new install shows devices/sessions immediately rather than a blank
+            // window the user has to know to go initialise themselves. Subsequent
+            // launches skip this - the frontend's own `autoInitialise` handles them.
+            if settings::get().first_run {
+                let _ = hardware_input::init_direct_input(app.state::<hardware_input::InputManagerState>(), None);
+                let _ = audio_management::init_audio_manager(app.state::<audio_management::AudioManagerState>());
+                let _ = app.emit("first-run", ());
+                settings::update(|s| s.first_run = false);
+            }
+
             Ok(())
         })
         .on_window_event(move |window, event| {
             match event {
                 tauri::WindowEvent::CloseRequested { api, .. } => {
-                    // Prevent window from closing, hide it instead
-                    let _ = window.hide();
+                    // Default behaviour (and the app's long-standing one) is to hide rather
+                    // than exit - quitting stays reachable via the tray/menu. `Quit` and
+                    // `Ask` are opt-in via `window_utils::set_close_action`.
                     api.prevent_close();
+                    match settings::get().close_action {
+                        window_utils::CloseAction::HideToTray => {
+                            let _ = window.hide();
+                            notify_window_visibility(window.app_handle(), false);
+                        }
+                        window_utils::CloseAction::Quit => {
+                            shutdown_and_exit(window.app_handle());
+                        }
+                        window_utils::CloseAction::Ask => {
+                            let _ = window.emit("close-requested", ());
+                        }
+                    }
+                }
+                tauri::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                    // Monitor moves / display-scaling changes land here. The window's logical
+                    // size is unaffected, but its physical size and on-screen position (computed
+                    // in physical pixels) are now stale, so recompute both against the new factor.
+                    let old_scale_factor = {
+                        let mut last = LAST_SCALE_FACTOR.lock().unwrap_or_else(|e| e.into_inner());
+                        let old = *last;
+                        *last = *scale_factor;
+                        old
+                    };
+
+                    tracing::info!("[Window] Scale factor changed: {} -> {}", old_scale_factor, scale_factor);
+
+                    if old_scale_factor > 0.0 {
+                        if let Ok(current_physical) = window.outer_size() {
+                            let logical_width = current_physical.width as f64 / old_scale_factor;
+                            let logical_height = current_physical.height as f64 / old_scale_factor;
+
+                            let new_physical = tauri::PhysicalSize {
+                                width: (logical_width * scale_factor) as u32,
+                                height: (logical_height * scale_factor) as u32,
+                            };
+
+                            let _ = window.set_size(tauri::Size::Physical(new_physical));
+                        }
+                    }
+
+                    window_utils::position_window_bottom_right(window);
+                }
+                tauri::WindowEvent::Moved(_) => {
+                    // No native "drag end" event exists, so debounce: stamp this
+                    // move, wait a short settle period, then only snap if no
+                    // later `Moved` superseded it (i.e. the drag has stopped).
+                    let this_moved = Instant::now();
+                    if let Ok(mut last) = last_moved_for_events.lock() {
+                        *last = this_moved;
+                    }
+
+                    let last_moved_for_thread = last_moved_for_events.clone();
+                    let window_for_thread = window.clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(Duration::from_millis(DRAG_SETTLE_MS));
+                        let is_latest = last_moved_for_thread
+                            .lock()
+                            .map(|last| *last == this_moved)
+                            .unwrap_or(false);
+                        if is_latest {
+                            window_utils::snap_to_nearest_corner(&window_for_thread);
+                        }
+                    });
                 }
                 tauri::WindowEvent::Focused(focused) => {
                     let is_pinned = window.is_always_on_top().unwrap_or(false);
@@ -626,8 +1338,9 @@ fn main() {
                             }));
                             let _ = window.set_size(tauri::Size::Physical(size));
                         }
-                    } else if !focused {
+                    } else if !focused && !settings::get().widget_mode {
                         // Window not pinned and lost focus - hide it and record timestamp
+                        // (skipped entirely in widget mode, which stays visible regardless)
                         tracing::debug!("[Window] Lost focus, hiding");
                         // Only update last_hidden if the window was actually visible
                         if let Ok(is_visible) = window.is_visible() {
@@ -638,6 +1351,7 @@ fn main() {
                             }
                         }
                         let _ = window.hide();
+                        notify_window_visibility(window.app_handle(), false);
                     }
                 }
                 _ => {}
@@ -647,27 +1361,131 @@ fn main() {
             hardware_input::init_direct_input,
             hardware_input::get_direct_input_status,
             hardware_input::enumerate_input_devices,
+            hardware_input::get_input_devices,
+            hardware_input::get_input_debug,
             hardware_input::get_all_axis_values,
             hardware_input::cleanup_input_manager,
+            hardware_input::start_axis_graph,
+            hardware_input::stop_axis_graph,
+            hardware_input::capture_next_input,
+            hardware_input::get_axis_range_report,
+            hardware_input::set_axis_label,
+            hardware_input::set_axis_rotary,
+            hardware_input::reset_axis_calibration,
+            hardware_input::reset_device_calibration,
+            hardware_input::set_device_polling,
+            hardware_input::get_disabled_devices,
+            hardware_input::get_default_curve,
+            hardware_input::set_default_curve,
+            midi_input::list_midi_ports,
+            midi_input::init_midi_input,
+            midi_input::get_all_midi_axis_values,
+            midi_input::cleanup_midi_input,
+            discord_presence::init_discord_presence,
+            discord_presence::get_discord_voice_label,
+            discord_presence::cleanup_discord_presence,
+            binding_poller::sync_axis_mappings,
+            binding_poller::sync_button_mappings,
+            binding_poller::save_binding_template,
+            binding_poller::apply_binding_template,
+            binding_poller::delete_binding_template,
+            binding_poller::list_binding_templates,
+            binding_poller::measure_binding_latency,
             audio_management::init_audio_manager,
             audio_management::get_audio_sessions,
+            audio_management::get_active_sessions,
+            audio_management::get_combined_sessions,
+            audio_management::get_session_peak_history,
+            audio_management::diff_sessions,
             audio_management::set_session_volume,
+            audio_management::adjust_session_volume,
+            audio_management::set_session_volume_percent,
+            audio_management::set_session_gain,
+            audio_management::map_simvar_to_session,
+            audio_management::set_volume_by_process_tree,
+            audio_management::scale_all_volumes,
+            audio_management::restore_all_volumes,
             audio_management::set_session_mute,
+            audio_management::get_sessions_by_process,
+            audio_management::clear_clearcomms_mutes,
+            audio_management::restore_windows_state,
+            audio_management::set_session_tag,
+            audio_management::get_session_tags,
+            audio_management::set_session_volume_cap,
+            audio_management::get_session_volume_caps,
+            audio_management::pin_session_to_device,
+            audio_management::unpin_session_device,
+            audio_management::get_device_pins,
+            audio_management::get_device_by_name,
+            audio_management::reattach_pinned_sessions,
+            set_tray_tooltip_template,
+            set_tray_tooltip_interval,
+            lvar_input::get_aircraft_lvar_map,
+            lvar_input::set_aircraft_lvar_override,
+            mute_key_hook::start_mute_key_mirror,
+            mute_key_hook::stop_mute_key_mirror,
+            audio_management::activate_priority_mode,
+            audio_management::deactivate_priority_mode,
             audio_management::check_default_device_changed,
+            audio_management::get_default_device,
+            audio_management::list_audio_devices,
+            audio_management::list_all_devices,
             audio_management::cleanup_audio_manager,
             audio_management::get_system_volume,
             audio_management::get_system_mute,
             audio_management::set_system_volume,
             audio_management::set_system_mute,
+            audio_management::set_session_sort,
+            audio_management::set_session_order,
+            audio_management::get_session_order,
+            audio_management::play_test_tone,
+            audio_management::duplicate_session_to_device,
+            audio_management::stop_session_duplication,
+            audio_management::start_monitor_session,
+            audio_management::stop_monitor_session,
+            audio_management::get_audio_diagnostics,
+            audio_management::debug_dump_sessions,
+            audio_management::get_capture_features,
+            audio_management::set_capture_feature,
+            audio_management::set_exclusive_mode_allowed,
+            audio_management::get_exclusive_mode_allowed,
+            audio_management::get_capabilities,
             update_layout_measurements,
+            set_channel_layout,
             resize_window_to_content,
             show_main_window,
             hide_main_window,
             toggle_pin_window,
+            set_pin,
             is_window_pinned,
             restart_application,
             quit_application,
             open_url,
+            window_utils::set_window_padding,
+            window_utils::set_window_opacity,
+            window_utils::get_window_opacity,
+            window_utils::set_position_mode,
+            window_utils::get_position_mode,
+            window_utils::set_close_action,
+            window_utils::get_close_action,
+            set_widget_mode,
+            set_show_on_pause,
+            set_meter_while_hidden,
+            get_meter_while_hidden,
+            report_window_activity,
+            reset_first_run,
+            set_auto_hide_after_seconds,
+            show_main_window_for_sim_state,
+            set_focus_duck,
+            clear_focus_duck,
+            get_focus_duck,
+            send_keystroke,
+            import_bindings_csv,
+            validate_settings,
+            set_input_lock,
+            get_input_lock,
+            control_server::set_control_server,
+            control_server::get_control_server_status,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
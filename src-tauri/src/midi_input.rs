@@ -0,0 +1,209 @@
+//! MIDI input
+//!
+//! Optional input backend, feature-gated behind the `midi` Cargo feature, for a MIDI fader
+//! board or similar controller. A connected port is exposed as an ordinary `AxisData` (see
+//! `hardware_input`) with one axis per Control Change number, normalised 0-127 -> 0.0-1.0 the
+//! same way a joystick axis is normalised - so the frontend's existing binding/curve/smoothing
+//! poller doesn't need to know MIDI exists at all, it just merges `get_all_midi_axis_values`
+//! into the same axis-data array it already matches bindings against.
+//!
+//! Not part of `hardware_input::InputManagerState`: a MIDI port isn't a joystick, and keeping it
+//! in its own slot means the `midi` feature being off (or the port disappearing) can't affect
+//! joystick input at all.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Serialize, Deserialize};
+
+use crate::hardware_input::AxisData;
+
+/// Highest possible Control Change value, for normalising to the 0.0-1.0 range the rest of the
+/// binding pipeline expects.
+const MAX_CC_VALUE: f32 = 127.0;
+
+/// A discovered MIDI input port, returned by `list_midi_ports` for a picker UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MidiPortInfo {
+    pub id: usize,
+    pub name: String,
+}
+
+/// Build the stable `device_key`/`device_handle` for a connected MIDI port. Unlike
+/// `hardware_input::device_identity_key`, there's no vendor/product ID to key against - a
+/// port's index can shift if other MIDI devices are connected or disconnected in between, which
+/// is an accepted limitation for this first pass rather than something worth a full
+/// re-binding UX for.
+fn midi_device_key(port_id: usize, name: &str) -> String {
+    format!("midi:{}:{}", port_id, name)
+}
+
+#[cfg(feature = "midi")]
+mod backend {
+    use super::*;
+    use midir::{MidiInput, MidiInputConnection};
+    use std::sync::Arc;
+
+    /// Holds the live connection to one MIDI input port plus the latest Control Change value
+    /// seen for each CC number, updated from `midir`'s background callback thread.
+    pub struct MidiInputManager {
+        device_key: String,
+        device_name: String,
+        cc_values: Arc<Mutex<HashMap<u8, f32>>>,
+        _connection: MidiInputConnection<()>,
+    }
+
+    impl MidiInputManager {
+        pub fn list_ports() -> Result<Vec<MidiPortInfo>, String> {
+            let midi_in = MidiInput::new("ClearComms MIDI probe")
+                .map_err(|e| format!("Failed to initialise MIDI input: {}", e))?;
+
+            Ok(midi_in.ports().iter().enumerate().map(|(id, port)| {
+                let name = midi_in.port_name(port).unwrap_or_else(|_| format!("MIDI Port {}", id));
+                MidiPortInfo { id, name }
+            }).collect())
+        }
+
+        pub fn connect(port_id: usize) -> Result<Self, String> {
+            let midi_in = MidiInput::new("ClearComms MIDI input")
+                .map_err(|e| format!("Failed to initialise MIDI input: {}", e))?;
+
+            let ports = midi_in.ports();
+            let port = ports.get(port_id)
+                .ok_or_else(|| format!("No MIDI port at index {}", port_id))?;
+            let port_name = midi_in.port_name(port)
+                .unwrap_or_else(|_| format!("MIDI Port {}", port_id));
+            let device_key = midi_device_key(port_id, &port_name);
+
+            let cc_values: Arc<Mutex<HashMap<u8, f32>>> = Arc::new(Mutex::new(HashMap::new()));
+            let callback_values = cc_values.clone();
+
+            let connection = midi_in
+                .connect(port, "clearcomms-midi-cc", move |_timestamp_us, message, _| {
+                    // Control Change: status nibble 0xB, data1 = CC number, data2 = value (0-127).
+                    if message.len() == 3 && (message[0] & 0xF0) == 0xB0 {
+                        let cc = message[1];
+                        let value = message[2] as f32 / MAX_CC_VALUE;
+                        if let Ok(mut values) = callback_values.lock() {
+                            values.insert(cc, value);
+                        }
+                    }
+                }, ())
+                .map_err(|e| format!("Failed to connect to MIDI port '{}': {}", port_name, e))?;
+
+            Ok(Self {
+                device_key,
+                device_name: port_name,
+                cc_values,
+                _connection: connection,
+            })
+        }
+
+        pub fn read_axis_data(&self) -> AxisData {
+            let axes = self.cc_values.lock()
+                .map(|values| values.iter().map(|(&cc, &value)| (format!("CC {}", cc), value)).collect())
+                .unwrap_or_default();
+
+            AxisData {
+                device_handle: self.device_key.clone(),
+                device_key: self.device_key.clone(),
+                device_name: self.device_name.clone(),
+                manufacturer: "MIDI".to_string(),
+                product_id: 0,
+                vendor_id: 0,
+                axes,
+                buttons: HashMap::new(),
+                axis_labels: HashMap::new(),
+                rotary_position: HashMap::new(),
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "midi"))]
+mod backend {
+    use super::*;
+
+    /// Stub used when the binary was built without the `midi` feature - every method reports
+    /// the same "not built in" error a `#[cfg(not(windows))]` stub reports for Windows-only
+    /// functionality, so callers don't need to know whether MIDI support compiled in at all.
+    pub struct MidiInputManager;
+
+    impl MidiInputManager {
+        pub fn list_ports() -> Result<Vec<MidiPortInfo>, String> {
+            Err("MIDI support was not built into this binary".to_string())
+        }
+
+        pub fn connect(_port_id: usize) -> Result<Self, String> {
+            Err("MIDI support was not built into this binary".to_string())
+        }
+
+        pub fn read_axis_data(&self) -> AxisData {
+            AxisData {
+                device_handle: String::new(),
+                device_key: String::new(),
+                device_name: String::new(),
+                manufacturer: String::new(),
+                product_id: 0,
+                vendor_id: 0,
+                axes: HashMap::new(),
+                buttons: HashMap::new(),
+                axis_labels: HashMap::new(),
+                rotary_position: HashMap::new(),
+            }
+        }
+    }
+}
+
+use backend::MidiInputManager;
+
+/// Tauri-managed slot for the MIDI input manager, installed via `app.manage(...)` in `main.rs`'s
+/// `setup` - mirrors `hardware_input::InputManagerState`, kept separate since a MIDI port isn't
+/// a joystick and the two backends shouldn't affect each other's lifecycle.
+pub type MidiInputManagerState = Mutex<Option<MidiInputManager>>;
+
+/// List available MIDI input ports for a picker UI. Errors (rather than returning an empty
+/// list) when the binary wasn't built with the `midi` feature, so the UI can tell "no MIDI
+/// ports plugged in" apart from "MIDI isn't supported here".
+#[tauri::command]
+pub fn list_midi_ports() -> Result<Vec<MidiPortInfo>, String> {
+    MidiInputManager::list_ports()
+}
+
+/// Connect to a MIDI input port by the index `list_midi_ports` reported, and start tracking its
+/// Control Change messages.
+#[tauri::command]
+pub fn init_midi_input(state: tauri::State<'_, MidiInputManagerState>, port_id: usize) -> Result<String, String> {
+    let manager = MidiInputManager::connect(port_id)?;
+    let device_name = manager.read_axis_data().device_name.clone();
+
+    let mut lock = state.lock().map_err(|e| format!("Failed to lock MIDI input mutex: {}", e))?;
+    *lock = Some(manager);
+
+    Ok(format!("MIDI input connected to '{}'", device_name))
+}
+
+/// Get the latest Control Change values from the connected MIDI port, shaped as an `AxisData`
+/// so the frontend's existing joystick binding poller can match against it unchanged - see the
+/// module doc comment. Returns an empty list (not an error) when no port is connected, the same
+/// way a poll with no joysticks plugged in just reports no devices.
+#[tauri::command]
+pub fn get_all_midi_axis_values(state: tauri::State<'_, MidiInputManagerState>) -> Result<Vec<AxisData>, String> {
+    let lock = state.lock().map_err(|e| format!("Failed to lock MIDI input mutex: {}", e))?;
+
+    Ok(match lock.as_ref() {
+        Some(manager) => vec![manager.read_axis_data()],
+        None => Vec::new(),
+    })
+}
+
+/// Disconnect from the current MIDI port, if any.
+#[tauri::command]
+pub fn cleanup_midi_input(state: tauri::State<'_, MidiInputManagerState>) -> Result<String, String> {
+    let mut lock = state.lock().map_err(|e| format!("Failed to lock MIDI input mutex: {}", e))?;
+
+    Ok(match lock.take() {
+        Some(_) => "MIDI input disconnected".to_string(),
+        None => "MIDI input not connected".to_string(),
+    })
+}
@@ -0,0 +1,147 @@
+//! Mute key mirror
+//!
+//! Optional input backend, feature-gated behind the `mute-key-hook` Cargo feature, that mirrors
+//! the system volume-mute key (the one a headset's hardware mute button - or any "F-key"/media
+//! keyboard mute key - sends via Windows' HID consumer-control driver) onto a chosen audio
+//! session's `set_session_mute`. Unlike `hardware_input`/`midi_input`, there's nothing to poll:
+//! the key only exists as an OS-wide event, so catching it needs a low-level keyboard hook
+//! (`WH_KEYBOARD_LL`) rather than a normal DirectInput/HID device read - and only while a
+//! mirror is actually running, since a system-wide keyboard hook is process-invasive enough
+//! that it shouldn't be installed just because the binary happened to be compiled with the
+//! feature on.
+//!
+//! Only `MirrorMode::Toggle` is implemented: the hook sees a single keydown per physical press
+//! of the mute key, the same discrete event whether the button itself is momentary or latching,
+//! so there's no "held" state to mirror as a true momentary mute - `start_mute_key_mirror`
+//! rejects `MirrorMode::Momentary` rather than silently behaving like `Toggle`.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// How a mirrored mute key press should map onto the target session's mute - see the module
+/// doc comment for why only `Toggle` is actually implemented today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorMode {
+    #[default]
+    Toggle,
+    Momentary,
+}
+
+/// Generation counter for the running mirror, the same "bump to stop" idiom
+/// `hardware_input::AXIS_GRAPH_GENERATION` uses - simpler than threading a cancellation channel
+/// through `start_mute_key_mirror`'s return value, which `#[tauri::command]` can't easily carry.
+static MUTE_KEY_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Set by the keyboard hook callback when it sees the mute key go down; cleared by the pump
+/// loop once it's acted on it. The callback itself only sets a flag rather than touching
+/// `AudioManager` directly, since a low-level hook callback runs on a system-wide chain other
+/// processes' hooks also sit on and should return as fast as possible.
+static MUTE_KEY_PRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Toggle `target_process_name`'s cached session mute, if one is currently running. Silently
+/// does nothing if no session matches or the audio manager isn't initialised - a mute key
+/// press for an app that isn't open right now just has nothing to mirror onto.
+fn toggle_target_mute(app: &tauri::AppHandle, target_process_name: &str) {
+    let mut lock = crate::audio_management::lock_audio_manager(&app.state::<crate::audio_management::AudioManagerState>());
+    let Some(manager) = lock.as_mut() else { return };
+
+    let Some(session) = manager.cached_sessions().values().find(|s| s.process_name == target_process_name) else { return };
+    let session_id = session.session_id.clone();
+    let new_muted = !session.is_muted;
+
+    let _ = manager.set_session_mute(&session_id, new_muted);
+}
+
+#[cfg(feature = "mute-key-hook")]
+mod backend {
+    use super::*;
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::VK_VOLUME_MUTE;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, DispatchMessageW, PeekMessageW, SetWindowsHookExW, TranslateMessage,
+        UnhookWindowsHookEx, HC_ACTION, KBDLLHOOKSTRUCT, MSG, PM_REMOVE, WH_KEYBOARD_LL, WM_KEYDOWN,
+    };
+
+    /// Low-level keyboard hook procedure. Runs on the hooking thread's own message loop - see
+    /// `run` - for every key event system-wide, so it just flags a mute-key-down and returns;
+    /// the actual `set_session_mute` call happens back in `run`'s pump loop.
+    unsafe extern "system" fn low_level_keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code as u32 == HC_ACTION && wparam.0 as u32 == WM_KEYDOWN {
+            let kb = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+            if kb.vkCode == VK_VOLUME_MUTE.0 as u32 {
+                MUTE_KEY_PRESSED.store(true, Ordering::SeqCst);
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    /// Install the hook and pump its message queue until `generation` is superseded by a later
+    /// `start`/`stop` call. A low-level hook's callback only ever fires from a message loop on
+    /// the thread that installed it, so this polls with `PeekMessageW` (rather than blocking on
+    /// `GetMessageW`) specifically so the loop can also notice the generation bump and exit -
+    /// `UnhookWindowsHookEx` must run on this same thread.
+    pub fn start(app: tauri::AppHandle, target_process_name: String) -> Result<(), String> {
+        let generation = MUTE_KEY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+        std::thread::spawn(move || {
+            let hook = match unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(low_level_keyboard_proc), None, 0) } {
+                Ok(hook) => hook,
+                Err(e) => {
+                    tracing::error!("[MuteKeyMirror] Failed to install keyboard hook: {}", e);
+                    return;
+                }
+            };
+
+            loop {
+                if MUTE_KEY_GENERATION.load(Ordering::SeqCst) != generation {
+                    break;
+                }
+
+                let mut msg = MSG::default();
+                unsafe {
+                    while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                        let _ = TranslateMessage(&msg);
+                        DispatchMessageW(&msg);
+                    }
+                }
+
+                if MUTE_KEY_PRESSED.swap(false, Ordering::SeqCst) {
+                    toggle_target_mute(&app, &target_process_name);
+                }
+
+                std::thread::sleep(std::time::Duration::from_millis(15));
+            }
+
+            let _ = unsafe { UnhookWindowsHookEx(hook) };
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "mute-key-hook"))]
+mod backend {
+    pub fn start(_app: tauri::AppHandle, _target_process_name: String) -> Result<(), String> {
+        Err("Mute key mirroring was not built into this binary (requires the \"mute-key-hook\" feature)".to_string())
+    }
+}
+
+/// Start mirroring the system mute key onto `target_process_name`'s session mute. Replaces any
+/// mirror already running (only one target at a time - there's only one physical mute key to
+/// watch). See the module doc comment for why only `MirrorMode::Toggle` is accepted.
+#[tauri::command]
+pub fn start_mute_key_mirror(app: tauri::AppHandle, target_process_name: String, mode: Option<MirrorMode>) -> Result<(), String> {
+    if mode.unwrap_or_default() != MirrorMode::Toggle {
+        return Err("Only \"toggle\" mirroring is implemented - a keyboard media key reports a single press, not a held state".to_string());
+    }
+
+    backend::start(app, target_process_name)
+}
+
+/// Stop any running mute key mirror.
+#[tauri::command]
+pub fn stop_mute_key_mirror() -> Result<(), String> {
+    MUTE_KEY_GENERATION.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
@@ -9,7 +9,7 @@ use windows::Win32::{
 };
 
 #[cfg(windows)]
-use tauri::{Manager, Emitter};
+use tauri::Manager;
 
 #[cfg(windows)]
 use crate::window_utils::position_window_bottom_right;
@@ -95,6 +95,9 @@ pub fn show_native_context_menu(app: &tauri::AppHandle, x: i32, y: i32) -> Resul
                     position_window_bottom_right(&window);
                     let _ = window.show();
                     let _ = window.set_focus();
+                    crate::touch_activity();
+                    crate::window_utils::apply_window_opacity(&window, crate::settings::get().window_opacity);
+                    crate::notify_window_visibility(app, true);
                 }
             }
             MENU_HIDE => {
@@ -106,12 +109,8 @@ pub fn show_native_context_menu(app: &tauri::AppHandle, x: i32, y: i32) -> Resul
                 if let Some(window) = app.get_webview_window("main") {
                     let is_visible = window.is_visible().unwrap_or(false);
 
-                    match crate::perform_pin_toggle(&window) {
+                    match crate::perform_pin_toggle(app, &window) {
                         Ok(new_pin_state) => {
-                            if let Err(e) = app.emit("window-pin-changed", new_pin_state) {
-                                tracing::error!("[Menu] Failed to emit pin state event: {}", e);
-                            }
-
                             if !is_visible {
                                 tracing::info!("[Menu] Window shown and pinned on top");
                             } else if new_pin_state {
@@ -127,7 +126,7 @@ pub fn show_native_context_menu(app: &tauri::AppHandle, x: i32, y: i32) -> Resul
                 }
             }
             MENU_QUIT => {
-                std::process::exit(0);
+                crate::shutdown_and_exit(app);
             }
             _ => {}
         }
@@ -0,0 +1,502 @@
+//! Settings
+//!
+//! User-configurable settings persisted as JSON alongside the executable.
+//! Kept intentionally small; new settings should have sane defaults so
+//! existing installs upgrade without a migration step.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Serialize, Deserialize};
+
+use crate::audio_management::SortMode;
+use crate::hardware_input::AxisCurve;
+use crate::window_utils::{AnchorCorner, CloseAction, PositionMode};
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Constants
+// ─────────────────────────────────────────────────────────────────────────────
+
+const SETTINGS_FILE_NAME: &str = "clearcomms_settings.json";
+
+/// Where an unparsable settings file gets copied before falling back to defaults - see
+/// `Settings::load`. Sits next to the real file so it survives the defaults-save that follows.
+const SETTINGS_CORRUPT_FILE_NAME: &str = "clearcomms_settings.corrupt.json";
+
+/// Default padding from the screen edge in pixels.
+const DEFAULT_WINDOW_PADDING: i32 = 18;
+
+/// Default estimated Windows taskbar height in pixels, used only as a fallback
+/// when the monitor work area can't be determined.
+const DEFAULT_TASKBAR_OFFSET: i32 = 72;
+
+fn default_window_padding() -> i32 {
+    DEFAULT_WINDOW_PADDING
+}
+
+fn default_taskbar_offset() -> i32 {
+    DEFAULT_TASKBAR_OFFSET
+}
+
+fn default_window_opacity() -> f32 {
+    1.0
+}
+
+fn default_tray_tooltip_template() -> String {
+    "ClearComms | {apps} apps | {controllers} controllers | {profile} | {sim}".to_string()
+}
+
+fn default_tray_tooltip_interval_ms() -> u64 {
+    2000
+}
+
+fn default_layout_base_width() -> u32 {
+    250
+}
+
+fn default_layout_channel_width() -> u32 {
+    48
+}
+
+fn default_layout_min_width() -> u32 {
+    250
+}
+
+fn default_layout_max_width() -> u32 {
+    2000
+}
+
+/// Per-session "focus duck" configuration: the session to duck while the main
+/// window is shown, and back to restore once it's hidden again. See
+/// `set_focus_duck` in `main.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusDuckConfig {
+    pub session_id: String,
+    /// Volume (0.0-1.0) to ramp the session down to while the window is shown.
+    pub duck_to: f32,
+    /// Ramp duration in milliseconds, used for both the duck-down and restore ramps.
+    pub ms: u32,
+}
+
+/// One axis binding captured by a reusable `BindingTemplate`, keyed by the axis's
+/// user-assigned label (see `hardware_input::set_axis_label`) and the target session's
+/// process name rather than a concrete device/session - see
+/// `binding_poller::apply_binding_template`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateAxisBinding {
+    pub axis_label: String,
+    pub target_role: String,
+    pub inverted: bool,
+    pub curve: Option<crate::hardware_input::AxisCurve>,
+    pub range_min: Option<f32>,
+    pub range_max: Option<f32>,
+}
+
+/// A named, reusable set of axis-to-session bindings, saved via
+/// `binding_poller::save_binding_template` and replayed against a (possibly different)
+/// physical device and running sessions via `binding_poller::apply_binding_template`.
+/// Separate from any notion of per-aircraft profiles (bindings themselves aren't
+/// profile-scoped in this codebase) - a template is just a portable shape one or more
+/// profiles could apply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingTemplate {
+    pub bindings: Vec<TemplateAxisBinding>,
+}
+
+/// A session pin's target, set via `audio_management::pin_session_to_device`. Carries the
+/// device's friendly name alongside its id so `audio_management::reattach_pinned_sessions`
+/// can still find a replugged device by name once USB re-enumeration has changed its endpoint
+/// id, rather than leaving the pin permanently dangling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedDevice {
+    pub device_id: String,
+    pub device_name: String,
+}
+
+/// User-configurable settings, persisted to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    /// Padding from the screen edge in pixels, applied when positioning the window.
+    #[serde(default = "default_window_padding")]
+    pub window_padding: i32,
+    /// Manual fallback taskbar offset in pixels, used only when the monitor work
+    /// area can't be determined (older Windows versions, multi-monitor edge cases).
+    #[serde(default = "default_taskbar_offset")]
+    pub manual_taskbar_offset: i32,
+    /// When enabled, the window stays always-on-top without stealing focus and the
+    /// focus-loss auto-hide is disabled, so it behaves as a persistent compact widget.
+    #[serde(default)]
+    pub widget_mode: bool,
+    /// Saved screen position for widget mode, in physical pixels. Falls back to the
+    /// normal bottom-right corner when unset.
+    #[serde(default)]
+    pub widget_position: Option<(i32, i32)>,
+    /// How the channel strip orders sessions.
+    #[serde(default)]
+    pub sort_mode: SortMode,
+    /// User-defined process name order, used when `sort_mode` is `Manual`.
+    #[serde(default)]
+    pub manual_session_order: Vec<String>,
+    /// When enabled, an explicit "sim paused" show request (see
+    /// `show_main_window_for_sim_state` in `main.rs`) is allowed to bring the window
+    /// up on its own, independent of the normal tray/focus flow. There's no live
+    /// SimConnect/SimVar polling in this codebase yet, so today this only takes
+    /// effect when something external calls that command; it's the setting such
+    /// a poller would consult once added.
+    #[serde(default)]
+    pub show_on_pause: bool,
+    /// Process names of sessions ClearComms has muted and not yet unmuted, so a
+    /// crash or forced kill can still be recovered from on the next launch
+    /// without blindly unmuting sessions the user muted themselves.
+    #[serde(default)]
+    pub clearcomms_muted_processes: Vec<String>,
+    /// Which screen corner the window anchors to, updated by drag-and-snap
+    /// (see `window_utils::snap_to_nearest_corner`). Only consulted while
+    /// `position_mode` is `FixedCorner`.
+    #[serde(default)]
+    pub anchor_corner: AnchorCorner,
+    /// Whether the window anchors to a fixed corner or to wherever the tray icon was last
+    /// clicked, set via `window_utils::set_position_mode`.
+    #[serde(default)]
+    pub position_mode: PositionMode,
+    /// Volume taper new axis bindings inherit when not given an explicit curve of their own.
+    #[serde(default)]
+    pub default_curve: AxisCurve,
+    /// User-friendly axis names (e.g. "Throttle" for "Z Axis"), keyed first by a stable
+    /// per-device identity (see `hardware_input::device_identity_key`) so they follow a
+    /// device across reboots/enumeration-order changes, then by the device's raw axis name.
+    #[serde(default)]
+    pub axis_labels: HashMap<String, HashMap<String, String>>,
+    /// Axes flagged as continuously-wrapping rotary controls (set via calibration), keyed the
+    /// same way as `axis_labels`. Flagged axes get wrap-around correction in `read_all_axes`
+    /// instead of the normal absolute 0.0-1.0 reading.
+    #[serde(default)]
+    pub rotary_axes: HashMap<String, HashSet<String>>,
+    /// Seconds of inactivity (no show/focus command and no reported mouse interaction)
+    /// before the main window auto-hides itself, even while pinned. `None` disables the
+    /// behavior entirely, which is also the default so existing installs are unaffected.
+    #[serde(default)]
+    pub auto_hide_after_seconds: Option<u32>,
+    /// Session to duck on window show and restore on window hide (see `set_focus_duck`
+    /// in `main.rs`). `None` (the default) disables the behaviour entirely.
+    #[serde(default)]
+    pub focus_duck: Option<FocusDuckConfig>,
+    /// Auth token for the optional control server (see `control_server::set_control_server`),
+    /// generated once on first enable and kept stable across restarts so an external
+    /// controller's saved config doesn't need updating every launch.
+    #[serde(default)]
+    pub control_server_token: Option<String>,
+    /// Whether this is the first launch since install (or since `reset_first_run`) - drives
+    /// the onboarding auto-init/`"first-run"` event in `main.rs`'s `setup`. `#[serde(default)]`
+    /// (i.e. `false`) rather than a true-returning default fn: that default only kicks in when
+    /// an *existing* settings file predates this field, and an existing install is exactly the
+    /// case that should NOT be treated as first-run. A genuinely new install instead gets
+    /// `Settings::default()` wholesale (no file to parse at all), which sets this `true`.
+    #[serde(default)]
+    pub first_run: bool,
+    /// Window opacity (1.0 = fully opaque), set via `window_utils::set_window_opacity` for
+    /// an overlay setup where the sim needs to stay visible behind ClearComms.
+    #[serde(default = "default_window_opacity")]
+    pub window_opacity: f32,
+    /// Tags assigned via `audio_management::set_session_tag`, keyed by process name.
+    /// `activate_priority_mode` leaves tagged sessions alone and ducks/mutes everything else.
+    #[serde(default)]
+    pub session_tags: HashMap<String, String>,
+    /// Prior mute state of every session `activate_priority_mode` muted, keyed by process
+    /// name - `None` when priority mode isn't active. Recording the state it found rather
+    /// than assuming "was unmuted" means `deactivate_priority_mode` puts a session that was
+    /// already muted beforehand back to muted, not audible, the same "restore what was
+    /// actually there" approach `clearcomms_muted_processes` takes for crash recovery.
+    #[serde(default)]
+    pub priority_mode_prior_mutes: Option<HashMap<String, bool>>,
+    /// Whether the frontend's audio-peak polling loop keeps running while the main window
+    /// is hidden, set via `main::set_meter_while_hidden`. Defaults to `false` so idle
+    /// COM/CPU usage stays near zero while ClearComms is just sitting in the tray; a
+    /// feature that needs peaks while hidden (e.g. `focus_duck` ducking by peak rather
+    /// than a flat level) should turn this on instead of polling on its own.
+    #[serde(default)]
+    pub meter_while_hidden: bool,
+    /// Devices excluded from the background poller, keyed by the same stable per-device
+    /// identity as `axis_labels` (see `hardware_input::device_identity_key`), set via
+    /// `hardware_input::set_device_polling`. A device in here is skipped entirely during
+    /// `read_all_axes` - no `joyGetPosEx`/DirectInput read at all - rather than just having
+    /// its axes ignored downstream, since the point is avoiding the read itself (wasted work,
+    /// and some drivers wake hardware on poll).
+    #[serde(default)]
+    pub disabled_devices: HashSet<String>,
+    /// What the window's close button does, set via `window_utils::set_close_action`.
+    /// Defaults to `HideToTray`, the app's long-standing behaviour.
+    #[serde(default)]
+    pub close_action: CloseAction,
+    /// Reusable axis-to-session binding templates, keyed by name - see `BindingTemplate`.
+    #[serde(default)]
+    pub binding_templates: HashMap<String, BindingTemplate>,
+    /// Whether the main window was pinned (always-on-top) last, set via `main::perform_set_pin`
+    /// and re-applied in `setup` so a restart/crash-relaunch restores a persistent-overlay
+    /// user's expected layout without re-pinning every session.
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// Sessions pinned to a fixed output device, keyed by `process_name` - see `PinnedDevice`
+    /// and `audio_management::pin_session_to_device`. A pinned process's volume/mute
+    /// operations scope to the session instance on that device instead of every instance with
+    /// a matching `process_id`, so an app explicitly routed to a fixed endpoint doesn't go
+    /// dead - or start responding on the wrong device - after the system default changes.
+    #[serde(default)]
+    pub device_pins: HashMap<String, PinnedDevice>,
+    /// Template for the tray icon's tooltip text, set via `main::set_tray_tooltip_template`
+    /// and rendered by `main::format_tray_tooltip` on every refresh. Supports `{apps}`,
+    /// `{controllers}`, `{profile}` and `{sim}` placeholders - see `format_tray_tooltip` for
+    /// what each one resolves to.
+    #[serde(default = "default_tray_tooltip_template")]
+    pub tray_tooltip_template: String,
+    /// How often the tray tooltip refreshes itself from current state, in milliseconds - set
+    /// via `main::set_tray_tooltip_interval`. Defaults to 2000; a lower value costs a little
+    /// more idle CPU re-counting sessions/devices for no visible benefit since Windows doesn't
+    /// repaint an unfocused tooltip until it's next hovered anyway.
+    #[serde(default = "default_tray_tooltip_interval_ms")]
+    pub tray_tooltip_interval_ms: u64,
+    /// User overrides for `lvar_input`'s built-in aircraft LVar registry, keyed by the exact
+    /// `ATC MODEL`/title string and then by role (`"com1_volume"`, etc.) - set via
+    /// `lvar_input::set_aircraft_lvar_override`. Layered on top of
+    /// `BUILTIN_AIRCRAFT_LVAR_REGISTRY`'s substring-matched entries rather than replacing them,
+    /// so overriding one role doesn't require re-specifying every other role that registry
+    /// already got right.
+    #[serde(default)]
+    pub aircraft_lvar_overrides: HashMap<String, HashMap<String, String>>,
+    /// Each session's volume the first time ClearComms ever changed it, keyed by process
+    /// name - see `capture_original_volume`. Backs `audio_management::restore_windows_state`'s
+    /// "put everything back the way it was" behaviour, the volume counterpart to
+    /// `clearcomms_muted_processes` for mutes.
+    #[serde(default)]
+    pub original_session_volumes: HashMap<String, f32>,
+    /// Base window width for a single channel, in logical pixels - see `main::set_channel_layout`
+    /// and `main::calculate_window_width`. Seeds `LayoutMeasurements::base_width` on launch so
+    /// a user with a wider custom theme doesn't get reset to the stock layout every restart,
+    /// though the frontend's own DPI-measured `update_layout_measurements` call still overrides
+    /// it at runtime the same as before.
+    #[serde(default = "default_layout_base_width")]
+    pub layout_base_width: u32,
+    /// Per-channel width added for each additional session beyond the first, in logical
+    /// pixels - the `channel_width` half of `main::calculate_window_width`'s increment.
+    #[serde(default = "default_layout_channel_width")]
+    pub layout_channel_width: u32,
+    /// Floor on the calculated window width, in logical pixels - `calculate_window_width`
+    /// clamps to this even if a measured/configured `layout_base_width` would otherwise
+    /// produce something narrower.
+    #[serde(default = "default_layout_min_width")]
+    pub layout_min_width: u32,
+    /// Ceiling on the calculated window width, in logical pixels - keeps a very wide channel
+    /// theme with many sessions bound from growing the window past a sane screen fraction.
+    #[serde(default = "default_layout_max_width")]
+    pub layout_max_width: u32,
+    /// Hard per-session volume ceilings, keyed by process name - see
+    /// `audio_management::set_session_volume_cap`. Checked by `AudioManager::set_session_volume`
+    /// and by its process-tree/scale batch counterparts before any of them write a volume, so a
+    /// binding or scene can't push a capped channel (e.g. alarms, ATC) past its ceiling through
+    /// any path.
+    #[serde(default)]
+    pub session_volume_caps: HashMap<String, f32>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window_padding: DEFAULT_WINDOW_PADDING,
+            manual_taskbar_offset: DEFAULT_TASKBAR_OFFSET,
+            widget_mode: false,
+            widget_position: None,
+            sort_mode: SortMode::default(),
+            manual_session_order: Vec::new(),
+            show_on_pause: false,
+            clearcomms_muted_processes: Vec::new(),
+            anchor_corner: AnchorCorner::default(),
+            position_mode: PositionMode::default(),
+            default_curve: AxisCurve::default(),
+            axis_labels: HashMap::new(),
+            rotary_axes: HashMap::new(),
+            auto_hide_after_seconds: None,
+            focus_duck: None,
+            control_server_token: None,
+            window_opacity: default_window_opacity(),
+            first_run: true,
+            session_tags: HashMap::new(),
+            priority_mode_prior_mutes: None,
+            meter_while_hidden: false,
+            disabled_devices: HashSet::new(),
+            close_action: CloseAction::default(),
+            binding_templates: HashMap::new(),
+            always_on_top: false,
+            device_pins: HashMap::new(),
+            tray_tooltip_template: default_tray_tooltip_template(),
+            tray_tooltip_interval_ms: default_tray_tooltip_interval_ms(),
+            aircraft_lvar_overrides: HashMap::new(),
+            original_session_volumes: HashMap::new(),
+            layout_base_width: default_layout_base_width(),
+            layout_channel_width: default_layout_channel_width(),
+            layout_min_width: default_layout_min_width(),
+            layout_max_width: default_layout_max_width(),
+            session_volume_caps: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    fn file_path() -> Option<PathBuf> {
+        let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+        Some(exe_dir.join(SETTINGS_FILE_NAME))
+    }
+
+    fn load() -> Self {
+        let Some(path) = Self::file_path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!("[Settings] Failed to parse {}: {}. Backing up and using defaults.", path.display(), e);
+                Self::backup_corrupt_file(&path, &contents);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Preserve a settings file that failed to parse (partial write from a crash, a manual
+    /// edit gone wrong) alongside the real one, so it isn't silently lost to the defaults
+    /// `load` falls back to and the next `save` would otherwise overwrite for good.
+    fn backup_corrupt_file(path: &PathBuf, contents: &str) {
+        let Some(backup_path) = path.parent().map(|dir| dir.join(SETTINGS_CORRUPT_FILE_NAME)) else {
+            return;
+        };
+        if let Err(e) = fs::write(&backup_path, contents) {
+            tracing::warn!("[Settings] Failed to back up corrupt settings to {}: {}", backup_path.display(), e);
+        }
+    }
+
+    /// Write via a temp file plus rename rather than a direct write, so a crash or forced kill
+    /// mid-save can at worst leave a stray `.tmp` file behind - never a truncated/partial
+    /// `clearcomms_settings.json` that `load` would otherwise have to recover from.
+    fn save(&self) {
+        let Some(path) = Self::file_path() else {
+            tracing::warn!("[Settings] Could not determine settings file location, not saving");
+            return;
+        };
+
+        let json = match serde_json::to_string_pretty(self) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::warn!("[Settings] Failed to serialise settings: {}", e);
+                return;
+            }
+        };
+
+        let tmp_path = path.with_extension("json.tmp");
+        if let Err(e) = fs::write(&tmp_path, json) {
+            tracing::warn!("[Settings] Failed to write {}: {}", tmp_path.display(), e);
+            return;
+        }
+        if let Err(e) = fs::rename(&tmp_path, &path) {
+            tracing::warn!("[Settings] Failed to finalise {}: {}", path.display(), e);
+        }
+    }
+
+    /// Cross-check every settings-resident per-device/per-process association (axis labels,
+    /// rotary-axis flags, disabled devices, session tags, manual sort order, crash-recovery
+    /// mute tracking) against what's currently connected/running, so a config that's drifted
+    /// from reality (hardware unplugged, an app uninstalled) is reported rather than silently
+    /// carried forward - see `validate_settings` in `main.rs`, which gathers the "currently
+    /// connected/running" sets this needs. Axis/button bindings themselves live in the
+    /// frontend's own storage, not here - see the frontend's own `validateBindings`.
+    pub fn validate(&self, known_device_keys: &HashSet<String>, known_process_names: &HashSet<String>) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        for key in self.axis_labels.keys().chain(self.rotary_axes.keys()).chain(self.disabled_devices.iter()) {
+            if !known_device_keys.contains(key) {
+                issues.push(format!("No connected device matches identity \"{}\"", key));
+            }
+        }
+
+        for process_name in self.session_tags.keys()
+            .chain(self.manual_session_order.iter())
+            .chain(self.clearcomms_muted_processes.iter())
+            .chain(self.session_volume_caps.keys())
+        {
+            if !known_process_names.contains(process_name) {
+                issues.push(format!("No running session for \"{}\"", process_name));
+            }
+        }
+
+        issues.sort();
+        issues.dedup();
+        issues
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref SETTINGS: Mutex<Settings> = Mutex::new(Settings::load());
+}
+
+/// Get a clone of the current settings.
+pub fn get() -> Settings {
+    SETTINGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Update settings via the given closure, then persist the result to disk.
+pub fn update<F: FnOnce(&mut Settings)>(f: F) {
+    let mut guard = SETTINGS.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut guard);
+    guard.save();
+}
+
+/// Record that ClearComms itself muted `process_name`, so it can be recovered
+/// on the next launch even if this run crashes before unmuting it.
+pub fn mark_clearcomms_muted(process_name: &str) {
+    update(|s| {
+        if !s.clearcomms_muted_processes.iter().any(|p| p == process_name) {
+            s.clearcomms_muted_processes.push(process_name.to_string());
+        }
+    });
+}
+
+/// Clear the "ClearComms muted this" tracking for `process_name`, typically
+/// once it's been unmuted (by us or the user).
+pub fn unmark_clearcomms_muted(process_name: &str) {
+    update(|s| s.clearcomms_muted_processes.retain(|p| p != process_name));
+}
+
+/// Record `volume` as `process_name`'s pre-ClearComms volume, if nothing's been recorded for
+/// it yet. Only the first call for a given process name sticks - every later call during this
+/// install is assumed to be ClearComms' own change, not the user's original setting, so it
+/// must not overwrite the snapshot `restore_windows_state` needs to put back.
+pub fn capture_original_volume(process_name: &str, volume: f32) {
+    update(|s| {
+        s.original_session_volumes.entry(process_name.to_string()).or_insert(volume);
+    });
+}
+
+/// Drain and return every captured original volume, clearing the tracking set - called once by
+/// `audio_management::restore_windows_state` as it puts each one back.
+pub fn take_original_volumes() -> HashMap<String, f32> {
+    let mut taken = HashMap::new();
+    update(|s| taken = std::mem::take(&mut s.original_session_volumes));
+    taken
+}
+
+/// Set, update, or clear (`cap: None`) `process_name`'s hard volume ceiling - see
+/// `session_volume_caps`.
+pub fn set_session_volume_cap(process_name: &str, cap: Option<f32>) {
+    update(|s| match cap {
+        Some(cap) => { s.session_volume_caps.insert(process_name.to_string(), cap.clamp(0.0, 1.0)); }
+        None => { s.session_volume_caps.remove(process_name); }
+    });
+}
+
+/// `process_name`'s current volume ceiling, if any - consulted by `AudioManager::set_session_volume`
+/// and its process-tree/scale batch counterparts before every volume write.
+pub fn session_volume_cap(process_name: &str) -> Option<f32> {
+    get().session_volume_caps.get(process_name).copied()
+}
@@ -2,49 +2,450 @@
 //!
 //! Helper functions for positioning and managing the ClearComms window.
 
+use serde::{Serialize, Deserialize};
 use tauri::PhysicalPosition;
 
+use crate::settings;
+
+/// Which screen corner the window anchors to. Defaults to `BottomRight` (the
+/// app's original fixed position) and otherwise only changes via drag-and-snap
+/// (see `main.rs`'s `Moved` handling).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnchorCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Default for AnchorCorner {
+    fn default() -> Self {
+        AnchorCorner::BottomRight
+    }
+}
+
+/// How the window is placed when shown. `FixedCorner` (the default) uses `anchor_corner`;
+/// `TrayRelative` instead places it next to wherever the tray icon was clicked, clamped to
+/// that click's monitor - see `position_window_near_point`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionMode {
+    FixedCorner,
+    TrayRelative,
+}
+
+impl Default for PositionMode {
+    fn default() -> Self {
+        PositionMode::FixedCorner
+    }
+}
+
+/// What the window's close button ("X") does, consulted by `main.rs`'s `CloseRequested`
+/// handler. `HideToTray` (the default) keeps the long-standing behaviour of hiding rather
+/// than exiting, with quitting only reachable via the tray/menu; `Quit` is for users who
+/// expect the X to actually close the app; `Ask` defers the decision to the frontend instead
+/// of picking one, via the `close-requested` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloseAction {
+    HideToTray,
+    Quit,
+    Ask,
+}
+
+impl Default for CloseAction {
+    fn default() -> Self {
+        CloseAction::HideToTray
+    }
+}
+
+/// Distance in pixels from a work-area edge, measured at drag-release, within
+/// which the window snaps flush with that edge instead of staying put.
+pub const SNAP_THRESHOLD_PX: i32 = 48;
+
 // ─────────────────────────────────────────────────────────────────────────────
-// Constants
+// Monitor Work Area
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Padding from screen edge in pixels
-const WINDOW_PADDING: i32 = 18;
+/// Get the monitor work area (screen bounds minus the taskbar) for the monitor
+/// the window is currently on, if Windows can report it. This makes the manual
+/// taskbar offset setting unnecessary whenever it's available.
+#[cfg(windows)]
+fn monitor_work_area(window: &tauri::WebviewWindow) -> Option<(i32, i32, i32, i32)> {
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+
+    let hwnd = window.hwnd().ok()?;
+
+    unsafe {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+
+        if GetMonitorInfoW(monitor, &mut info).as_bool() {
+            let rc = info.rcWork;
+            Some((rc.left, rc.top, rc.right, rc.bottom))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn monitor_work_area(_window: &tauri::WebviewWindow) -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+/// Same as `monitor_work_area`, but for the monitor containing an arbitrary screen point
+/// rather than the one a window currently sits on - what `position_window_near_point` needs
+/// to clamp against when the point (a tray icon click) isn't on the window's own monitor.
+#[cfg(windows)]
+fn monitor_work_area_at_point(x: i32, y: i32) -> Option<(i32, i32, i32, i32)> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+
+    unsafe {
+        let monitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST);
+
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+
+        if GetMonitorInfoW(monitor, &mut info).as_bool() {
+            let rc = info.rcWork;
+            Some((rc.left, rc.top, rc.right, rc.bottom))
+        } else {
+            None
+        }
+    }
+}
 
-/// Estimated Windows taskbar height in pixels (for 150% scaling on 4K displays)
-/// This accounts for the taskbar so the window doesn't overlap it.
-const TASKBAR_HEIGHT: i32 = 72;
+#[cfg(not(windows))]
+fn monitor_work_area_at_point(_x: i32, _y: i32) -> Option<(i32, i32, i32, i32)> {
+    None
+}
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Window Positioning
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Position the window in the bottom-right corner of the primary monitor.
+/// Position the window flush with its saved anchor corner (`settings.anchor_corner`,
+/// bottom-right by default) on the current monitor.
 ///
-/// This places the window above the Windows taskbar with appropriate padding,
+/// Prefers the monitor's work area (screen bounds minus the taskbar), which
+/// makes the manual taskbar offset setting unnecessary. Falls back to the
+/// full monitor size plus the configured `manual_taskbar_offset` when the
+/// work area can't be determined.
 ///
 /// # Arguments
 /// * `window` - The Tauri webview window to position
 ///
 /// # Notes
-/// - Uses the primary monitor for positioning
-/// - Accounts for taskbar height and screen edge padding
+/// - Accounts for screen edge padding (`window_padding` setting)
 /// - Silently fails if monitor or window size cannot be determined
 pub fn position_window_bottom_right(window: &tauri::WebviewWindow) {
+    let Ok(window_size) = window.outer_size() else {
+        return;
+    };
+
+    let Some((x, y)) = anchored_position(window, window_size.width as i32, window_size.height as i32) else {
+        return;
+    };
+
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+}
+
+/// Compute where the window should sit for its saved anchor corner at a given `width`/
+/// `height`, without reading the window's current size - lets `set_bounds_anchored` compute
+/// the final position for a resize ahead of time, instead of positioning against the size
+/// the window had a moment ago. `position_window_bottom_right` is this plus the window's
+/// actual current size, for callers that aren't also changing it.
+fn anchored_position(window: &tauri::WebviewWindow, width: i32, height: i32) -> Option<(i32, i32)> {
+    let config = settings::get();
+
+    let is_left = matches!(config.anchor_corner, AnchorCorner::TopLeft | AnchorCorner::BottomLeft);
+    let is_top = matches!(config.anchor_corner, AnchorCorner::TopLeft | AnchorCorner::TopRight);
+
+    if let Some((left, top, right, bottom)) = monitor_work_area(window) {
+        let x = if is_left { left + config.window_padding } else { right - width - config.window_padding };
+        let y = if is_top { top + config.window_padding } else { bottom - height - config.window_padding };
+
+        return Some((x.max(left), y.max(top)));
+    }
+
     if let Ok(Some(monitor)) = window.primary_monitor() {
-        if let Ok(window_size) = window.outer_size() {
-            let screen_size = monitor.size();
-            
-            let screen_width = screen_size.width as i32;
-            let screen_height = screen_size.height as i32;
-            let window_width = window_size.width as i32;
-            let window_height = window_size.height as i32;
-            
-            let x = screen_width - window_width - WINDOW_PADDING;
-            let y = screen_height - window_height - TASKBAR_HEIGHT - WINDOW_PADDING;
-            
-            let position = PhysicalPosition::new(x, y);
-            let _ = window.set_position(position);
+        let screen_size = monitor.size();
+
+        let screen_width = screen_size.width as i32;
+        let screen_height = screen_size.height as i32;
+
+        let x = if is_left {
+            config.window_padding
+        } else {
+            screen_width - width - config.window_padding
+        };
+        let y = if is_top {
+            config.window_padding
+        } else {
+            screen_height - height - config.manual_taskbar_offset - config.window_padding
+        };
+
+        return Some((x, y));
+    }
+
+    None
+}
+
+/// Position the window next to a screen point - the tray icon's click location - instead
+/// of a fixed corner, for `PositionMode::TrayRelative`. Clamped to the work area of whichever
+/// monitor contains that point, so a click on a secondary monitor doesn't open the window on
+/// the primary one. Opens above-left of the point by default, matching where a Windows tray
+/// flyout normally appears relative to a taskbar that usually sits bottom-right, then nudges
+/// back inside the work area on whichever edge it would otherwise overflow.
+pub fn position_window_near_point(window: &tauri::WebviewWindow, point_x: i32, point_y: i32) {
+    let Ok(window_size) = window.outer_size() else { return };
+    let width = window_size.width as i32;
+    let height = window_size.height as i32;
+
+    let Some((left, top, right, bottom)) = monitor_work_area_at_point(point_x, point_y) else { return };
+
+    let padding = settings::get().window_padding;
+
+    let x = (point_x - width - padding).clamp(left, (right - width).max(left));
+    let y = (point_y - height - padding).clamp(top, (bottom - height).max(top));
+
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+}
+
+/// Move and resize the window in a single operation instead of a separate `set_size`+
+/// `set_position` pair, so a resize-on-session-change doesn't visibly flash at the old
+/// position with the new size for a frame before snapping to the right spot - see
+/// `animate_window_resize` in `main.rs`. Deliberately doesn't hide/show around the change:
+/// once the move and resize happen together there's nothing left to mask, and a hide/show
+/// would introduce its own flicker on every frame of the resize animation.
+#[cfg(windows)]
+pub fn set_bounds_anchored(window: &tauri::WebviewWindow, width: i32, height: i32) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{SetWindowPos, SWP_NOACTIVATE, SWP_NOZORDER};
+
+    let Some((x, y)) = anchored_position(window, width, height) else { return };
+    let Ok(hwnd) = window.hwnd() else { return };
+
+    unsafe {
+        let _ = SetWindowPos(hwnd, HWND::default(), x, y, width, height, SWP_NOACTIVATE | SWP_NOZORDER);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_bounds_anchored(window: &tauri::WebviewWindow, width: i32, height: i32) {
+    let _ = window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
+        width: width.max(0) as u32,
+        height: height.max(0) as u32,
+    }));
+    position_window_bottom_right(window);
+}
+
+/// Called from `main.rs`'s debounced `Moved` handler once a drag settles. If the
+/// window was released within `SNAP_THRESHOLD_PX` of a work-area edge on both
+/// axes, snaps it flush with the nearest corner and persists that as the new
+/// `anchor_corner` so future shows (tray click, pin, widget mode) anchor there
+/// too. Otherwise leaves the window exactly where it was dropped.
+pub fn snap_to_nearest_corner(window: &tauri::WebviewWindow) {
+    let Ok(position) = window.outer_position() else { return };
+    let Ok(size) = window.outer_size() else { return };
+    let Some((left, top, right, bottom)) = monitor_work_area(window) else { return };
+
+    let window_left = position.x;
+    let window_top = position.y;
+    let window_right = position.x + size.width as i32;
+    let window_bottom = position.y + size.height as i32;
+
+    let dist_left = (window_left - left).abs();
+    let dist_right = (right - window_right).abs();
+    let dist_top = (window_top - top).abs();
+    let dist_bottom = (bottom - window_bottom).abs();
+
+    let near_left = dist_left <= SNAP_THRESHOLD_PX;
+    let near_right = dist_right <= SNAP_THRESHOLD_PX;
+    let near_top = dist_top <= SNAP_THRESHOLD_PX;
+    let near_bottom = dist_bottom <= SNAP_THRESHOLD_PX;
+
+    if !(near_left || near_right) || !(near_top || near_bottom) {
+        return;
+    }
+
+    // Prefer whichever horizontal/vertical edge is actually closest if both
+    // register as "near" (a window nearly as wide/tall as the work area).
+    let is_left = near_left && (!near_right || dist_left <= dist_right);
+    let is_top = near_top && (!near_bottom || dist_top <= dist_bottom);
+
+    let corner = match (is_left, is_top) {
+        (true, true) => AnchorCorner::TopLeft,
+        (false, true) => AnchorCorner::TopRight,
+        (true, false) => AnchorCorner::BottomLeft,
+        (false, false) => AnchorCorner::BottomRight,
+    };
+
+    settings::update(|s| s.anchor_corner = corner);
+    position_window_bottom_right(window);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Foreground Full-Screen Detection
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Whether the current foreground window looks like a full-screen exclusive app (a flight
+/// sim, a game) rather than an ordinary windowed one - used to decide whether showing our
+/// window should steal focus or not. Heuristic: the foreground window isn't ours and its
+/// window rect exactly fills the monitor it's on, which a maximized window doesn't do (the
+/// taskbar still claims its strip), but borderless/exclusive full-screen does.
+#[cfg(windows)]
+pub fn foreground_window_is_fullscreen(window: &tauri::WebviewWindow) -> bool {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if foreground.is_invalid() {
+            return false;
+        }
+        if let Ok(own_hwnd) = window.hwnd() {
+            if foreground == own_hwnd {
+                return false;
+            }
+        }
+
+        let mut window_rect: RECT = std::mem::zeroed();
+        if GetWindowRect(foreground, &mut window_rect).is_err() {
+            return false;
+        }
+
+        let monitor = MonitorFromWindow(foreground, MONITOR_DEFAULTTONEAREST);
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+
+        let monitor_rect = info.rcMonitor;
+        window_rect.left <= monitor_rect.left
+            && window_rect.top <= monitor_rect.top
+            && window_rect.right >= monitor_rect.right
+            && window_rect.bottom >= monitor_rect.bottom
+    }
+}
+
+#[cfg(not(windows))]
+pub fn foreground_window_is_fullscreen(_window: &tauri::WebviewWindow) -> bool {
+    false
+}
+
+/// Show the window without activating it (`SW_SHOWNOACTIVATE`), so it doesn't steal focus
+/// from - and potentially minimize - whatever full-screen app is currently foreground.
+#[cfg(windows)]
+pub fn show_without_activating(window: &tauri::WebviewWindow) {
+    use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SW_SHOWNOACTIVATE};
+
+    if let Ok(hwnd) = window.hwnd() {
+        unsafe {
+            let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
         }
     }
 }
+
+#[cfg(not(windows))]
+pub fn show_without_activating(window: &tauri::WebviewWindow) {
+    let _ = window.show();
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Opacity
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Floor for `set_window_opacity` - low enough for a barely-visible overlay, but never so low
+/// the window becomes fully invisible and unrecoverable (there's no other UI to bring it back
+/// up once you can't see it to click anything in it).
+const MIN_WINDOW_OPACITY: f32 = 0.15;
+
+/// Apply `opacity` (1.0 = fully opaque) to the window via `WS_EX_LAYERED` +
+/// `SetLayeredWindowAttributes`, rather than Tauri's own `set_opacity` wrapper - that wrapper
+/// sets the same Win32 attribute but swallows errors into `()`, and call sites here already
+/// follow the `let Ok(hwnd) = window.hwnd()` idiom used by the rest of this module.
+#[cfg(windows)]
+pub fn apply_window_opacity(window: &tauri::WebviewWindow, opacity: f32) {
+    use windows::Win32::Foundation::COLORREF;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+        LWA_ALPHA, WS_EX_LAYERED,
+    };
+
+    let Ok(hwnd) = window.hwnd() else { return };
+    let alpha = (opacity.clamp(MIN_WINDOW_OPACITY, 1.0) * 255.0).round() as u8;
+
+    unsafe {
+        let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style | WS_EX_LAYERED.0 as isize);
+        let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn apply_window_opacity(_window: &tauri::WebviewWindow, _opacity: f32) {}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Settings Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Update the window padding setting and reposition the window immediately,
+/// for quick experimentation without restarting the app.
+#[tauri::command]
+pub fn set_window_padding(window: tauri::WebviewWindow, px: i32) -> Result<(), String> {
+    settings::update(|s| s.window_padding = px);
+    position_window_bottom_right(&window);
+    Ok(())
+}
+
+/// Switch between anchoring the window to a fixed corner and anchoring it to wherever the
+/// tray icon was last clicked - see `PositionMode` and `position_window_near_point`. Doesn't
+/// reposition immediately; the new mode takes effect on the next show, same as
+/// `anchor_corner` changing only taking effect on the next `position_window_bottom_right`.
+#[tauri::command]
+pub fn set_position_mode(mode: PositionMode) -> Result<(), String> {
+    settings::update(|s| s.position_mode = mode);
+    Ok(())
+}
+
+/// Current window positioning mode - see `set_position_mode`.
+#[tauri::command]
+pub fn get_position_mode() -> Result<PositionMode, String> {
+    Ok(settings::get().position_mode)
+}
+
+/// Set what the window's close button does - see `CloseAction`.
+#[tauri::command]
+pub fn set_close_action(action: CloseAction) -> Result<(), String> {
+    settings::update(|s| s.close_action = action);
+    Ok(())
+}
+
+/// Current close-button behaviour - see `set_close_action`.
+#[tauri::command]
+pub fn get_close_action() -> Result<CloseAction, String> {
+    Ok(settings::get().close_action)
+}
+
+/// Set the main window's opacity, clamped to `MIN_WINDOW_OPACITY` so it can never become
+/// fully invisible. Persisted, and re-applied on every subsequent show so it survives
+/// whatever the acrylic/rounded-corner setup in `main.rs`'s `setup` does to the window's
+/// extended style.
+#[tauri::command]
+pub fn set_window_opacity(window: tauri::WebviewWindow, opacity: f32) -> Result<(), String> {
+    let opacity = opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+    settings::update(|s| s.window_opacity = opacity);
+    apply_window_opacity(&window, opacity);
+    Ok(())
+}
+
+/// Current window opacity - see `set_window_opacity`.
+#[tauri::command]
+pub fn get_window_opacity() -> Result<f32, String> {
+    Ok(settings::get().window_opacity)
+}